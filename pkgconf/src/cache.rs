@@ -0,0 +1,196 @@
+//! On-disk cache for [`PkgConfig`] probe results.
+//!
+//! The cache file is a simple line-based text format:
+//!
+//! ```text
+//! key <hex hash of the probe inputs>
+//! file <mtime in ns> <path>
+//! pc <path>
+//! search <path>
+//! lib <default|static|whole-archive> <name>
+//! warning <message>
+//! ...
+//! ```
+//!
+//! A cached result is reused only if the key matches and every recorded
+//! file still has the same modification time.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::{CompilerFlag, DecisionReason, LinkKind, LinkerFlag, PkgConfig};
+
+/// Name of the cache file inside the cache directory.
+pub(crate) const CACHE_FILE: &str = "pkgconf-probe.cache";
+
+/// Returns the modification time of `path` in nanoseconds since the epoch.
+pub(crate) fn mtime(path: &Path) -> Option<u128> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_nanos())
+}
+
+/// Reads a cached result, returning `None` on any mismatch or parse error.
+pub(crate) fn read(cache_dir: &Path, key: u64) -> Option<PkgConfig> {
+    let contents = fs::read_to_string(cache_dir.join(CACHE_FILE)).ok()?;
+    let mut lines = contents.lines();
+
+    let stored_key = lines.next()?.strip_prefix("key ")?;
+    if u64::from_str_radix(stored_key, 16).ok()? != key {
+        return None;
+    }
+
+    let mut libs = Vec::new();
+    let mut cflags = Vec::new();
+    let mut pc_files = Vec::new();
+    let mut decisions = Vec::new();
+    let mut warnings = Vec::new();
+
+    for line in lines {
+        let (tag, rest) = line.split_once(' ')?;
+        match tag {
+            "file" => {
+                let (stamp, path) = rest.split_once(' ')?;
+                if mtime(Path::new(path))? != stamp.parse::<u128>().ok()? {
+                    return None;
+                }
+            }
+            "pc" => pc_files.push(PathBuf::from(rest)),
+            "search" => libs.push(LinkerFlag::SearchPath(rest.to_string())),
+            "lib" => {
+                let (kind, name) = rest.split_once(' ')?;
+                libs.push(LinkerFlag::Library {
+                    name: name.to_string(),
+                    kind: parse_kind(kind)?,
+                });
+            }
+            "decision" => {
+                let (kind, rest) = rest.split_once(' ')?;
+                let (reason, name) = rest.split_once(' ')?;
+                decisions.push((
+                    name.to_string(),
+                    parse_kind(kind)?,
+                    DecisionReason::from_str(reason)?,
+                ));
+            }
+            "warning" => warnings.push(rest.to_string()),
+            "arg" => libs.push(LinkerFlag::LinkerArg(rest.to_string())),
+            "framework" => libs.push(LinkerFlag::Framework(rest.to_string())),
+            "framework-search" => libs.push(LinkerFlag::FrameworkSearchPath(rest.to_string())),
+            "verbatim" => libs.push(LinkerFlag::VerbatimLibrary(rest.to_string())),
+            "include" => cflags.push(CompilerFlag::IncludePath(PathBuf::from(rest))),
+            "isystem" => cflags.push(CompilerFlag::SystemIncludePath(PathBuf::from(rest))),
+            "iquote" => cflags.push(CompilerFlag::QuoteIncludePath(PathBuf::from(rest))),
+            "forced-include" => cflags.push(CompilerFlag::ForcedInclude(PathBuf::from(rest))),
+            "undefine" => cflags.push(CompilerFlag::Undefine(rest.to_string())),
+            "cflag" if rest == "-pthread" => cflags.push(CompilerFlag::Pthread),
+            "cflag" => cflags.push(CompilerFlag::Codegen(rest.to_string())),
+            "define" => cflags.push(match rest.split_once('=') {
+                Some((key, value)) => CompilerFlag::Define {
+                    key: key.to_string(),
+                    value: Some(value.to_string()),
+                },
+                None => CompilerFlag::Define {
+                    key: rest.to_string(),
+                    value: None,
+                },
+            }),
+            _ => return None,
+        }
+    }
+
+    Some(PkgConfig {
+        libs,
+        cflags,
+        pc_files,
+        tool: None,
+        decisions,
+        warnings,
+    })
+}
+
+fn parse_kind(kind: &str) -> Option<LinkKind> {
+    match kind {
+        "default" => Some(LinkKind::Default),
+        "static" => Some(LinkKind::Static),
+        "whole-archive" => Some(LinkKind::WholeArchive),
+        _ => None,
+    }
+}
+
+fn kind_str(kind: LinkKind) -> &'static str {
+    match kind {
+        LinkKind::Default => "default",
+        LinkKind::Static => "static",
+        LinkKind::WholeArchive => "whole-archive",
+    }
+}
+
+/// Writes a probe result to the cache, recording the mtimes of `files`.
+pub(crate) fn write(
+    cache_dir: &Path,
+    key: u64,
+    files: &[PathBuf],
+    pkg: &PkgConfig,
+) -> std::io::Result<()> {
+    let mut out = format!("key {:016x}\n", key);
+
+    for file in files {
+        if let Some(stamp) = mtime(file) {
+            out.push_str(&format!("file {} {}\n", stamp, file.display()));
+        }
+    }
+
+    for pc_file in &pkg.pc_files {
+        out.push_str(&format!("pc {}\n", pc_file.display()));
+    }
+
+    for flag in &pkg.libs {
+        let line = match flag {
+            LinkerFlag::SearchPath(path) => format!("search {}", path),
+            LinkerFlag::Library { name, kind } => format!("lib {} {}", kind_str(*kind), name),
+            LinkerFlag::LinkerArg(arg) => format!("arg {}", arg),
+            LinkerFlag::Framework(name) => format!("framework {}", name),
+            LinkerFlag::FrameworkSearchPath(path) => format!("framework-search {}", path),
+            LinkerFlag::VerbatimLibrary(file) => format!("verbatim {}", file),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    for (name, kind, reason) in &pkg.decisions {
+        out.push_str(&format!(
+            "decision {} {} {}\n",
+            kind_str(*kind),
+            reason.as_str(),
+            name
+        ));
+    }
+
+    for warning in &pkg.warnings {
+        out.push_str(&format!("warning {}\n", warning));
+    }
+
+    for flag in &pkg.cflags {
+        let line = match flag {
+            CompilerFlag::IncludePath(path) => format!("include {}", path.display()),
+            CompilerFlag::Define { key, value: None } => format!("define {}", key),
+            CompilerFlag::Define {
+                key,
+                value: Some(v),
+            } => format!("define {}={}", key, v),
+            CompilerFlag::Undefine(key) => format!("undefine {}", key),
+            CompilerFlag::SystemIncludePath(path) => format!("isystem {}", path.display()),
+            CompilerFlag::QuoteIncludePath(path) => format!("iquote {}", path.display()),
+            CompilerFlag::ForcedInclude(path) => format!("forced-include {}", path.display()),
+            CompilerFlag::Pthread | CompilerFlag::Codegen(_) => {
+                format!("cflag {}", flag.to_clang_arg())
+            }
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    fs::create_dir_all(cache_dir)?;
+    fs::write(cache_dir.join(CACHE_FILE), out)
+}
@@ -0,0 +1,313 @@
+//! Error type for [`PkgConfigParser::probe`](crate::PkgConfigParser::probe),
+//! [`PkgConfigParser::probe_lib_dirs`](crate::PkgConfigParser::probe_lib_dirs),
+//! [`PkgConfigParser::check_tool`](crate::PkgConfigParser::check_tool),
+//! [`PkgConfigParser::try_parse`](crate::PkgConfigParser::try_parse),
+//! [`PkgConfigParser::variable`](crate::PkgConfigParser::variable),
+//! [`PkgConfigParser::probe_from_files`](crate::PkgConfigParser::probe_from_files) and the
+//! [`run_pkg_config`](crate::PkgConfigParser::run_pkg_config) helpers.
+
+use std::fmt;
+use std::process::ExitStatus;
+
+/// Error type of this crate; the same type as [`ProbeError`].
+pub type Error = ProbeError;
+
+/// Why [`PkgConfigParser::probe`](crate::PkgConfigParser::probe) failed.
+///
+/// When pkg-config rejects the combined query, each package is re-probed on
+/// its own so the error names exactly which ones are missing. The
+/// [`Display`](fmt::Display) output is meant to be shown as-is from a build
+/// script; `String::from(err)` gives the same text for callers that still
+/// expect `Result<_, String>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeError {
+    /// pkg-config itself could not be run.
+    Spawn(String),
+
+    /// The pkg-config executable was not found.
+    ToolNotFound {
+        /// The executable that was looked for (`pkg-config`, `$PKG_CONFIG`
+        /// or the one set with
+        /// [`pkg_config_exe`](crate::PkgConfigParser::pkg_config_exe)).
+        program: String,
+    },
+
+    /// pkg-config is older than
+    /// [`min_tool_version`](crate::PkgConfigParser::min_tool_version).
+    ToolTooOld {
+        /// The version it reported.
+        version: String,
+        /// The minimum version.
+        required: String,
+    },
+
+    /// A package is older than
+    /// [`atleast_version`](crate::PkgConfigParser::atleast_version).
+    PackageTooOld {
+        /// The package.
+        package: String,
+        /// The version found (`--modversion`).
+        version: String,
+        /// The minimum version.
+        required: String,
+    },
+
+    /// One or more packages (or their `Requires`) were not found.
+    MissingPackages {
+        /// Each missing package with pkg-config's `--print-errors` output.
+        missing: Vec<(String, String)>,
+        /// Packages that were found.
+        present: Vec<String>,
+        /// The `PKG_CONFIG_PATH` that was searched, if any.
+        pkg_config_path: Option<String>,
+    },
+
+    /// A single query named a package (or one of its `Requires`) that
+    /// pkg-config could not find.
+    ///
+    /// Returned by the [`run_pkg_config`](crate::PkgConfigParser::run_pkg_config)
+    /// helpers; [`probe`](crate::PkgConfigParser::probe) reports
+    /// [`MissingPackages`](Self::MissingPackages) instead.
+    PackageNotFound {
+        /// The package pkg-config reported as not found.
+        package: String,
+        /// pkg-config's error output.
+        stderr: String,
+    },
+
+    /// pkg-config exited unsuccessfully for another reason (e.g. a version
+    /// constraint that isn't met).
+    ExitFailure {
+        /// pkg-config's exit status.
+        status: ExitStatus,
+        /// pkg-config's error output.
+        stderr: String,
+    },
+
+    /// pkg-config's output was not valid UTF-8.
+    InvalidOutput(String),
+
+    /// [`PkgConfigParser::variable`](crate::PkgConfigParser::variable)
+    /// asked for a variable the package does not define (or defines as
+    /// empty).
+    UndefinedVariable {
+        /// The package that was queried.
+        package: String,
+        /// The variable name.
+        variable: String,
+    },
+
+    /// Recorded pkg-config output given to
+    /// [`PkgConfigParser::probe_from_files`](crate::PkgConfigParser::probe_from_files)
+    /// could not be read.
+    ReadOutput {
+        /// The file that was read.
+        path: std::path::PathBuf,
+        /// The I/O error.
+        message: String,
+    },
+
+    /// pkg-config failed although every package exists on its own (e.g. a
+    /// version conflict between them). Holds pkg-config's error message.
+    Failed(String),
+
+    /// Libraries given to
+    /// [`PkgConfigParser::probe_lib_dirs`](crate::PkgConfigParser::probe_lib_dirs)
+    /// have no library file in its directories.
+    MissingArchives {
+        /// The missing libraries, without `lib` prefix or suffix.
+        missing: Vec<String>,
+        /// The directories that were searched.
+        searched: Vec<std::path::PathBuf>,
+    },
+
+    /// A library inside a `-Wl,-Bstatic` region has no `lib<name>.a` in any
+    /// search directory.
+    StaticArchiveNotFound {
+        /// The library name, without `lib` prefix or suffix.
+        library: String,
+        /// The directories that were searched.
+        searched: Vec<std::path::PathBuf>,
+    },
+}
+
+impl ProbeError {
+    /// Names of the missing packages, empty unless
+    /// [`MissingPackages`](Self::MissingPackages) or
+    /// [`PackageNotFound`](Self::PackageNotFound).
+    ///
+    /// A build script can use this to fall back to a vendored build only
+    /// when packages are missing, not when pkg-config itself is broken.
+    pub fn missing_packages(&self) -> Vec<&str> {
+        match self {
+            ProbeError::MissingPackages { missing, .. } => {
+                missing.iter().map(|(name, _)| name.as_str()).collect()
+            }
+            ProbeError::PackageNotFound { package, .. } => vec![package.as_str()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Finds the package pkg-config reports as missing in `stderr`.
+///
+/// Recognizes both `Package foo was not found in the pkg-config search
+/// path.` (pkg-config) and `Package 'foo', required by 'bar', not found`
+/// (pkgconf).
+pub(crate) fn parse_package_not_found(stderr: &str) -> Option<String> {
+    stderr.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("Package ")?;
+        let package = match rest.strip_prefix('\'') {
+            Some(quoted) => {
+                let (package, tail) = quoted.split_once('\'')?;
+                tail.ends_with("not found").then_some(package)?
+            }
+            None => {
+                rest.split_once(' ')
+                    .filter(|(_, tail)| tail.starts_with("was not found"))?
+                    .0
+            }
+        };
+        Some(package.to_string())
+    })
+}
+
+/// SPDK `configure` flags that enable optional modules, by package prefix.
+const CONFIGURE_HINTS: &[(&str, &str)] = &[
+    ("spdk_bdev_uring", "--with-uring"),
+    ("spdk_bdev_rbd", "--with-rbd"),
+    ("spdk_bdev_iscsi", "--with-iscsi-initiator"),
+    ("spdk_bdev_xnvme", "--with-xnvme"),
+    ("spdk_bdev_daos", "--with-daos"),
+    ("spdk_bdev_ocf", "--with-ocf"),
+    ("spdk_bdev_compress", "--with-vbdev-compress"),
+    ("spdk_bdev_crypto", "--with-crypto"),
+    ("spdk_accel_dpdk_cryptodev", "--with-crypto"),
+    ("spdk_accel_dpdk_compressdev", "--with-dpdk-compressdev"),
+    ("spdk_rdma", "--with-rdma"),
+    ("spdk_vfio_user", "--with-vfio-user"),
+    ("spdk_ublk", "--with-ublk"),
+    ("spdk_event_ublk", "--with-ublk"),
+    ("spdk_fuse_dispatcher", "--with-fuse"),
+    ("spdk_fsdev", "--with-fsdev"),
+];
+
+/// Returns the SPDK `configure` flag that builds `package`, if it is a
+/// known optional module.
+pub(crate) fn configure_hint(package: &str) -> Option<&'static str> {
+    CONFIGURE_HINTS
+        .iter()
+        .find(|(prefix, _)| package.starts_with(prefix))
+        .map(|(_, flag)| *flag)
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeError::Spawn(msg) => write!(f, "Failed to run pkg-config: {}", msg),
+            ProbeError::ToolNotFound { program } => write!(
+                f,
+                "{} not found in PATH (set PKG_CONFIG to override)",
+                program
+            ),
+            ProbeError::ToolTooOld { version, required } => write!(
+                f,
+                "pkg-config {} too old, need >= {} for --static ordering",
+                version, required
+            ),
+            ProbeError::PackageTooOld {
+                package,
+                version,
+                required,
+            } => write!(
+                f,
+                "{} {} is too old, need >= {}",
+                package, version, required
+            ),
+            ProbeError::MissingPackages {
+                missing,
+                present,
+                pkg_config_path,
+            } => {
+                write!(
+                    f,
+                    "pkg-config could not find {} of {} packages",
+                    missing.len(),
+                    missing.len() + present.len()
+                )?;
+                match pkg_config_path {
+                    Some(path) => writeln!(f, " (PKG_CONFIG_PATH={})", path)?,
+                    None => writeln!(f, " (PKG_CONFIG_PATH not set)")?,
+                }
+                for (name, errors) in missing {
+                    write!(f, "  missing: {}", name)?;
+                    if let Some(flag) = configure_hint(name) {
+                        write!(f, " (optional SPDK module, configure with {})", flag)?;
+                    }
+                    writeln!(f)?;
+                    for line in errors.lines() {
+                        writeln!(f, "    {}", line)?;
+                    }
+                }
+                if !present.is_empty() {
+                    write!(f, "  present: {}", present.join(", "))?;
+                }
+                Ok(())
+            }
+            ProbeError::PackageNotFound { package, stderr } => {
+                write!(f, "pkg-config could not find package {}", package)?;
+                if let Some(flag) = configure_hint(package) {
+                    write!(f, " (optional SPDK module, configure with {})", flag)?;
+                }
+                for line in stderr.lines() {
+                    write!(f, "\n    {}", line)?;
+                }
+                Ok(())
+            }
+            ProbeError::ExitFailure { status, stderr } => {
+                write!(f, "pkg-config failed ({}): {}", status, stderr.trim())
+            }
+            ProbeError::InvalidOutput(msg) => {
+                write!(f, "pkg-config output is not valid UTF-8: {}", msg)
+            }
+            ProbeError::UndefinedVariable { package, variable } => write!(
+                f,
+                "pkg-config variable {} is not defined for package {}",
+                variable, package
+            ),
+            ProbeError::ReadOutput { path, message } => write!(
+                f,
+                "Failed to read pkg-config output from {}: {}",
+                path.display(),
+                message
+            ),
+            ProbeError::Failed(msg) => write!(f, "{}", msg),
+            ProbeError::MissingArchives { missing, searched } => {
+                write!(f, "Libraries not found: {} (searched:", missing.join(", "))?;
+                for dir in searched {
+                    write!(f, " {}", dir.display())?;
+                }
+                write!(f, ")")
+            }
+            ProbeError::StaticArchiveNotFound { library, searched } => {
+                write!(
+                    f,
+                    "-l{} is inside a -Wl,-Bstatic region, but lib{}.a was not found in:",
+                    library, library
+                )?;
+                for dir in searched {
+                    write!(f, " {}", dir.display())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+impl From<ProbeError> for String {
+    fn from(err: ProbeError) -> String {
+        err.to_string()
+    }
+}
@@ -64,10 +64,377 @@
 //!     // Force whole-archive for libs with constructor functions
 //!     .force_whole_archive(["mylib_with_constructors"]);
 //! ```
+//!
+//! # Environment Overrides
+//!
+//! Linkage can be changed without editing `build.rs`:
+//!
+//! | Variable | Effect |
+//! |----------|--------|
+//! | `SPDK_PKGCONF_ALL_DYNAMIC=1` | Every library becomes [`LinkKind::Default`] |
+//! | `SPDK_PKGCONF_STATIC=lib1,lib2` | Listed libraries become [`LinkKind::Static`] |
+//! | `SPDK_PKGCONF_WHOLE_ARCHIVE=lib1,lib2` | Listed libraries with a static archive become [`LinkKind::WholeArchive`] |
+//! | `PKGCONF_SYSTEM_ROOTS=dir1:dir2` | Added to the system roots, replacing [`PkgConfigParser::detect_system_roots`] |
+//!
+//! Precedence is environment, then builder settings, then auto-detection.
+//! Per-library variables beat `SPDK_PKGCONF_ALL_DYNAMIC`. Call
+//! [`PkgConfigParser::emit_rerun_env`] so cargo re-runs the build script
+//! when they change.
+//!
+//! # Cross-compiling
+//!
+//! When cargo's `TARGET` differs from `HOST`, `$TARGET_PKG_CONFIG` names the
+//! pkg-config executable (before `$PKG_CONFIG`);
+//! [`PkgConfigParser::pkg_config_exe`] overrides both. If pkg-config reports
+//! library directories as the target sees them, set
+//! [`PkgConfigParser::sysroot`] so static archives are looked for under the
+//! sysroot while the emitted search paths stay unchanged.
+//!
+//! # Offline Builds
+//!
+//! Where pkg-config can't be run (e.g. a hermetic CI sandbox), record the
+//! `--static --libs` and `--cflags` output beforehand and hand it to
+//! [`PkgConfigParser::probe_from_output`] or [`PkgConfig::from_files`].
+//! The output is parsed exactly as [`PkgConfigParser::probe`] would, and
+//! static detection still checks the filesystem for archives.
+//!
+//! # Windows
+//!
+//! [`TargetEnv`] (read from cargo's `TARGET` / `CARGO_CFG_TARGET_*`
+//! variables, or set with [`PkgConfigParser::target_env`]) selects the
+//! archive names static detection looks for: `<name>.lib` on MSVC, and
+//! `lib<name>.a` or `<name>.lib` on MinGW, where `lib<name>.dll.a` import
+//! libraries link dynamically. System roots may use drive letters.
+//!
+//! MSVC has no `+whole-archive`; [`emit_cargo_metadata`] emits
+//! `/WHOLEARCHIVE:<name>.lib` link arguments instead and drops GNU `-Wl,`
+//! arguments.
+//!
+//! # Serialization
+//!
+//! With the `serde` feature, [`PkgConfig`] and the flag types implement
+//! `Serialize`/`Deserialize`, and [`PkgConfig::to_json`] /
+//! [`PkgConfig::from_json`] snapshot a probe result without depending on
+//! `serde_json` directly.
+//!
+//! The format is stable within a major version: struct fields and enum
+//! variants are kebab-case (`"pc-files"`, `"whole-archive"`,
+//! `"search-path"`) and enums are externally tagged. Paths must be valid UTF-8;
+//! serializing a non-UTF-8 path is an error rather than a lossy conversion.
+
+mod cache;
+mod error;
+
+pub use error::{Error, ProbeError};
 
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
+
+/// Environment variables that influence the output of `pkg-config`.
+///
+/// These are part of the cache key used by [`PkgConfigParser::cached`].
+const PKG_CONFIG_ENV_VARS: &[&str] = &[
+    ENV_PKG_CONFIG,
+    ENV_TARGET_PKG_CONFIG,
+    "PKG_CONFIG_PATH",
+    "PKG_CONFIG_LIBDIR",
+    "PKG_CONFIG_SYSROOT_DIR",
+    "PKG_CONFIG_ALLOW_SYSTEM_LIBS",
+    "PKG_CONFIG_ALLOW_SYSTEM_CFLAGS",
+];
+
+/// The pkg-config executable to run instead of `pkg-config`.
+const ENV_PKG_CONFIG: &str = "PKG_CONFIG";
+
+/// The pkg-config executable for the target when cross-compiling; takes
+/// precedence over [`ENV_PKG_CONFIG`].
+const ENV_TARGET_PKG_CONFIG: &str = "TARGET_PKG_CONFIG";
+
+/// Oldest pkg-config accepted by [`PkgConfigParser::check_tool`] by default.
+const DEFAULT_MIN_TOOL_VERSION: &str = "0.29";
+
+/// Forces every library to [`LinkKind::Default`] when set to `1`.
+const ENV_ALL_DYNAMIC: &str = "SPDK_PKGCONF_ALL_DYNAMIC";
+
+/// Comma-separated libraries forced to [`LinkKind::Static`].
+const ENV_STATIC: &str = "SPDK_PKGCONF_STATIC";
+
+/// Comma-separated libraries added to the force-whole-archive set.
+const ENV_WHOLE_ARCHIVE: &str = "SPDK_PKGCONF_WHOLE_ARCHIVE";
+
+/// Extra system roots, in `PATH` syntax, used instead of asking the compiler.
+const ENV_SYSTEM_ROOTS: &str = "PKGCONF_SYSTEM_ROOTS";
+
+/// Environment variables that override link kinds in [`PkgConfigParser::parse`].
+const LINK_OVERRIDE_ENV_VARS: &[&str] = &[
+    ENV_ALL_DYNAMIC,
+    ENV_STATIC,
+    ENV_WHOLE_ARCHIVE,
+    ENV_SYSTEM_ROOTS,
+];
+
+/// Directories the system linker searches by default.
+///
+/// `-L` flags for these are dropped unless
+/// [`PkgConfigParser::keep_default_lib_dirs`] is set.
+const DEFAULT_LIB_DIRS: &[&str] = &["/usr/lib", "/usr/lib64", "/lib"];
+
+/// Cleans up a search path from pkg-config output.
+///
+/// Existing paths are canonicalized (resolving symlinks). Otherwise the path
+/// is normalized lexically: duplicate slashes and `.` are removed and `..`
+/// is resolved against the preceding component.
+fn normalize_path(path: &str) -> PathBuf {
+    if let Ok(canonical) = std::fs::canonicalize(path) {
+        return canonical;
+    }
+
+    let mut normalized = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if normalized.file_name().is_some() {
+                    normalized.pop();
+                } else if !normalized.has_root() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Rebuilds the `--libs` argument list from the split queries.
+///
+/// Keeps the tokens of `full` that one of the `--libs-only-*` outputs
+/// reported, in their original order. Directories and libraries reported
+/// only by the split queries are added, directories first.
+fn merge_split_libs(full: &str, dirs: &str, names: &str, other: &str) -> Vec<String> {
+    let dirs: Vec<String> = split_args(dirs)
+        .into_iter()
+        .filter(|arg| arg.starts_with("-L"))
+        .collect();
+    let names: Vec<String> = split_args(names)
+        .into_iter()
+        .filter(|arg| arg.starts_with("-l"))
+        .collect();
+    let other: HashSet<String> = split_args(other).into_iter().collect();
+
+    let mut tokens: Vec<String> = split_args(full)
+        .into_iter()
+        .filter(|arg| dirs.contains(arg) || names.contains(arg) || other.contains(arg))
+        .collect();
+
+    let missing_dirs: Vec<String> = dirs
+        .into_iter()
+        .filter(|dir| !tokens.contains(dir))
+        .collect();
+    let missing_names: Vec<String> = names
+        .into_iter()
+        .filter(|name| !tokens.contains(name))
+        .collect();
+    tokens.splice(0..0, missing_dirs);
+    tokens.extend(missing_names);
+    tokens
+}
+
+/// The pkg-config executable: `$TARGET_PKG_CONFIG` when cross-compiling
+/// (cargo's `TARGET` differs from `HOST`), then `$PKG_CONFIG`, or
+/// `pkg-config`.
+fn pkg_config_program() -> String {
+    pkg_config_program_from(|var| std::env::var(var).ok())
+}
+
+/// [`pkg_config_program`] reading variables through `lookup`.
+fn pkg_config_program_from(lookup: impl Fn(&str) -> Option<String>) -> String {
+    let cross = matches!(
+        (lookup("TARGET"), lookup("HOST")),
+        (Some(target), Some(host)) if target != host
+    );
+    cross
+        .then(|| lookup(ENV_TARGET_PKG_CONFIG))
+        .flatten()
+        .filter(|program| !program.is_empty())
+        .or_else(|| lookup(ENV_PKG_CONFIG).filter(|program| !program.is_empty()))
+        .unwrap_or_else(|| "pkg-config".to_string())
+}
+
+/// Parses the leading `major.minor[.patch]` of a version string.
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let parts: Vec<u32> = version
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect();
+    (!parts.is_empty()).then_some(parts)
+}
+
+/// Finds `program` in `PATH`, unless it already names a path.
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| [dir.join(program), dir.join(format!("{}.exe", program))])
+        .find(|candidate| candidate.is_file())
+}
+
+/// Asks the C compiler `cc` for its library search directories.
+///
+/// `cc` may include arguments (`"ccache gcc"`, `"clang --target=..."`).
+/// Returns an empty list if the compiler can't be run.
+fn compiler_search_dirs(cc: &str) -> Vec<PathBuf> {
+    let mut args = split_args(cc);
+    if args.is_empty() {
+        return Vec::new();
+    }
+    let program = args.remove(0);
+
+    match Command::new(program)
+        .args(args)
+        .arg("-print-search-dirs")
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            parse_search_dirs(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Extracts the directories from the `libraries: =dir:dir` line of
+/// `cc -print-search-dirs` output.
+fn parse_search_dirs(output: &str) -> Vec<PathBuf> {
+    let Some(line) = output
+        .lines()
+        .find_map(|line| line.strip_prefix("libraries:"))
+    else {
+        return Vec::new();
+    };
+    let line = line.trim();
+    let line = line.strip_prefix('=').unwrap_or(line);
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in std::env::split_paths(line) {
+        if dir.as_os_str().is_empty() {
+            continue;
+        }
+        let dir = normalize_path(&dir.to_string_lossy());
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Splits pkg-config output into arguments the way a POSIX shell would.
+///
+/// pkg-config escapes whitespace in paths (`-L/opt/my\ spdk/lib`), and
+/// hand-written `.pc` files sometimes quote them instead. Outside quotes a
+/// backslash escapes the next character; inside double quotes it only
+/// escapes `"` and `\`; single quotes are literal. Adjacent quoted and
+/// unquoted segments join into one argument.
+fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '\\' => {
+                in_arg = true;
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            '\'' => {
+                in_arg = true;
+                for next in chars.by_ref() {
+                    if next == '\'' {
+                        break;
+                    }
+                    current.push(next);
+                }
+            }
+            '"' => {
+                in_arg = true;
+                while let Some(next) = chars.next() {
+                    match next {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(escaped @ ('"' | '\\')) => current.push(escaped),
+                            Some(other) => {
+                                current.push('\\');
+                                current.push(other);
+                            }
+                            None => current.push('\\'),
+                        },
+                        other => current.push(other),
+                    }
+                }
+            }
+            other => {
+                in_arg = true;
+                current.push(other);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Link kind overrides read from the `SPDK_PKGCONF_*` environment variables.
+#[derive(Debug, Default)]
+struct EnvOverrides {
+    all_dynamic: bool,
+    static_libs: HashSet<String>,
+    whole_archive: HashSet<String>,
+}
+
+impl EnvOverrides {
+    /// Reads the overrides from the process environment.
+    fn from_env() -> Self {
+        Self::from_lookup(|var| std::env::var(var).ok())
+    }
+
+    /// Reads the overrides through `lookup`, which maps a variable name to its value.
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let list = |var| -> HashSet<String> {
+            lookup(var)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|lib| !lib.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Self {
+            all_dynamic: lookup(ENV_ALL_DYNAMIC).is_some_and(|v| v == "1"),
+            static_libs: list(ENV_STATIC),
+            whole_archive: list(ENV_WHOLE_ARCHIVE),
+        }
+    }
+}
 
 /// Represents how a library should be linked.
 ///
@@ -76,6 +443,8 @@ use std::process::Command;
 /// - [`Static`](LinkKind::Static) → `cargo:rustc-link-lib=static:[-bundle]=name`
 /// - [`WholeArchive`](LinkKind::WholeArchive) → `cargo:rustc-link-lib=static:+whole-archive[,-bundle]=name`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum LinkKind {
     /// Let the linker decide (typically finds `.so` first, then `.a`).
     ///
@@ -97,15 +466,164 @@ pub enum LinkKind {
     WholeArchive,
 }
 
+/// The toolchain family the libraries are linked for.
+///
+/// Decides which file names count as a static archive in
+/// [`PkgConfigParser::parse`], how system roots are compared, and how
+/// [`LinkerFlag::to_target_cargo_directives`] spells whole-archive linking.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TargetEnv {
+    /// Unix-like targets: static archives are `lib<name>.a`.
+    #[default]
+    Unix,
+
+    /// MinGW (`*-windows-gnu`): static archives are `lib<name>.a` or
+    /// `<name>.lib`. `lib<name>.dll.a` is an import library for a DLL and
+    /// never counts as static.
+    WindowsGnu,
+
+    /// MSVC (`*-windows-msvc`): static archives are `<name>.lib`.
+    ///
+    /// `+whole-archive` and GNU `-Wl,` arguments are not emitted; see
+    /// [`LinkerFlag::to_target_cargo_directives`].
+    Msvc,
+}
+
+impl TargetEnv {
+    /// Reads the target from cargo's build script environment.
+    ///
+    /// Uses `CARGO_CFG_TARGET_ENV` and `CARGO_CFG_TARGET_OS`, falling back to
+    /// the `TARGET` triple. Outside a build script this is [`TargetEnv::Unix`].
+    pub fn from_env() -> Self {
+        Self::from_lookup(|var| std::env::var(var).ok())
+    }
+
+    /// Reads the target through `lookup`, which maps a variable name to its value.
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let target = lookup("TARGET").unwrap_or_default();
+        let env = lookup("CARGO_CFG_TARGET_ENV")
+            .unwrap_or_else(|| target.rsplit('-').next().unwrap_or_default().to_string());
+        let windows = lookup("CARGO_CFG_TARGET_OS")
+            .map_or_else(|| target.contains("-windows"), |os| os == "windows");
+
+        match (windows, env.as_str()) {
+            (_, "msvc") => TargetEnv::Msvc,
+            (true, _) => TargetEnv::WindowsGnu,
+            _ => TargetEnv::Unix,
+        }
+    }
+
+    /// Whether this is a Windows target.
+    fn is_windows(self) -> bool {
+        matches!(self, TargetEnv::WindowsGnu | TargetEnv::Msvc)
+    }
+
+    /// File names that hold a static archive of `name`, in search order.
+    fn static_archive_names(self, name: &str) -> Vec<String> {
+        match self {
+            TargetEnv::Unix => vec![format!("lib{}.a", name)],
+            TargetEnv::WindowsGnu => vec![format!("lib{}.a", name), format!("{}.lib", name)],
+            TargetEnv::Msvc => vec![format!("{}.lib", name)],
+        }
+    }
+}
+
+/// Returns `true` if `dir` is `root` or inside it.
+///
+/// On Windows targets paths are compared as strings, ignoring case and the
+/// `\\?\` prefix added by canonicalization, with `\` and `/` treated alike,
+/// so `C:\msys64\mingw64\lib` is under `c:/msys64`. This also works when
+/// cross-compiling from a Unix host, where `Path` does not know about drive
+/// letters.
+fn is_under_root(dir: &Path, root: &Path, target: TargetEnv) -> bool {
+    if !target.is_windows() {
+        return dir.starts_with(root);
+    }
+
+    let key = |path: &Path| {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let path = path.strip_prefix("//?/").unwrap_or(&path);
+        path.trim_end_matches('/').to_ascii_lowercase()
+    };
+    let (dir, root) = (key(dir), key(root));
+    dir == root
+        || dir
+            .strip_prefix(&root)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Why a library was given its [`LinkKind`].
+///
+/// Reported by [`PkgConfig::decisions`] for debugging link problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum DecisionReason {
+    /// An `SPDK_PKGCONF_*` environment variable.
+    Environment,
+    /// [`PkgConfigParser::override_link_kind`] or
+    /// [`PkgConfigParser::force_whole_archive`].
+    Override,
+    /// The library appeared inside a `-Wl,--whole-archive` region.
+    WholeArchiveRegion,
+    /// `lib<name>.a` exists outside the system roots.
+    StaticAvailable,
+    /// No `lib<name>.a` outside the system roots (or implied by `-pthread`).
+    NoStaticArchive,
+    /// [`PkgConfigParser::prefer_dynamic`].
+    PreferDynamic,
+    /// The library appeared between `-Wl,-Bstatic` and `-Wl,-Bdynamic`.
+    StaticRegion,
+}
+
+impl DecisionReason {
+    /// Short kebab-case name, used in the probe cache.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            DecisionReason::Environment => "environment",
+            DecisionReason::Override => "override",
+            DecisionReason::WholeArchiveRegion => "whole-archive-region",
+            DecisionReason::StaticAvailable => "static-available",
+            DecisionReason::NoStaticArchive => "no-static-archive",
+            DecisionReason::PreferDynamic => "prefer-dynamic",
+            DecisionReason::StaticRegion => "static-region",
+        }
+    }
+
+    /// Inverse of [`as_str`](Self::as_str).
+    pub(crate) fn from_str(s: &str) -> Option<Self> {
+        Some(match s {
+            "environment" => DecisionReason::Environment,
+            "override" => DecisionReason::Override,
+            "whole-archive-region" => DecisionReason::WholeArchiveRegion,
+            "static-available" => DecisionReason::StaticAvailable,
+            "no-static-archive" => DecisionReason::NoStaticArchive,
+            "prefer-dynamic" => DecisionReason::PreferDynamic,
+            "static-region" => DecisionReason::StaticRegion,
+            _ => return None,
+        })
+    }
+}
+
+/// A library link decision: name, chosen kind and the reason for it.
+type LinkDecision = (String, LinkKind, DecisionReason);
+
+/// Parsed linker flags with their link decisions and warnings.
+type ParsedLibs = (Vec<LinkerFlag>, Vec<LinkDecision>, Vec<String>);
+
 /// A parsed linker flag from pkg-config output.
 ///
 /// These are the structured representations of flags parsed from
 /// `pkg-config --static --libs` output.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum LinkerFlag {
     /// Library search path (`-L/path/to/libs`).
     ///
-    /// Emitted as `cargo:rustc-link-search=native=/path/to/libs`.
+    /// Emitted as `cargo:rustc-link-search=native=/path/to/libs`. The path is
+    /// stored unescaped and emitted verbatim: cargo takes the rest of the
+    /// directive line as the value, so spaces need no quoting.
     SearchPath(String),
 
     /// Library to link (`-lname` or `-l:libname.a`).
@@ -121,9 +639,30 @@ pub enum LinkerFlag {
     /// Raw linker argument (`-Wl,--export-dynamic`, etc.).
     ///
     /// Only certain linker arguments are preserved (e.g., `--export-dynamic`,
-    /// `--as-needed`). The `--whole-archive` markers are consumed internally
-    /// and converted to [`LinkKind::WholeArchive`] on affected libraries.
+    /// `--as-needed`, `-rpath <dir>` as `-Wl,-rpath,<dir>`). Comma-separated
+    /// arguments are split first; the `--whole-archive` markers are consumed
+    /// internally and converted to [`LinkKind::WholeArchive`] on affected
+    /// libraries, and libraries named inside `-Wl,` become
+    /// [`LinkerFlag::Library`] entries.
     LinkerArg(String),
+
+    /// macOS framework to link (`-framework CoreFoundation`).
+    ///
+    /// Emitted as `cargo:rustc-link-lib=framework=CoreFoundation`. Frameworks
+    /// are never subject to static library detection.
+    Framework(String),
+
+    /// macOS framework search path (`-F/path/to/frameworks`).
+    ///
+    /// Emitted as `cargo:rustc-link-search=framework=/path/to/frameworks`.
+    FrameworkSearchPath(String),
+
+    /// Shared object referenced by exact file name (`-l:liblz4.so.1`).
+    ///
+    /// Emitted as `cargo:rustc-link-lib=dylib:+verbatim=liblz4.so.1` so the
+    /// linker receives the file name unchanged. Verbatim libraries are never
+    /// subject to static library detection.
+    VerbatimLibrary(String),
 }
 
 impl LinkerFlag {
@@ -148,6 +687,12 @@ impl LinkerFlag {
     /// let flag = LinkerFlag::Library { name: "bar".to_string(), kind: LinkKind::WholeArchive };
     /// assert_eq!(flag.to_cargo_directive(true), "cargo:rustc-link-lib=static:+whole-archive,-bundle=bar");
     /// assert_eq!(flag.to_cargo_directive(false), "cargo:rustc-link-lib=static:+whole-archive=bar");
+    ///
+    /// let flag = LinkerFlag::Framework("CoreFoundation".to_string());
+    /// assert_eq!(flag.to_cargo_directive(true), "cargo:rustc-link-lib=framework=CoreFoundation");
+    ///
+    /// let flag = LinkerFlag::VerbatimLibrary("liblz4.so.1".to_string());
+    /// assert_eq!(flag.to_cargo_directive(true), "cargo:rustc-link-lib=dylib:+verbatim=liblz4.so.1");
     /// ```
     pub fn to_cargo_directive(&self, no_bundle: bool) -> String {
         match self {
@@ -179,7 +724,94 @@ impl LinkerFlag {
             LinkerFlag::LinkerArg(arg) => {
                 format!("cargo:rustc-link-arg={}", arg)
             }
+            LinkerFlag::Framework(name) => {
+                format!("cargo:rustc-link-lib=framework={}", name)
+            }
+            LinkerFlag::FrameworkSearchPath(path) => {
+                format!("cargo:rustc-link-search=framework={}", path)
+            }
+            LinkerFlag::VerbatimLibrary(file) => {
+                format!("cargo:rustc-link-lib=dylib:+verbatim={}", file)
+            }
+        }
+    }
+
+    /// Converts this flag to cargo metadata directives for `target`.
+    ///
+    /// Same as [`to_cargo_directive`](Self::to_cargo_directive) except on
+    /// [`TargetEnv::Msvc`], where:
+    ///
+    /// - [`LinkKind::WholeArchive`] becomes a plain static library plus
+    ///   `cargo:rustc-link-arg=/WHOLEARCHIVE:<name>.lib`
+    /// - [`LinkerFlag::LinkerArg`]s in GNU `-Wl,` form are dropped, since
+    ///   `link.exe` does not understand them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pkgconf::{LinkerFlag, LinkKind, TargetEnv};
+    ///
+    /// let flag = LinkerFlag::Library { name: "bar".to_string(), kind: LinkKind::WholeArchive };
+    /// assert_eq!(
+    ///     flag.to_target_cargo_directives(true, TargetEnv::Msvc),
+    ///     [
+    ///         "cargo:rustc-link-lib=static:-bundle=bar",
+    ///         "cargo:rustc-link-arg=/WHOLEARCHIVE:bar.lib",
+    ///     ]
+    /// );
+    ///
+    /// let flag = LinkerFlag::LinkerArg("-Wl,--start-group".to_string());
+    /// assert!(flag.to_target_cargo_directives(true, TargetEnv::Msvc).is_empty());
+    /// ```
+    pub fn to_target_cargo_directives(&self, no_bundle: bool, target: TargetEnv) -> Vec<String> {
+        if target != TargetEnv::Msvc {
+            return vec![self.to_cargo_directive(no_bundle)];
+        }
+
+        match self {
+            LinkerFlag::Library {
+                name,
+                kind: LinkKind::WholeArchive,
+            } => {
+                let archive = LinkerFlag::Library {
+                    name: name.clone(),
+                    kind: LinkKind::Static,
+                };
+                vec![
+                    archive.to_cargo_directive(no_bundle),
+                    format!("cargo:rustc-link-arg=/WHOLEARCHIVE:{}.lib", name),
+                ]
+            }
+            LinkerFlag::LinkerArg(arg) if arg.starts_with("-Wl,") => Vec::new(),
+            _ => vec![self.to_cargo_directive(no_bundle)],
+        }
+    }
+}
+
+/// Splits a static archive named directly on the link line
+/// (`librte_bus_pci.a` or `/opt/dpdk/lib/librte_bus_pci.a`) into its
+/// directory, if any, and library name.
+fn split_archive_path(arg: &str) -> Option<(Option<&str>, &str)> {
+    let (dir, file) = match arg.rsplit_once('/') {
+        Some((dir, file)) => (Some(if dir.is_empty() { "/" } else { dir }), file),
+        None => (None, arg),
+    };
+    let name = file.strip_prefix("lib")?.strip_suffix(".a")?;
+    (!name.is_empty()).then_some((dir, name))
+}
+
+/// Returns `true` if `file` names a shared object (`foo.so` or `foo.so.1.2.3`).
+fn is_shared_object(file: &str) -> bool {
+    match file.split_once(".so") {
+        Some((stem, "")) => !stem.is_empty(),
+        Some((stem, version)) => {
+            !stem.is_empty()
+                && version.strip_prefix('.').is_some_and(|v| {
+                    v.split('.')
+                        .all(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+                })
         }
+        None => false,
     }
 }
 
@@ -188,6 +820,8 @@ impl LinkerFlag {
 /// These flags are **not** consumed by cargo or rustc — they are used as
 /// clang arguments for bindgen when generating FFI bindings from C headers.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum CompilerFlag {
     /// Include search path (`-I/path/to/headers`).
     ///
@@ -206,10 +840,49 @@ pub enum CompilerFlag {
         /// The macro value, if any.
         value: Option<String>,
     },
+
+    /// Preprocessor undefine (`-UFOO`).
+    ///
+    /// Passed to bindgen as `-UFOO`. Some SPDK configs ship `-UNDEBUG`,
+    /// which changes which declarations and asserts the headers expose.
+    Undefine(String),
+
+    /// System include search path (`-isystem /path`).
+    ///
+    /// Searched after `-I` paths, and warnings in these headers are
+    /// suppressed. Some distros ship DPDK cflags as `-isystem /usr/include/dpdk`.
+    SystemIncludePath(PathBuf),
+
+    /// Quote include search path (`-iquote /path`).
+    ///
+    /// Only used for `#include "..."`, not `#include <...>`.
+    QuoteIncludePath(PathBuf),
+
+    /// Header included before the translation unit (`-include rte_config.h`).
+    ForcedInclude(PathBuf),
+
+    /// `-pthread`.
+    ///
+    /// Besides linking libpthread, this defines `_REENTRANT`, which changes
+    /// some declarations in system headers. Forwarding it keeps bindgen's
+    /// view of the headers the same as the C build's.
+    Pthread,
+
+    /// Target code generation flag (`-m64`, `-march=native`, `-mavx2`, ...).
+    ///
+    /// These can change type sizes and struct layouts, and predefine macros
+    /// like `__AVX2__`. Only produced when
+    /// [`PkgConfigParser::keep_codegen_flags`] is set.
+    Codegen(String),
 }
 
 impl CompilerFlag {
-    /// Converts this flag to a clang argument string for bindgen.
+    /// Converts this flag to a single clang argument string for bindgen.
+    ///
+    /// Flags that take a separate path argument (`-isystem`, `-iquote`,
+    /// `-include`) are returned in their joined form, which clang also
+    /// accepts. Use [`to_clang_args`](Self::to_clang_args) for the
+    /// two-argument form.
     ///
     /// # Examples
     ///
@@ -225,6 +898,9 @@ impl CompilerFlag {
     ///
     /// let flag = CompilerFlag::Define { key: "FOO".to_string(), value: Some("1".to_string()) };
     /// assert_eq!(flag.to_clang_arg(), "-DFOO=1");
+    ///
+    /// let flag = CompilerFlag::SystemIncludePath(PathBuf::from("/usr/include/dpdk"));
+    /// assert_eq!(flag.to_clang_arg(), "-isystem/usr/include/dpdk");
     /// ```
     pub fn to_clang_arg(&self) -> String {
         match self {
@@ -234,12 +910,48 @@ impl CompilerFlag {
                 key,
                 value: Some(v),
             } => format!("-D{}={}", key, v),
+            CompilerFlag::Undefine(key) => format!("-U{}", key),
+            CompilerFlag::SystemIncludePath(path) => format!("-isystem{}", path.display()),
+            CompilerFlag::QuoteIncludePath(path) => format!("-iquote{}", path.display()),
+            CompilerFlag::ForcedInclude(path) => format!("-include{}", path.display()),
+            CompilerFlag::Pthread => "-pthread".to_string(),
+            CompilerFlag::Codegen(arg) => arg.clone(),
         }
     }
+
+    /// Converts this flag to clang argument strings for bindgen.
+    ///
+    /// `-isystem`, `-iquote` and `-include` are emitted as two arguments,
+    /// the option followed by the path. All other flags are a single argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use pkgconf::CompilerFlag;
+    ///
+    /// let flag = CompilerFlag::ForcedInclude(PathBuf::from("rte_config.h"));
+    /// assert_eq!(flag.to_clang_args(), ["-include", "rte_config.h"]);
+    ///
+    /// let flag = CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include"));
+    /// assert_eq!(flag.to_clang_args(), ["-I/opt/spdk/include"]);
+    /// ```
+    pub fn to_clang_args(&self) -> Vec<String> {
+        let (option, path) = match self {
+            CompilerFlag::SystemIncludePath(path) => ("-isystem", path),
+            CompilerFlag::QuoteIncludePath(path) => ("-iquote", path),
+            CompilerFlag::ForcedInclude(path) => ("-include", path),
+            _ => return vec![self.to_clang_arg()],
+        };
+        vec![option.to_string(), path.display().to_string()]
+    }
 }
 
 /// Converts a slice of [`CompilerFlag`]s to clang argument strings for bindgen.
 ///
+/// Flags with a separate path argument expand to two strings (see
+/// [`CompilerFlag::to_clang_args`]).
+///
 /// # Example
 ///
 /// ```
@@ -249,12 +961,13 @@ impl CompilerFlag {
 /// let flags = vec![
 ///     CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
 ///     CompilerFlag::Define { key: "FOO".to_string(), value: None },
+///     CompilerFlag::SystemIncludePath(PathBuf::from("/usr/include/dpdk")),
 /// ];
 /// let args = to_clang_args(&flags);
-/// assert_eq!(args, vec!["-I/opt/spdk/include", "-DFOO"]);
+/// assert_eq!(args, vec!["-I/opt/spdk/include", "-DFOO", "-isystem", "/usr/include/dpdk"]);
 /// ```
 pub fn to_clang_args(flags: &[CompilerFlag]) -> Vec<String> {
-    flags.iter().map(|f| f.to_clang_arg()).collect()
+    flags.iter().flat_map(|f| f.to_clang_args()).collect()
 }
 
 /// Converts a slice of [`LinkerFlag`]s to cargo metadata directive strings.
@@ -268,49 +981,666 @@ pub fn to_cargo_directives(flags: &[LinkerFlag], no_bundle: bool) -> Vec<String>
         .collect()
 }
 
-/// Emits cargo metadata directives to stdout.
+/// Converts a slice of [`LinkerFlag`]s to cargo metadata directives for `target`.
 ///
-/// Convenience function that prints each directive from [`to_cargo_directives`].
-///
-/// `no_bundle` controls whether static libraries get the `-bundle` modifier.
-/// Set to `true` for `-sys` crates that use the `links` key in `Cargo.toml`.
-pub fn emit_cargo_metadata(flags: &[LinkerFlag], no_bundle: bool) {
-    for directive in to_cargo_directives(flags, no_bundle) {
-        println!("{directive}");
-    }
+/// See [`LinkerFlag::to_target_cargo_directives`] for how MSVC differs.
+pub fn to_target_cargo_directives(
+    flags: &[LinkerFlag],
+    no_bundle: bool,
+    target: TargetEnv,
+) -> Vec<String> {
+    flags
+        .iter()
+        .flat_map(|f| f.to_target_cargo_directives(no_bundle, target))
+        .collect()
 }
 
-/// Parsed pkg-config output for a set of packages.
+/// Reconstructs a gcc-style link line from [`LinkerFlag`]s, for debugging.
 ///
-/// Contains structured linker flags (from `--libs`) and compiler flags
-/// (from `--cflags`). This is a pure data type — it holds what pkg-config
-/// reported, with no cargo/rustc/bindgen concerns baked in.
+/// The inverse of parsing: libraries are written as `-l` flags in order,
+/// [`LinkKind::Static`] and [`LinkKind::WholeArchive`] libraries inside
+/// `-Wl,-Bstatic` ... `-Wl,-Bdynamic`, and whole-archive libraries
+/// additionally inside `-Wl,--whole-archive` ... `-Wl,--no-whole-archive`.
+/// This is roughly what rustc passes to the linker for the equivalent cargo
+/// directives, so it can be pasted into a manual link attempt when linking
+/// fails. Arguments containing whitespace or shell metacharacters are
+/// single-quoted.
 ///
-/// Use [`to_clang_args`] to convert `cflags` for bindgen, and
-/// [`emit_cargo_metadata`] or [`to_cargo_directives`] to convert `libs`
-/// for cargo.
-#[derive(Debug, Clone)]
-pub struct PkgConfig {
-    /// Linker flags from `pkg-config --static --libs`.
-    pub libs: Vec<LinkerFlag>,
-    /// Compiler flags from `pkg-config --cflags`.
-    pub cflags: Vec<CompilerFlag>,
+/// # Example
+///
+/// ```
+/// use pkgconf::{LinkKind, LinkerFlag, link_line_preview};
+///
+/// let flags = vec![
+///     LinkerFlag::SearchPath("/opt/spdk/lib".to_string()),
+///     LinkerFlag::Library { name: "spdk_env_dpdk".to_string(), kind: LinkKind::WholeArchive },
+///     LinkerFlag::Library { name: "spdk_log".to_string(), kind: LinkKind::Static },
+///     LinkerFlag::Library { name: "numa".to_string(), kind: LinkKind::Default },
+/// ];
+/// assert_eq!(
+///     link_line_preview(&flags),
+///     "-L/opt/spdk/lib -Wl,-Bstatic -Wl,--whole-archive -lspdk_env_dpdk \
+///      -Wl,--no-whole-archive -lspdk_log -Wl,-Bdynamic -lnuma"
+/// );
+/// ```
+pub fn link_line_preview(flags: &[LinkerFlag]) -> String {
+    let mut args: Vec<String> = Vec::new();
+    let mut in_static_region = false;
+    let mut in_whole_archive_region = false;
+
+    for flag in flags {
+        // Only libraries open or close regions; other flags stay inside them
+        let (is_static, is_whole_archive) = match flag {
+            LinkerFlag::Library { kind, .. } => {
+                (*kind != LinkKind::Default, *kind == LinkKind::WholeArchive)
+            }
+            LinkerFlag::VerbatimLibrary(_) => (false, false),
+            _ => (in_static_region, in_whole_archive_region),
+        };
+        if in_whole_archive_region && !is_whole_archive {
+            args.push("-Wl,--no-whole-archive".to_string());
+            in_whole_archive_region = false;
+        }
+        if in_static_region != is_static {
+            args.push(
+                if is_static {
+                    "-Wl,-Bstatic"
+                } else {
+                    "-Wl,-Bdynamic"
+                }
+                .to_string(),
+            );
+            in_static_region = is_static;
+        }
+        if is_whole_archive && !in_whole_archive_region {
+            args.push("-Wl,--whole-archive".to_string());
+            in_whole_archive_region = true;
+        }
+
+        match flag {
+            LinkerFlag::SearchPath(path) => args.push(shell_quote(&format!("-L{}", path))),
+            LinkerFlag::Library { name, .. } => args.push(shell_quote(&format!("-l{}", name))),
+            LinkerFlag::LinkerArg(arg) => args.push(shell_quote(arg)),
+            LinkerFlag::Framework(name) => {
+                args.push("-framework".to_string());
+                args.push(shell_quote(name));
+            }
+            LinkerFlag::FrameworkSearchPath(path) => args.push(shell_quote(&format!("-F{}", path))),
+            LinkerFlag::VerbatimLibrary(file) => args.push(shell_quote(&format!("-l:{}", file))),
+        }
+    }
+
+    if in_whole_archive_region {
+        args.push("-Wl,--no-whole-archive".to_string());
+    }
+    if in_static_region {
+        args.push("-Wl,-Bdynamic".to_string());
+    }
+    args.join(" ")
 }
 
-/// Parser for pkg-config output that properly handles `--whole-archive` regions
-/// and auto-detects static library availability.
+/// Single-quotes `arg` for a POSIX shell if it contains anything but
+/// characters that are safe unquoted.
+fn shell_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_=+,.:/@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Converts a [`PkgConfig`] to `links` metadata directives for dependent crates.
 ///
-/// # Usage
+/// In a crate with `links = "spdk"` these become `DEP_SPDK_INCLUDE`,
+/// `DEP_SPDK_LIB_DIR` and `DEP_SPDK_DEFINES` in the build scripts of direct
+/// dependents, which can then compile C code against the same headers
+/// without re-probing:
 ///
-/// ```no_run
-/// use pkgconf::PkgConfigParser;
+/// - `cargo:include=` - `-I` and `-isystem` directories, colon-separated
+/// - `cargo:lib_dir=` - the first `-L` directory (omitted if there is none)
+/// - `cargo:defines=` - `-D` macros as `KEY=VALUE` or `KEY`, semicolon-separated
 ///
-/// let pkg = PkgConfigParser::new()
-///     .probe(["openssl", "libfoo"], None)
-///     .expect("pkg-config failed");
+/// ```
+/// use std::path::PathBuf;
+/// use pkgconf::{CompilerFlag, LinkerFlag, PkgConfig, to_links_metadata};
 ///
-/// // Emit cargo linker directives (no_bundle=true for -sys crates)
-/// pkgconf::emit_cargo_metadata(&pkg.libs, true);
+/// let mut pkg = PkgConfig::default();
+/// pkg.libs.push(LinkerFlag::SearchPath("/opt/spdk/lib".to_string()));
+/// pkg.cflags.push(CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")));
+/// assert_eq!(
+///     to_links_metadata(&pkg),
+///     [
+///         "cargo:include=/opt/spdk/include",
+///         "cargo:lib_dir=/opt/spdk/lib",
+///         "cargo:defines=",
+///     ]
+/// );
+/// ```
+pub fn to_links_metadata(pkg: &PkgConfig) -> Vec<String> {
+    let mut includes = Vec::new();
+    let mut defines = Vec::new();
+    for flag in &pkg.cflags {
+        match flag {
+            CompilerFlag::IncludePath(path) | CompilerFlag::SystemIncludePath(path) => {
+                includes.push(path.display().to_string());
+            }
+            CompilerFlag::Define { key, value: None } => defines.push(key.clone()),
+            CompilerFlag::Define {
+                key,
+                value: Some(value),
+            } => defines.push(format!("{}={}", key, value)),
+            _ => {}
+        }
+    }
+
+    let mut directives = vec![format!("cargo:include={}", includes.join(":"))];
+    if let Some(dir) = pkg.libs.iter().find_map(|flag| match flag {
+        LinkerFlag::SearchPath(path) => Some(path),
+        _ => None,
+    }) {
+        directives.push(format!("cargo:lib_dir={}", dir));
+    }
+    directives.push(format!("cargo:defines={}", defines.join(";")));
+    directives
+}
+
+/// Emits the `links` metadata from [`to_links_metadata`] to stdout.
+pub fn emit_links_metadata(pkg: &PkgConfig) {
+    emit_links_metadata_to(pkg, &mut std::io::stdout()).expect("failed to write to stdout");
+}
+
+/// Writes the `links` metadata from [`to_links_metadata`] to `w`, one
+/// directive per line.
+pub fn emit_links_metadata_to(pkg: &PkgConfig, w: &mut impl Write) -> std::io::Result<()> {
+    for directive in to_links_metadata(pkg) {
+        writeln!(w, "{}", directive)?;
+    }
+    Ok(())
+}
+
+/// Emits `cargo:rerun-if-changed` directives for [`PkgConfig::rerun_paths`].
+///
+/// Use this so rebuilding the native libraries in place (same prefix)
+/// re-runs the build script.
+pub fn emit_cargo_rerun(pkg: &PkgConfig) {
+    emit_cargo_rerun_to(pkg, &mut std::io::stdout()).expect("failed to write to stdout");
+}
+
+/// Writes the directives of [`emit_cargo_rerun`] to `w`, one per line.
+pub fn emit_cargo_rerun_to(pkg: &PkgConfig, w: &mut impl Write) -> std::io::Result<()> {
+    for path in pkg.rerun_paths() {
+        writeln!(w, "cargo:rerun-if-changed={}", path.display())?;
+    }
+    Ok(())
+}
+
+/// Emits cargo metadata directives to stdout.
+///
+/// Convenience function that prints each directive from
+/// [`to_target_cargo_directives`] for the build script's
+/// [`TargetEnv::from_env`].
+///
+/// `no_bundle` controls whether static libraries get the `-bundle` modifier.
+/// Set to `true` for `-sys` crates that use the `links` key in `Cargo.toml`.
+pub fn emit_cargo_metadata(flags: &[LinkerFlag], no_bundle: bool) {
+    emit_cargo_metadata_to(flags, no_bundle, &mut std::io::stdout())
+        .expect("failed to write to stdout");
+}
+
+/// Writes the directives of [`emit_cargo_metadata`] to `w`, one per line.
+///
+/// Lets a build script collect, inspect or deduplicate the directives
+/// before printing them, and lets tests check them without capturing
+/// stdout.
+///
+/// ```
+/// use pkgconf::{LinkKind, LinkerFlag};
+///
+/// let flags = [
+///     LinkerFlag::SearchPath("/opt/spdk/lib".to_string()),
+///     LinkerFlag::Library { name: "spdk_log".to_string(), kind: LinkKind::Static },
+/// ];
+/// let mut out = Vec::new();
+/// pkgconf::emit_cargo_metadata_to(&flags, true, &mut out).unwrap();
+/// # if pkgconf::TargetEnv::from_env() == pkgconf::TargetEnv::Unix {
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "cargo:rustc-link-search=native=/opt/spdk/lib\n\
+///      cargo:rustc-link-lib=static:-bundle=spdk_log\n"
+/// );
+/// # }
+/// ```
+pub fn emit_cargo_metadata_to(
+    flags: &[LinkerFlag],
+    no_bundle: bool,
+    w: &mut impl Write,
+) -> std::io::Result<()> {
+    for directive in to_target_cargo_directives(flags, no_bundle, TargetEnv::from_env()) {
+        writeln!(w, "{}", directive)?;
+    }
+    Ok(())
+}
+
+/// Parsed pkg-config output for a set of packages.
+///
+/// Contains structured linker flags (from `--libs`) and compiler flags
+/// (from `--cflags`). This is a pure data type — it holds what pkg-config
+/// reported, with no cargo/rustc/bindgen concerns baked in.
+///
+/// Use [`to_clang_args`] to convert `cflags` for bindgen, and
+/// [`emit_cargo_metadata`] or [`to_cargo_directives`] to convert `libs`
+/// for cargo.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct PkgConfig {
+    /// Linker flags from `pkg-config --static --libs`.
+    pub libs: Vec<LinkerFlag>,
+    /// Compiler flags from `pkg-config --cflags`.
+    pub cflags: Vec<CompilerFlag>,
+    /// The `.pc` files consulted, including those of transitive `Requires`.
+    ///
+    /// Resolved on a best-effort basis by [`PkgConfigParser::probe`]; empty
+    /// if the files could not be located.
+    pub pc_files: Vec<PathBuf>,
+    /// The pkg-config executable that produced this result.
+    ///
+    /// Set by [`PkgConfigParser::probe`]; `None` for results built by hand
+    /// or by [`merge`](Self::merge)-ing results without it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tool: Option<ToolInfo>,
+    /// Per-library link decisions, see [`decisions`](Self::decisions).
+    decisions: Vec<LinkDecision>,
+    /// Suspicious pkg-config output, see [`warnings`](Self::warnings).
+    #[cfg_attr(feature = "serde", serde(default))]
+    warnings: Vec<String>,
+}
+
+/// The pkg-config executable found by [`PkgConfigParser::check_tool`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub struct ToolInfo {
+    /// Where the executable was found, or the configured name if it could
+    /// not be located in `PATH`.
+    pub path: PathBuf,
+    /// The version reported by `--version` (e.g. `0.29.2`, or `1.8.1` for
+    /// pkgconf).
+    pub version: String,
+}
+
+/// A `major.minor.patch` package version, for comparisons.
+///
+/// Parsed leniently from [`PkgConfigParser::modversion`] output: missing
+/// components are zero and anything after the leading digits of a
+/// component (such as `-pre`) ends the version, so `"24.01"` is 24.1.0 and
+/// `"25.09-pre"` is 25.9.0.
+///
+/// ```
+/// use pkgconf::Version;
+///
+/// let version = Version::parse("24.01").unwrap();
+/// assert_eq!(version, Version::new(24, 1, 0));
+/// assert!(version >= Version::new(23, 9, 0));
+/// assert!(Version::parse("25.09-pre").unwrap() > version);
+/// assert_eq!(Version::parse("git"), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// Major version.
+    pub major: u32,
+    /// Minor version.
+    pub minor: u32,
+    /// Patch version.
+    pub patch: u32,
+}
+
+impl Version {
+    /// Creates a version from its components.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses the leading `major[.minor[.patch]]` of `version`.
+    ///
+    /// Returns `None` if it doesn't start with a digit.
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = [0u32; 3];
+        let mut components = version.trim().split('.');
+        for (i, part) in parts.iter_mut().enumerate() {
+            let Some(component) = components.next() else {
+                break;
+            };
+            let digits = component
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(component.len());
+            match component[..digits].parse() {
+                Ok(value) => *part = value,
+                Err(_) if i == 0 => return None,
+                Err(_) => break,
+            }
+            if digits < component.len() {
+                break;
+            }
+        }
+        Some(Self::new(parts[0], parts[1], parts[2]))
+    }
+}
+
+impl PkgConfig {
+    /// Builds a result from pkg-config output recorded in `libs_path`
+    /// (`--static --libs`) and `cflags_path` (`--cflags`), without running
+    /// pkg-config.
+    ///
+    /// Uses a default [`PkgConfigParser`]; call
+    /// [`PkgConfigParser::probe_from_files`] to apply builder settings such
+    /// as [`force_whole_archive`](PkgConfigParser::force_whole_archive).
+    ///
+    /// # Errors
+    ///
+    /// As for [`PkgConfigParser::probe_from_files`].
+    pub fn from_files(
+        libs_path: impl AsRef<Path>,
+        cflags_path: impl AsRef<Path>,
+    ) -> Result<Self, ProbeError> {
+        PkgConfigParser::new().probe_from_files(libs_path, cflags_path)
+    }
+
+    /// Returns the final [`LinkKind`] of each library and why it was chosen.
+    ///
+    /// Libraries are listed in the order they first appeared in the
+    /// pkg-config output.
+    ///
+    /// ```no_run
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let pkg = PkgConfigParser::new().probe(["libdpdk"], None).unwrap();
+    /// for (name, kind, reason) in pkg.decisions() {
+    ///     println!("{name}: {kind:?} ({reason:?})");
+    /// }
+    /// ```
+    pub fn decisions(&self) -> Vec<(String, LinkKind, DecisionReason)> {
+        self.decisions.clone()
+    }
+
+    /// Returns problems noticed in the pkg-config output that did not stop
+    /// parsing, such as a `-Wl,--whole-archive` that is never closed.
+    ///
+    /// A build script can forward them to the user:
+    ///
+    /// ```no_run
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let pkg = PkgConfigParser::new().probe(["spdk_env_dpdk"], None).unwrap();
+    /// for warning in pkg.warnings() {
+    ///     println!("cargo:warning={}", warning);
+    /// }
+    /// ```
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Serializes this result to pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a path in `cflags` or `pc_files` is not valid UTF-8.
+    ///
+    /// ```no_run
+    /// use pkgconf::{PkgConfig, PkgConfigParser};
+    ///
+    /// let pkg = PkgConfigParser::new().probe(["spdk_env_dpdk"], None).unwrap();
+    /// let json = pkg.to_json().unwrap();
+    /// std::fs::write("spdk.pkgconfig.json", &json).unwrap();
+    ///
+    /// let replayed = PkgConfig::from_json(&json).unwrap();
+    /// pkgconf::emit_cargo_metadata(&replayed.libs, true);
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserializes a result written by [`to_json`](Self::to_json).
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Returns the paths whose modification should re-run the build script.
+    ///
+    /// This is every consulted `.pc` file, each `-L` directory (so added or
+    /// removed archives are noticed), every `.a` file that made a library
+    /// [`LinkKind::Static`] or [`LinkKind::WholeArchive`], and each include
+    /// directory. Paths are deduplicated, preserving first occurrence.
+    pub fn rerun_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.pc_files.clone();
+
+        let lib_dirs: Vec<PathBuf> = self
+            .libs
+            .iter()
+            .filter_map(|flag| match flag {
+                LinkerFlag::SearchPath(path) => Some(PathBuf::from(path)),
+                _ => None,
+            })
+            .collect();
+        paths.extend(lib_dirs.iter().cloned());
+
+        for flag in &self.libs {
+            if let LinkerFlag::Library { name, kind } = flag
+                && *kind != LinkKind::Default
+            {
+                let libname = format!("lib{}.a", name);
+                paths.extend(
+                    lib_dirs
+                        .iter()
+                        .map(|dir| dir.join(&libname))
+                        .find(|path| path.exists()),
+                );
+            }
+        }
+
+        for flag in &self.cflags {
+            match flag {
+                CompilerFlag::IncludePath(path)
+                | CompilerFlag::SystemIncludePath(path)
+                | CompilerFlag::QuoteIncludePath(path) => paths.push(path.clone()),
+                _ => {}
+            }
+        }
+
+        let mut seen = HashSet::new();
+        paths.retain(|path| seen.insert(path.clone()));
+        paths
+    }
+
+    /// Merges the result of a second probe into this one.
+    ///
+    /// For build scripts that probe several independent packages (e.g. SPDK
+    /// and a vendored library) and need one coherent set of directives:
+    ///
+    /// - `-L` and `-F` search paths are unioned, keeping first occurrence,
+    ///   and listed before any library.
+    /// - A library present in both results is listed once, with the
+    ///   stronger [`LinkKind`] (`WholeArchive` > `Static` > `Default`), and
+    ///   its [`decisions`](Self::decisions) entry comes from the result that
+    ///   supplied that kind.
+    /// - Library order respects both inputs: if `a` precedes `b` in either
+    ///   result, `a` precedes `b` in the merged list, so static archives
+    ///   still come before the archives they depend on. Otherwise libraries
+    ///   keep the order in which they were first seen, `self` first. If the
+    ///   inputs contradict each other, the order of `self` wins for the
+    ///   libraries involved.
+    /// - `--start-group`/`--end-group` markers are dropped and, if either
+    ///   input had them, re-inserted around the merged static block.
+    /// - Remaining linker arguments and frameworks follow the libraries,
+    ///   deduplicated. `cflags`, `pc_files` and
+    ///   [`warnings`](Self::warnings) are concatenated with duplicates
+    ///   removed.
+    pub fn merge(self, other: PkgConfig) -> PkgConfig {
+        let is_group_marker = |flag: &LinkerFlag| {
+            matches!(flag, LinkerFlag::LinkerArg(arg)
+                if arg == "-Wl,--start-group" || arg == "-Wl,--end-group")
+        };
+        let grouped = self.libs.iter().chain(&other.libs).any(is_group_marker);
+
+        let mut search = Vec::new();
+        let mut trailing = Vec::new();
+        for flag in self.libs.iter().chain(&other.libs) {
+            match flag {
+                LinkerFlag::SearchPath(_) | LinkerFlag::FrameworkSearchPath(_) => {
+                    if !search.contains(flag) {
+                        search.push(flag.clone());
+                    }
+                }
+                LinkerFlag::LinkerArg(_) | LinkerFlag::Framework(_) => {
+                    if !is_group_marker(flag) && !trailing.contains(flag) {
+                        trailing.push(flag.clone());
+                    }
+                }
+                LinkerFlag::Library { .. } | LinkerFlag::VerbatimLibrary(_) => {}
+            }
+        }
+
+        let mut libs = search;
+        libs.extend(merge_link_order(&self.libs, &other.libs));
+        if grouped {
+            PkgConfigParser::insert_group_markers(&mut libs);
+        }
+        libs.extend(trailing);
+
+        let mut decisions: Vec<LinkDecision> = Vec::new();
+        for decision in self.decisions.into_iter().chain(other.decisions) {
+            match decisions.iter_mut().find(|d| d.0 == decision.0) {
+                Some(existing) if kind_rank(decision.1) > kind_rank(existing.1) => {
+                    *existing = decision;
+                }
+                Some(_) => {}
+                None => decisions.push(decision),
+            }
+        }
+
+        let mut cflags = self.cflags;
+        for flag in other.cflags {
+            if !cflags.contains(&flag) {
+                cflags.push(flag);
+            }
+        }
+
+        let mut pc_files = self.pc_files;
+        for file in other.pc_files {
+            if !pc_files.contains(&file) {
+                pc_files.push(file);
+            }
+        }
+
+        let mut warnings = self.warnings;
+        for warning in other.warnings {
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+
+        PkgConfig {
+            libs,
+            cflags,
+            pc_files,
+            tool: self.tool.or(other.tool),
+            decisions,
+            warnings,
+        }
+    }
+}
+
+/// Orders [`LinkKind`]s by strength for [`PkgConfig::merge`].
+fn kind_rank(kind: LinkKind) -> u8 {
+    match kind {
+        LinkKind::Default => 0,
+        LinkKind::Static => 1,
+        LinkKind::WholeArchive => 2,
+    }
+}
+
+/// Merges the library entries of two flag lists for [`PkgConfig::merge`].
+///
+/// Each input contributes "`a` before `b`" constraints between consecutive
+/// libraries, and the result is a topological sort that breaks ties (and
+/// cycles) by first appearance.
+fn merge_link_order(first: &[LinkerFlag], second: &[LinkerFlag]) -> Vec<LinkerFlag> {
+    let key = |flag: &LinkerFlag| match flag {
+        LinkerFlag::Library { name, .. } => Some(format!("-l{}", name)),
+        LinkerFlag::VerbatimLibrary(file) => Some(format!("-l:{}", file)),
+        _ => None,
+    };
+
+    let mut items: Vec<LinkerFlag> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut preds: Vec<BTreeSet<usize>> = Vec::new();
+
+    for input in [first, second] {
+        let mut prev = None;
+        for flag in input {
+            let Some(k) = key(flag) else { continue };
+            let i = match index.get(&k) {
+                Some(&i) => {
+                    if let (
+                        LinkerFlag::Library { kind, .. },
+                        LinkerFlag::Library { kind: new_kind, .. },
+                    ) = (&mut items[i], flag)
+                        && kind_rank(*new_kind) > kind_rank(*kind)
+                    {
+                        *kind = *new_kind;
+                    }
+                    i
+                }
+                None => {
+                    index.insert(k, items.len());
+                    items.push(flag.clone());
+                    preds.push(BTreeSet::new());
+                    items.len() - 1
+                }
+            };
+            if let Some(p) = prev
+                && p != i
+            {
+                preds[i].insert(p);
+            }
+            prev = Some(i);
+        }
+    }
+
+    let mut placed = vec![false; items.len()];
+    let mut order = Vec::with_capacity(items.len());
+    while order.len() < items.len() {
+        let ready = (0..items.len())
+            .find(|&i| !placed[i] && preds[i].iter().all(|&p| placed[p]))
+            .or_else(|| (0..items.len()).find(|&i| !placed[i]))
+            .unwrap_or_default();
+        placed[ready] = true;
+        order.push(ready);
+    }
+
+    order.into_iter().map(|i| items[i].clone()).collect()
+}
+
+/// Parser for pkg-config output that properly handles `--whole-archive` regions
+/// and auto-detects static library availability.
+///
+/// # Usage
+///
+/// ```no_run
+/// use pkgconf::PkgConfigParser;
+///
+/// let pkg = PkgConfigParser::new()
+///     .probe(["openssl", "libfoo"], None)
+///     .expect("pkg-config failed");
+///
+/// // Emit cargo linker directives (no_bundle=true for -sys crates)
+/// pkgconf::emit_cargo_metadata(&pkg.libs, true);
 ///
 /// // Get clang args for bindgen
 /// let clang_args = pkgconf::to_clang_args(&pkg.cflags);
@@ -324,14 +1654,125 @@ pub struct PkgConfigParser {
     /// the `.a` file exists. Default: `["/usr"]`.
     system_roots: Vec<PathBuf>,
 
-    /// Libraries that should always be linked with `+whole-archive`.
+    /// Directory for caching probe results, if caching is enabled.
+    ///
+    /// See [`cached`](Self::cached).
+    cache_dir: Option<PathBuf>,
+
+    /// Whether to wrap static libraries in `--start-group`/`--end-group`.
+    ///
+    /// See [`group_static_libs`](Self::group_static_libs).
+    group_static_libs: bool,
+
+    /// Explicit per-library link kinds.
     ///
-    /// This overrides the normal detection and forces these libraries
-    /// to use [`LinkKind::WholeArchive`] even if not in a `--whole-archive`
-    /// region from pkg-config. Useful for libraries with constructor
-    /// functions (like SPDK event subsystem registration) where the
-    /// pkg-config file doesn't include whole-archive flags.
-    force_whole_archive: HashSet<String>,
+    /// See [`override_link_kind`](Self::override_link_kind) and
+    /// [`force_whole_archive`](Self::force_whole_archive).
+    link_kind_overrides: BTreeMap<String, LinkKind>,
+
+    /// Whether to emit `-L` paths for the linker's default directories.
+    ///
+    /// See [`keep_default_lib_dirs`](Self::keep_default_lib_dirs).
+    keep_default_lib_dirs: bool,
+
+    /// Whether [`parse_cflags`](Self::parse_cflags) keeps `-m` flags.
+    ///
+    /// See [`keep_codegen_flags`](Self::keep_codegen_flags).
+    keep_codegen_flags: bool,
+
+    /// Whether to link against shared libraries only.
+    ///
+    /// See [`prefer_dynamic`](Self::prefer_dynamic).
+    prefer_dynamic: bool,
+
+    /// Whether to add an rpath for each `-L` directory.
+    ///
+    /// See [`rpath`](Self::rpath).
+    rpath: bool,
+
+    /// Whether to drop repeated search paths and linker arguments.
+    ///
+    /// See [`dedup`](Self::dedup).
+    dedup: bool,
+
+    /// Whether a `-Bstatic` library without any archive links statically.
+    ///
+    /// See [`allow_missing_static_archive`](Self::allow_missing_static_archive).
+    allow_missing_static_archive: bool,
+
+    /// Whether pkg-config keeps `-L` flags for system directories.
+    ///
+    /// See [`allow_system_libs`](Self::allow_system_libs).
+    allow_system_libs: bool,
+
+    /// Whether to add the compiler's library directories to the system roots.
+    ///
+    /// See [`detect_system_roots`](Self::detect_system_roots).
+    detect_system_roots: bool,
+
+    /// Roots from `PKGCONF_SYSTEM_ROOTS` or the compiler, computed on first use.
+    detected_roots: OnceLock<Vec<PathBuf>>,
+
+    /// Oldest accepted pkg-config version.
+    ///
+    /// See [`min_tool_version`](Self::min_tool_version).
+    min_tool_version: String,
+
+    /// Oldest accepted version of each probed package.
+    ///
+    /// See [`atleast_version`](Self::atleast_version).
+    min_package_version: Option<String>,
+
+    /// The pkg-config executable, overriding the environment.
+    ///
+    /// See [`pkg_config_exe`](Self::pkg_config_exe).
+    pkg_config_exe: Option<String>,
+
+    /// Root that `-L` directories are resolved against on the host.
+    ///
+    /// See [`sysroot`](Self::sysroot).
+    sysroot: Option<PathBuf>,
+
+    /// The toolchain family to detect static archives for.
+    ///
+    /// See [`target_env`](Self::target_env).
+    target_env: TargetEnv,
+}
+
+/// Linker regions open at a `-l` flag.
+#[derive(Clone, Copy)]
+struct Region {
+    /// Between `--whole-archive` and `--no-whole-archive`.
+    whole_archive: bool,
+    /// Between `-Bstatic` and `-Bdynamic`.
+    static_only: bool,
+}
+
+/// Libraries seen so far while parsing, for deduplication and upgrades.
+#[derive(Default)]
+struct LibraryState {
+    /// Library name → (index in flags, index in decisions)
+    indices: HashMap<String, (usize, usize)>,
+    decisions: Vec<LinkDecision>,
+}
+
+impl LibraryState {
+    /// Appends a new library flag and records the decision.
+    fn push(
+        &mut self,
+        flags: &mut Vec<LinkerFlag>,
+        name: &str,
+        kind: LinkKind,
+        reason: DecisionReason,
+    ) {
+        self.indices
+            .insert(name.to_string(), (flags.len(), self.decisions.len()));
+        flags.push(LinkerFlag::Library {
+            name: name.to_string(),
+            kind,
+        });
+        self.decisions.push((name.to_string(), kind, reason));
+    }
 }
 
 impl Default for PkgConfigParser {
@@ -345,11 +1786,28 @@ impl PkgConfigParser {
     ///
     /// Defaults:
     /// - `system_roots`: `["/usr"]`
-    /// - `force_whole_archive`: `[]` (empty)
+    /// - `override_link_kind`: none
+    /// - `target_env`: [`TargetEnv::from_env`]
     pub fn new() -> Self {
         Self {
             system_roots: vec![PathBuf::from("/usr")],
-            force_whole_archive: HashSet::new(),
+            cache_dir: None,
+            group_static_libs: false,
+            link_kind_overrides: BTreeMap::new(),
+            keep_default_lib_dirs: false,
+            keep_codegen_flags: false,
+            prefer_dynamic: false,
+            rpath: false,
+            dedup: true,
+            allow_missing_static_archive: false,
+            allow_system_libs: false,
+            detect_system_roots: false,
+            detected_roots: OnceLock::new(),
+            min_tool_version: DEFAULT_MIN_TOOL_VERSION.to_string(),
+            min_package_version: None,
+            pkg_config_exe: None,
+            sysroot: None,
+            target_env: TargetEnv::from_env(),
         }
     }
 
@@ -380,80 +1838,495 @@ impl PkgConfigParser {
         self
     }
 
-    /// Sets libraries that should always use `+whole-archive`.
+    /// Adds the active C compiler's library directories to the system roots.
     ///
-    /// These libraries will be linked with [`LinkKind::WholeArchive`] even if
-    /// they don't appear inside a `--whole-archive` region in the pkg-config
-    /// output. This is necessary for libraries that use constructor functions
-    /// (like `__attribute__((constructor))` or DPDK's `RTE_INIT` macros)
-    /// where the symbols would otherwise be discarded by the linker.
+    /// Runs `$CC -print-search-dirs` (`cc` if `CC` is unset) and treats each
+    /// `libraries:` directory as a system root, so toolchain libraries under
+    /// `/nix/store`, `/opt/homebrew` or a cross sysroot link dynamically.
+    /// The compiler is run once, on first use, and the result is kept for
+    /// the lifetime of this parser.
+    ///
+    /// If `PKGCONF_SYSTEM_ROOTS` is set (a `PATH`-style list), its
+    /// directories are used instead of asking the compiler. That variable
+    /// applies even without this setting.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use pkgconf::PkgConfigParser;
     ///
-    /// // Force whole-archive for libraries with constructor functions
-    /// let parser = PkgConfigParser::new()
-    ///     .force_whole_archive([
-    ///         "mylib_with_constructors",
-    ///     ]);
+    /// let parser = PkgConfigParser::new().detect_system_roots();
     /// ```
-    pub fn force_whole_archive<I, S>(mut self, libs: I) -> Self
-    where
-        I: IntoIterator<Item = S>,
-        S: AsRef<str>,
-    {
-        self.force_whole_archive = libs.into_iter().map(|s| s.as_ref().to_string()).collect();
+    pub fn detect_system_roots(mut self) -> Self {
+        self.detect_system_roots = true;
         self
     }
 
-    /// Runs `pkg-config` with the given arguments and returns the raw output.
+    /// Roots from `PKGCONF_SYSTEM_ROOTS` or, with
+    /// [`detect_system_roots`](Self::detect_system_roots), the compiler.
+    fn detected_system_roots(&self) -> &[PathBuf] {
+        self.detected_roots.get_or_init(|| {
+            if let Some(roots) = std::env::var_os(ENV_SYSTEM_ROOTS) {
+                return std::env::split_paths(&roots)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .collect();
+            }
+            if !self.detect_system_roots {
+                return Vec::new();
+            }
+            compiler_search_dirs(&std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        })
+    }
+
+    /// Sets the oldest pkg-config version [`probe`](Self::probe) accepts.
     ///
-    /// # Arguments
+    /// Older versions do not keep `--static` flags in dependency order.
     ///
-    /// * `args` - Arguments to pass before the package names (e.g., `["--static", "--libs"]`)
-    /// * `packages` - Package names to query (e.g., `["spdk_env_dpdk", "libdpdk"]`)
-    /// * `pkg_config_path` - Optional path to set as `PKG_CONFIG_PATH` environment variable
+    /// Default: `"0.29"`
+    pub fn min_tool_version(mut self, version: &str) -> Self {
+        self.min_tool_version = version.to_string();
+        self
+    }
+
+    /// Sets the pkg-config executable to run, e.g.
+    /// `aarch64-linux-gnu-pkg-config` when cross-compiling.
+    ///
+    /// Without this, `$TARGET_PKG_CONFIG` is used when cross-compiling
+    /// (cargo's `TARGET` differs from `HOST`), then `$PKG_CONFIG`, then
+    /// `pkg-config` from `PATH`. Applies to every query of this parser; the
+    /// associated [`run_pkg_config`](Self::run_pkg_config) helpers always
+    /// use the environment.
+    pub fn pkg_config_exe(mut self, program: &str) -> Self {
+        self.pkg_config_exe = Some(program.to_string());
+        self
+    }
+
+    /// The pkg-config executable this parser runs.
+    fn program(&self) -> String {
+        self.pkg_config_exe
+            .clone()
+            .unwrap_or_else(pkg_config_program)
+    }
+
+    /// Sets the target sysroot that `-L` directories live under on the host.
+    ///
+    /// When cross-compiling, pkg-config may report library directories as
+    /// the target sees them (`-L/usr/lib`) while the files are at
+    /// `<sysroot>/usr/lib` on the build machine. With a sysroot, checks for
+    /// library files look under `<sysroot>` (directories already inside it,
+    /// as pkg-config prints them with `PKG_CONFIG_SYSROOT_DIR`, are used
+    /// as-is). Emitted `rustc-link-search` directives and system root
+    /// comparisons still use the paths pkg-config reported.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let pkg = PkgConfigParser::new()
+    ///     .pkg_config_exe("aarch64-linux-gnu-pkg-config")
+    ///     .sysroot("/sysroots/aarch64")
+    ///     .probe(["spdk_env_dpdk"], None)
+    ///     .unwrap();
+    /// ```
+    pub fn sysroot(mut self, sysroot: impl Into<PathBuf>) -> Self {
+        self.sysroot = Some(sysroot.into());
+        self
+    }
+
+    /// Where the library directory `dir` is on the host, given the
+    /// [`sysroot`](Self::sysroot).
+    fn host_dir(&self, dir: &Path) -> PathBuf {
+        match &self.sysroot {
+            Some(sysroot) if dir.is_absolute() && !dir.starts_with(sysroot) => {
+                sysroot.join(dir.strip_prefix("/").unwrap_or(dir))
+            }
+            _ => dir.to_path_buf(),
+        }
+    }
+
+    /// Makes [`probe`](Self::probe) require every package to be at least
+    /// `version`.
+    ///
+    /// Each package is checked with `pkg-config --atleast-version` before
+    /// anything else is queried, so an outdated install fails with
+    /// [`ProbeError::PackageTooOld`] naming the version found rather than
+    /// with a confusing error later in the build.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let pkg = PkgConfigParser::new()
+    ///     .atleast_version("24.01")
+    ///     .probe(["spdk_env_dpdk"], None)
+    ///     .expect("SPDK 24.01 or newer is required");
+    /// ```
+    pub fn atleast_version(mut self, version: &str) -> Self {
+        self.min_package_version = Some(version.to_string());
+        self
+    }
+
+    /// Sets libraries that should always use `+whole-archive`.
+    ///
+    /// These libraries will be linked with [`LinkKind::WholeArchive`] even if
+    /// they don't appear inside a `--whole-archive` region in the pkg-config
+    /// output. This is necessary for libraries that use constructor functions
+    /// (like `__attribute__((constructor))` or DPDK's `RTE_INIT` macros)
+    /// where the symbols would otherwise be discarded by the linker.
+    ///
+    /// Shorthand for [`override_link_kind`](Self::override_link_kind) with
+    /// [`LinkKind::WholeArchive`] for each library, so it follows the same
+    /// rules: it applies whether or not a static archive is found, beats
+    /// [`prefer_dynamic`](Self::prefer_dynamic) and `-Wl,-Bstatic` regions,
+    /// and is reported as [`DecisionReason::Override`]. Repeated calls (of
+    /// either method) add to the overrides; the last one for a library wins.
+    ///
+    /// Unlike earlier versions, [`prefer_dynamic`](Self::prefer_dynamic) no
+    /// longer downgrades these libraries to [`LinkKind::Default`]. Builds
+    /// that switch to shared libraries should stop forcing whole-archive, as
+    /// in the example.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let dynamic = std::env::var("SPDK_DYNAMIC").is_ok_and(|v| v == "1");
+    /// // Shared libraries keep their constructors, so only force static builds
+    /// let constructors: &[&str] = if dynamic { &[] } else { &["mylib_with_constructors"] };
+    /// let parser = PkgConfigParser::new()
+    ///     .force_whole_archive(constructors)
+    ///     .prefer_dynamic(dynamic);
+    /// ```
+    pub fn force_whole_archive<I, S>(self, libs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.override_link_kind(
+            libs.into_iter()
+                .map(|name| (name.as_ref().to_string(), LinkKind::WholeArchive)),
+        )
+    }
+
+    /// Forces specific libraries to a given [`LinkKind`].
+    ///
+    /// Overrides take precedence over [`prefer_dynamic`](Self::prefer_dynamic),
+    /// `-Wl,-Bstatic` and whole-archive regions and static detection; only
+    /// the `SPDK_PKGCONF_*` environment variables beat them. An overridden
+    /// library is never upgraded to [`LinkKind::WholeArchive`] when it
+    /// reappears in a whole-archive region. Repeated calls add to the
+    /// existing overrides, and a later override for the same library
+    /// (including one from [`force_whole_archive`](Self::force_whole_archive))
+    /// replaces the earlier one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pkgconf::{LinkKind, PkgConfigParser};
+    ///
+    /// // Use the system libisal.so even though /opt ships libisal.a
+    /// let parser = PkgConfigParser::new()
+    ///     .override_link_kind([("isal", LinkKind::Default)]);
+    /// ```
+    pub fn override_link_kind<I, S>(mut self, libs: I) -> Self
+    where
+        I: IntoIterator<Item = (S, LinkKind)>,
+        S: AsRef<str>,
+    {
+        self.link_kind_overrides.extend(
+            libs.into_iter()
+                .map(|(name, kind)| (name.as_ref().to_string(), kind)),
+        );
+        self
+    }
+
+    /// Keeps `-L` flags for the linker's default directories.
+    ///
+    /// By default [`parse`](Self::parse) drops [`LinkerFlag::SearchPath`]s for
+    /// `/usr/lib`, `/usr/lib64` and `/lib`, since the linker searches them
+    /// anyway. They are still used for static library detection.
+    ///
+    /// Default: `false`
+    pub fn keep_default_lib_dirs(mut self, keep: bool) -> Self {
+        self.keep_default_lib_dirs = keep;
+        self
+    }
+
+    /// Runs pkg-config with `PKG_CONFIG_ALLOW_SYSTEM_LIBS=1`.
+    ///
+    /// pkg-config normally strips `-L` flags for its system library
+    /// directories. With this enabled it reports them, and
+    /// [`parse`](Self::parse) keeps them too, as if
+    /// [`keep_default_lib_dirs`](Self::keep_default_lib_dirs) were set, so
+    /// the directories are not filtered a second time.
+    /// [`system_roots`](Self::system_roots) still decide whether libraries
+    /// found there link statically.
+    ///
+    /// When disabled, the variable is inherited from the environment.
+    ///
+    /// Default: `false`
+    pub fn allow_system_libs(mut self, enabled: bool) -> Self {
+        self.allow_system_libs = enabled;
+        self
+    }
+
+    /// Keeps target code generation flags (`-m64`, `-march=...`) in cflags.
+    ///
+    /// By default [`parse_cflags`](Self::parse_cflags) drops every `-m` flag,
+    /// so bindgen uses clang's defaults for the host. Enable this when the C
+    /// build's flags change type sizes or struct layouts, so the generated
+    /// bindings match. They are returned as [`CompilerFlag::Codegen`].
+    ///
+    /// Default: `false`
+    pub fn keep_codegen_flags(mut self, keep: bool) -> Self {
+        self.keep_codegen_flags = keep;
+        self
+    }
+
+    /// Links every library dynamically, for faster development builds.
+    ///
+    /// When enabled, [`probe`](Self::probe) runs `pkg-config --libs` without
+    /// `--static`, so `Libs.private` dependencies of shared libraries are not
+    /// listed, and [`parse`](Self::parse) skips `.a` detection: every library
+    /// becomes [`LinkKind::Default`] with [`DecisionReason::PreferDynamic`].
+    ///
+    /// Libraries in a `--whole-archive` region are downgraded too. That is
+    /// safe: whole-archive only exists to stop the linker dropping
+    /// constructor objects from a static archive, and a shared library
+    /// always keeps (and runs) its constructors. Explicit choices via
+    /// [`override_link_kind`](Self::override_link_kind) (including
+    /// [`force_whole_archive`](Self::force_whole_archive), which is an
+    /// override) or the `SPDK_PKGCONF_*` environment variables still win,
+    /// so drop forced whole-archive libraries when enabling this.
+    ///
+    /// Pair with [`rpath`](Self::rpath) so the binary finds the `.so` files
+    /// outside the system directories.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let dev = std::env::var("SPDK_DYNAMIC").is_ok_and(|v| v == "1");
+    /// let parser = PkgConfigParser::new().prefer_dynamic(dev).rpath(dev);
+    /// ```
+    pub fn prefer_dynamic(mut self, enabled: bool) -> Self {
+        self.prefer_dynamic = enabled;
+        self
+    }
+
+    /// Adds `-Wl,-rpath,<dir>` after each emitted [`LinkerFlag::SearchPath`].
+    ///
+    /// Lets a dynamically linked binary find its shared libraries without
+    /// `LD_LIBRARY_PATH`. The linker's default directories get no rpath.
+    ///
+    /// Default: `false`
+    pub fn rpath(mut self, enabled: bool) -> Self {
+        self.rpath = enabled;
+        self
+    }
+
+    /// Drops repeated [`LinkerFlag::SearchPath`],
+    /// [`LinkerFlag::FrameworkSearchPath`] and [`LinkerFlag::LinkerArg`]
+    /// entries, keeping the first occurrence of each.
+    ///
+    /// Probing many packages repeats the same `-L` directories once per
+    /// package, which would otherwise print one `rustc-link-search` line
+    /// each. Disable this to keep them in pkg-config's raw order; libraries
+    /// are deduplicated either way.
+    ///
+    /// Default: `true`
+    pub fn dedup(mut self, enabled: bool) -> Self {
+        self.dedup = enabled;
+        self
+    }
+
+    /// Links a library in a `-Wl,-Bstatic` region statically even when no
+    /// `lib<name>.a` is found.
+    ///
+    /// By default such a library fails [`try_parse`](Self::try_parse) with
+    /// [`ProbeError::StaticArchiveNotFound`], since the `.pc` file asks for a
+    /// static link that cannot work. Enable this when the archive is only
+    /// visible to the linker (e.g. in its own default search path on the
+    /// target), so the library is emitted as [`LinkKind::Static`] and the
+    /// linker reports it if it is really missing. An archive found only under
+    /// the [`system_roots`](Self::system_roots) still links dynamically.
+    ///
+    /// Default: `false`
+    pub fn allow_missing_static_archive(mut self, enabled: bool) -> Self {
+        self.allow_missing_static_archive = enabled;
+        self
+    }
+
+    /// Sets the toolchain family used for static archive detection.
+    ///
+    /// On [`TargetEnv::Msvc`] a library is static if `<name>.lib` exists,
+    /// and on [`TargetEnv::WindowsGnu`] if `lib<name>.a` or `<name>.lib`
+    /// does. System roots on Windows targets may use drive letters and
+    /// either slash (`C:\msys64\mingw64`).
+    ///
+    /// Default: [`TargetEnv::from_env`], which is correct inside a build
+    /// script.
+    pub fn target_env(mut self, target: TargetEnv) -> Self {
+        self.target_env = target;
+        self
+    }
+
+    /// Wraps the static library block in `-Wl,--start-group`/`-Wl,--end-group`.
+    ///
+    /// When enabled, [`parse`](Self::parse) inserts a
+    /// [`LinkerFlag::LinkerArg`] for `--start-group` before the first
+    /// [`LinkKind::Static`] or [`LinkKind::WholeArchive`] library and one for
+    /// `--end-group` after the last. This lets the linker resolve circular
+    /// dependencies between SPDK and DPDK archives. Link kinds inside the
+    /// group are unchanged.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let parser = PkgConfigParser::new().group_static_libs(true);
+    /// ```
+    pub fn group_static_libs(mut self, enabled: bool) -> Self {
+        self.group_static_libs = enabled;
+        self
+    }
+
+    /// Enables caching of [`probe`](Self::probe) results in `cache_dir`.
+    ///
+    /// The cache is keyed on the package list, the `pkg_config_path` argument,
+    /// the `PKG_CONFIG_*` environment variables and the parser settings. A
+    /// cached result is reused only if the key matches and the `.pc` files,
+    /// static archives and `-L` directories it was derived from still have
+    /// the same modification times. Otherwise pkg-config is run again and
+    /// the cache is rewritten.
+    ///
+    /// `cache_dir` is typically `OUT_DIR`. Pair this with
+    /// [`emit_rerun_directives`](Self::emit_rerun_directives) so cargo re-runs
+    /// the build script when the environment changes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let out_dir = std::env::var("OUT_DIR").unwrap();
+    /// let parser = PkgConfigParser::new().cached(&out_dir);
+    /// parser.emit_rerun_directives();
+    /// let pkg = parser.probe(["spdk_env_dpdk"], None).expect("pkg-config failed");
+    /// ```
+    pub fn cached<P: Into<PathBuf>>(mut self, cache_dir: P) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Emits `cargo:rerun-if-env-changed` directives for every environment
+    /// variable that influences pkg-config output.
+    ///
+    /// This includes the link override variables emitted by
+    /// [`emit_rerun_env`](Self::emit_rerun_env).
+    pub fn emit_rerun_directives(&self) {
+        for var in PKG_CONFIG_ENV_VARS {
+            println!("cargo:rerun-if-env-changed={var}");
+        }
+        self.emit_rerun_env();
+    }
+
+    /// Emits `cargo:rerun-if-env-changed` directives for the `SPDK_PKGCONF_*`
+    /// link override variables.
+    ///
+    /// See the [crate-level docs](crate#environment-overrides).
+    pub fn emit_rerun_env(&self) {
+        for var in LINK_OVERRIDE_ENV_VARS {
+            println!("cargo:rerun-if-env-changed={var}");
+        }
+        if self.detect_system_roots {
+            println!("cargo:rerun-if-env-changed=CC");
+        }
+    }
+
+    /// Runs `pkg-config` with the given arguments and returns the raw output.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - Arguments to pass before the package names (e.g., `["--static", "--libs"]`)
+    /// * `packages` - Package names to query (e.g., `["spdk_env_dpdk", "libdpdk"]`)
+    /// * `pkg_config_path` - Optional path to set as `PKG_CONFIG_PATH` environment variable
     ///
     /// # Errors
     ///
-    /// Returns an error if pkg-config is not found or if any package is not found.
+    /// As for [`run_pkg_config`](Self::run_pkg_config).
     fn run_pkg_config_raw<I, S>(
+        program: &str,
         args: &[&str],
         packages: I,
         pkg_config_path: Option<&str>,
-    ) -> Result<String, String>
+        allow_system_libs: bool,
+    ) -> Result<String, ProbeError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let packages: Vec<String> = packages
-            .into_iter()
-            .map(|s| s.as_ref().to_string())
-            .collect();
-
-        let mut cmd = Command::new("pkg-config");
+        let output =
+            Self::pkg_config_command(program, args, packages, pkg_config_path, allow_system_libs)
+                .output()
+                .map_err(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => ProbeError::ToolNotFound {
+                        program: program.to_string(),
+                    },
+                    _ => ProbeError::Spawn(e.to_string()),
+                })?;
 
-        if let Some(path) = pkg_config_path {
-            cmd.env("PKG_CONFIG_PATH", path);
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            return Err(match error::parse_package_not_found(&stderr) {
+                Some(package) => ProbeError::PackageNotFound { package, stderr },
+                None => ProbeError::ExitFailure {
+                    status: output.status,
+                    stderr,
+                },
+            });
         }
 
-        cmd.args(args);
-        cmd.args(&packages);
+        String::from_utf8(output.stdout).map_err(|e| ProbeError::InvalidOutput(e.to_string()))
+    }
 
-        let output = cmd
-            .output()
-            .map_err(|e| format!("Failed to run pkg-config: {}", e))?;
+    /// Builds the `pkg-config` command for [`run_pkg_config_raw`](Self::run_pkg_config_raw).
+    ///
+    /// Sets `PKG_CONFIG_ALLOW_SYSTEM_LIBS=1` if `allow_system_libs`;
+    /// otherwise the variable is inherited from the environment.
+    fn pkg_config_command<I, S>(
+        program: &str,
+        args: &[&str],
+        packages: I,
+        pkg_config_path: Option<&str>,
+        allow_system_libs: bool,
+    ) -> Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut cmd = Command::new(program);
 
-        if !output.status.success() {
-            return Err(format!(
-                "pkg-config failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
+        if let Some(path) = pkg_config_path {
+            cmd.env("PKG_CONFIG_PATH", path);
+        }
+        if allow_system_libs {
+            cmd.env("PKG_CONFIG_ALLOW_SYSTEM_LIBS", "1");
         }
 
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        cmd.arg("--print-errors");
+        cmd.args(args);
+        cmd.args(packages.into_iter().map(|s| s.as_ref().to_string()));
+        cmd
     }
 
     /// Runs `pkg-config --static --libs` and returns the raw output.
@@ -465,16 +2338,26 @@ impl PkgConfigParser {
     ///
     /// # Errors
     ///
-    /// Returns an error if pkg-config is not found or if any package is not found.
+    /// - [`ProbeError::ToolNotFound`] if the pkg-config executable does not exist
+    /// - [`ProbeError::Spawn`] if it can't be run
+    /// - [`ProbeError::PackageNotFound`] if it reports a package as not found
+    /// - [`ProbeError::ExitFailure`] if it fails for another reason
+    /// - [`ProbeError::InvalidOutput`] if its output is not valid UTF-8
     pub fn run_pkg_config<I, S>(
         packages: I,
         pkg_config_path: Option<&str>,
-    ) -> Result<String, String>
+    ) -> Result<String, ProbeError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        Self::run_pkg_config_raw(&["--static", "--libs"], packages, pkg_config_path)
+        Self::run_pkg_config_raw(
+            &pkg_config_program(),
+            &["--static", "--libs"],
+            packages,
+            pkg_config_path,
+            false,
+        )
     }
 
     /// Runs `pkg-config --cflags` and returns the raw output.
@@ -486,115 +2369,464 @@ impl PkgConfigParser {
     ///
     /// # Errors
     ///
-    /// Returns an error if pkg-config is not found or if any package is not found.
+    /// As for [`run_pkg_config`](Self::run_pkg_config).
     pub fn run_pkg_config_cflags<I, S>(
         packages: I,
         pkg_config_path: Option<&str>,
-    ) -> Result<String, String>
+    ) -> Result<String, ProbeError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        Self::run_pkg_config_raw(&["--cflags"], packages, pkg_config_path)
+        Self::run_pkg_config_raw(
+            &pkg_config_program(),
+            &["--cflags"],
+            packages,
+            pkg_config_path,
+            false,
+        )
+    }
+
+    /// Returns the value of the variable `var` defined in `package`'s `.pc`
+    /// file, e.g. `libdir`, `includedir` or `prefix`.
+    ///
+    /// Runs `pkg-config --variable=<var> <package>` and trims the output.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let parser = PkgConfigParser::new();
+    /// let prefix = parser.variable("spdk_env_dpdk", "prefix", None).unwrap();
+    /// let rpc_py = std::path::Path::new(&prefix).join("scripts/rpc.py");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - [`ProbeError::UndefinedVariable`] if `package` does not define `var`
+    ///   or defines it as empty
+    /// - Otherwise as for [`run_pkg_config`](Self::run_pkg_config), e.g.
+    ///   [`ProbeError::PackageNotFound`] if `package` is missing
+    pub fn variable(
+        &self,
+        package: &str,
+        var: &str,
+        pkg_config_path: Option<&str>,
+    ) -> Result<String, ProbeError> {
+        let output = Self::run_pkg_config_raw(
+            &self.program(),
+            &[&format!("--variable={}", var)],
+            [package],
+            pkg_config_path,
+            self.allow_system_libs,
+        )?;
+        let value = output.trim();
+        if value.is_empty() {
+            return Err(ProbeError::UndefinedVariable {
+                package: package.to_string(),
+                variable: var.to_string(),
+            });
+        }
+        Ok(value.to_string())
+    }
+
+    /// Returns the values of several variables of `package`, in the order of
+    /// `vars`.
+    ///
+    /// pkg-config reports one variable per call, so this runs it once per
+    /// variable and stops at the first error.
+    ///
+    /// # Errors
+    ///
+    /// As for [`variable`](Self::variable).
+    pub fn variables(
+        &self,
+        package: &str,
+        vars: &[&str],
+        pkg_config_path: Option<&str>,
+    ) -> Result<Vec<String>, ProbeError> {
+        vars.iter()
+            .map(|var| self.variable(package, var, pkg_config_path))
+            .collect()
     }
 
     /// Checks if a static library (`.a`) is available in a non-system directory.
     ///
-    /// Returns `true` if `lib<name>.a` exists in any of the provided directories
+    /// Returns `true` if `lib<name>.a` (or the [`TargetEnv`]'s equivalent,
+    /// such as `<name>.lib`) exists in any of the provided directories
     /// and that directory is not under a system root. This is used to decide
     /// whether to force static linking or let the linker find a shared library.
     fn is_static_available(&self, name: &str, dirs: &[PathBuf]) -> bool {
-        let libname = format!("lib{}.a", name);
+        let archives = self.target_env.static_archive_names(name);
 
         dirs.iter().any(|dir| {
-            let library_exists = dir.join(&libname).exists();
-            let is_system_dir = self.system_roots.iter().any(|sys| dir.starts_with(sys));
-            library_exists && !is_system_dir
+            let host_dir = self.host_dir(dir);
+            let library_exists = archives
+                .iter()
+                .any(|archive| host_dir.join(archive).exists());
+            library_exists && !self.is_system_dir(dir)
         })
     }
 
+    /// Library directories of the system roots, searched for `-Bstatic`
+    /// archives after the `-L` directories: each root itself, its `lib` and
+    /// `lib64`, and multiarch directories such as `lib/x86_64-linux-gnu`.
+    fn system_lib_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        for root in self.system_roots.iter().chain(self.detected_system_roots()) {
+            let lib = root.join("lib");
+            let mut multiarch: Vec<PathBuf> = std::fs::read_dir(self.host_dir(&lib))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().contains("-linux-"))
+                .map(|entry| lib.join(entry.file_name()))
+                .collect();
+            multiarch.sort();
+
+            for dir in [root.clone(), lib.clone(), root.join("lib64")]
+                .into_iter()
+                .chain(multiarch)
+            {
+                if !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+        dirs
+    }
+
+    /// Whether `dir` is under one of the system roots.
+    fn is_system_dir(&self, dir: &Path) -> bool {
+        self.system_roots
+            .iter()
+            .chain(self.detected_system_roots())
+            .any(|sys| is_under_root(dir, sys, self.target_env))
+    }
+
     /// Parse pkg-config output into structured linker flags.
     ///
     /// This function:
+    /// - Splits arguments like a shell, so escaped (`\ `) or quoted spaces
+    ///   in paths stay within one flag
     /// - Tracks `--whole-archive` and `--no-whole-archive` markers
+    /// - Canonicalizes and deduplicates `-L` paths, dropping the linker's
+    ///   default directories (see [`keep_default_lib_dirs`](Self::keep_default_lib_dirs)
+    ///   and [`dedup`](Self::dedup))
     /// - Checks if static libraries (.a) exist for each library
     /// - Libraries with .a in non-system dirs → Static or WholeArchive
     /// - Libraries without .a (or in system dirs) → Default (let linker find .so)
     /// - If a library appears first outside, then inside a whole-archive region,
     ///   it will be upgraded to WholeArchive.
+    /// - Libraries between `-Wl,-Bstatic` and `-Wl,-Bdynamic` are Static (or
+    ///   WholeArchive inside a whole-archive region) wherever their `.a` is,
+    ///   unless it is only under the system roots
+    /// - macOS `-F/path` and two-token `-framework Name` flags become
+    ///   [`LinkerFlag::FrameworkSearchPath`] and [`LinkerFlag::Framework`]
+    /// - Versioned shared objects (`-l:liblz4.so.1`) become
+    ///   [`LinkerFlag::VerbatimLibrary`]
+    /// - `SPDK_PKGCONF_*` environment overrides are applied last (see the
+    ///   [crate-level docs](crate#environment-overrides))
+    ///
+    /// # Panics
+    ///
+    /// Panics if a library in a `-Wl,-Bstatic` region has no `lib<name>.a`
+    /// and [`allow_missing_static_archive`](Self::allow_missing_static_archive)
+    /// is off. Use [`try_parse`](Self::try_parse) to handle that case.
     pub fn parse(&self, pkg_config_output: &str) -> Vec<LinkerFlag> {
+        self.try_parse(pkg_config_output)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`parse`](Self::parse), but returns an error instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProbeError::StaticArchiveNotFound`] if a library in a
+    /// `-Wl,-Bstatic` region has no `lib<name>.a` in any `-L` directory or
+    /// library directory (`lib`, `lib64`, multiarch `lib/<triple>`) of the
+    /// [`system_roots`](Self::system_roots), since it could not be linked
+    /// statically as the `.pc` file intends. See
+    /// [`allow_missing_static_archive`](Self::allow_missing_static_archive).
+    pub fn try_parse(&self, pkg_config_output: &str) -> Result<Vec<LinkerFlag>, ProbeError> {
+        self.parse_with_overrides(pkg_config_output, &EnvOverrides::from_env())
+    }
+
+    /// [`try_parse`](Self::try_parse) with explicit environment overrides.
+    fn parse_with_overrides(
+        &self,
+        pkg_config_output: &str,
+        overrides: &EnvOverrides,
+    ) -> Result<Vec<LinkerFlag>, ProbeError> {
+        Ok(self.parse_detailed(pkg_config_output, overrides)?.0)
+    }
+
+    /// Parses linker flags and also returns the per-library link decisions
+    /// and any warnings.
+    fn parse_detailed(
+        &self,
+        pkg_config_output: &str,
+        overrides: &EnvOverrides,
+    ) -> Result<ParsedLibs, ProbeError> {
+        self.parse_tokens(&split_args(pkg_config_output), overrides)
+    }
+
+    /// [`parse_detailed`](Self::parse_detailed) on already split arguments.
+    fn parse_tokens(
+        &self,
+        args: &[String],
+        overrides: &EnvOverrides,
+    ) -> Result<ParsedLibs, ProbeError> {
         let mut flags = Vec::new();
-        let mut seen_libs: HashSet<String> = HashSet::new();
-        // Track library indices for upgrading to WholeArchive if seen again in whole-archive region
-        let mut lib_indices: std::collections::HashMap<String, usize> =
-            std::collections::HashMap::new();
+        let mut libs = LibraryState::default();
         // Track whether we're inside a --whole-archive region from pkg-config
         let mut in_whole_archive_region = false;
+        // Libraries seen before the open --whole-archive region, if any
+        let mut whole_archive_start = 0;
+        // Track whether we're between -Bstatic and -Bdynamic
+        let mut in_static_region = false;
         // Collect library search directories from -L flags
         let mut lib_dirs: Vec<PathBuf> = Vec::new();
 
-        // First pass: collect all -L directories
-        for flag in pkg_config_output.split_whitespace() {
-            if let Some(path) = flag.strip_prefix("-L") {
-                lib_dirs.push(PathBuf::from(path));
+        // First pass: collect all -L directories (and those of archive paths
+        // inside -Wl, arguments), canonicalized and deduplicated
+        for flag in args {
+            let dir = match flag.strip_prefix("-Wl,") {
+                Some(wl_args) => {
+                    for (dir, _) in wl_args.split(',').filter_map(split_archive_path) {
+                        if let Some(dir) = dir {
+                            let dir = normalize_path(dir);
+                            if !lib_dirs.contains(&dir) {
+                                lib_dirs.push(dir);
+                            }
+                        }
+                    }
+                    None
+                }
+                None => flag.strip_prefix("-L"),
+            };
+            if let Some(path) = dir {
+                let path = normalize_path(path);
+                if !lib_dirs.contains(&path) {
+                    lib_dirs.push(path);
+                }
             }
         }
+        let mut emitted_dirs: HashSet<PathBuf> = HashSet::new();
 
         // Second pass: parse all flags
-        for flag in pkg_config_output.split_whitespace() {
+        let mut tokens = args.iter().map(String::as_str);
+        while let Some(flag) = tokens.next() {
+            let region = Region {
+                whole_archive: in_whole_archive_region,
+                static_only: in_static_region,
+            };
             if let Some(path) = flag.strip_prefix("-L") {
-                flags.push(LinkerFlag::SearchPath(path.to_string()));
-            } else if let Some(wl_args) = flag.strip_prefix("-Wl,") {
-                // Handle --whole-archive/--no-whole-archive state tracking
-                if wl_args.contains("--whole-archive") && !wl_args.contains("--no-whole-archive") {
-                    in_whole_archive_region = true;
-                } else if wl_args.contains("--no-whole-archive") {
-                    in_whole_archive_region = false;
-                }
-                // Pass through certain linker flags
-                if wl_args.contains("export-dynamic") || wl_args.contains("as-needed") {
-                    flags.push(LinkerFlag::LinkerArg(flag.to_string()));
+                self.push_search_path(&mut flags, &mut emitted_dirs, normalize_path(path));
+            } else if flag == "-framework" {
+                // Two-token form: the framework name is the next token
+                if let Some(name) = tokens.next() {
+                    flags.push(LinkerFlag::Framework(name.to_string()));
                 }
-                // Don't emit --whole-archive/--no-whole-archive - we handle via link-lib modifiers
-            } else if let Some(rest) = flag.strip_prefix("-l:") {
-                // Explicit static archive like -l:libfoo.a
-                let lib_name = rest
-                    .strip_prefix("lib")
-                    .unwrap_or(rest)
-                    .strip_suffix(".a")
-                    .unwrap_or(rest);
-
-                self.handle_library(
+            } else if let Some(path) = flag.strip_prefix("-F") {
+                self.push_unique(
                     &mut flags,
-                    &mut seen_libs,
-                    &mut lib_indices,
-                    lib_name,
-                    in_whole_archive_region,
-                    &lib_dirs,
+                    LinkerFlag::FrameworkSearchPath(path.to_string()),
                 );
-            } else if let Some(lib_name) = flag.strip_prefix("-l") {
-                self.handle_library(
+            } else if let Some(wl_args) = flag.strip_prefix("-Wl,") {
+                // Options apply in order, so one token can open a
+                // whole-archive region, name libraries and close it again.
+                // Don't emit --whole-archive/--no-whole-archive - we handle
+                // via link-lib modifiers
+                let mut options = wl_args.split(',');
+                while let Some(option) = options.next() {
+                    let region = Region {
+                        whole_archive: in_whole_archive_region,
+                        static_only: in_static_region,
+                    };
+                    match option {
+                        "--whole-archive" => {
+                            if !in_whole_archive_region {
+                                whole_archive_start = libs.decisions.len();
+                            }
+                            in_whole_archive_region = true;
+                        }
+                        "--no-whole-archive" => in_whole_archive_region = false,
+                        "-Bstatic" => in_static_region = true,
+                        "-Bdynamic" => in_static_region = false,
+                        // Two-argument form: the directory is the next option
+                        "-rpath" | "--rpath" => {
+                            if let Some(dir) = options.next() {
+                                self.push_rpath(&mut flags, dir);
+                            }
+                        }
+                        _ if option.starts_with("-l") => self.handle_library_flag(
+                            &mut flags, &mut libs, option, region, &lib_dirs, overrides,
+                        )?,
+                        _ => {
+                            if let Some(dir) = option
+                                .strip_prefix("-rpath=")
+                                .or_else(|| option.strip_prefix("--rpath="))
+                            {
+                                self.push_rpath(&mut flags, dir);
+                            } else if let Some((dir, lib_name)) = split_archive_path(option) {
+                                if let Some(dir) = dir {
+                                    self.push_search_path(
+                                        &mut flags,
+                                        &mut emitted_dirs,
+                                        normalize_path(dir),
+                                    );
+                                }
+                                self.handle_library(
+                                    &mut flags, &mut libs, lib_name, region, &lib_dirs, overrides,
+                                )?;
+                            } else if option.contains("export-dynamic")
+                                || option.contains("as-needed")
+                            {
+                                // Pass through certain linker flags
+                                self.push_unique(
+                                    &mut flags,
+                                    LinkerFlag::LinkerArg(format!("-Wl,{}", option)),
+                                );
+                            }
+                        }
+                    }
+                }
+            } else if flag.starts_with("-l") {
+                self.handle_library_flag(
+                    &mut flags, &mut libs, flag, region, &lib_dirs, overrides,
+                )?;
+            } else if flag == "-pthread" && !libs.indices.contains_key("pthread") {
+                libs.push(
                     &mut flags,
-                    &mut seen_libs,
-                    &mut lib_indices,
-                    lib_name,
-                    in_whole_archive_region,
-                    &lib_dirs,
+                    "pthread",
+                    LinkKind::Default,
+                    DecisionReason::NoStaticArchive,
                 );
-            } else if flag == "-pthread" && !seen_libs.contains("pthread") {
-                flags.push(LinkerFlag::Library {
-                    name: "pthread".to_string(),
-                    kind: LinkKind::Default,
-                });
-                seen_libs.insert("pthread".to_string());
             }
         }
 
-        flags
+        if self.group_static_libs {
+            Self::insert_group_markers(&mut flags);
+        }
+
+        let mut warnings = Vec::new();
+        if in_whole_archive_region {
+            // The region ends with the output either way, since the markers
+            // are not emitted; but everything after it was whole-archived
+            let names: Vec<&str> = libs.decisions[whole_archive_start..]
+                .iter()
+                .map(|(name, ..)| name.as_str())
+                .collect();
+            warnings.push(format!(
+                "-Wl,--whole-archive is never closed by -Wl,--no-whole-archive; \
+                 linking every library after it with +whole-archive ({})",
+                names.join(", ")
+            ));
+        }
+
+        Ok((flags, libs.decisions, warnings))
     }
 
-    /// Handles adding a library to the flags list, with deduplication and upgrade logic.
-    ///
+    /// Emits a `-L` directory (and its rpath) unless it is a default
+    /// directory or was already emitted (see [`dedup`](Self::dedup)).
+    fn push_search_path(
+        &self,
+        flags: &mut Vec<LinkerFlag>,
+        emitted_dirs: &mut HashSet<PathBuf>,
+        path: PathBuf,
+    ) {
+        let is_default = DEFAULT_LIB_DIRS.iter().any(|dir| path == Path::new(dir));
+        let keep_default = self.keep_default_lib_dirs || self.allow_system_libs;
+        if !keep_default && is_default {
+            return;
+        }
+        if emitted_dirs.insert(path.clone()) || !self.dedup {
+            flags.push(LinkerFlag::SearchPath(path.display().to_string()));
+            if self.rpath {
+                self.push_rpath(flags, &path.display().to_string());
+            }
+        }
+    }
+
+    /// Adds `-Wl,-rpath,<dir>`, see [`push_unique`](Self::push_unique).
+    fn push_rpath(&self, flags: &mut Vec<LinkerFlag>, dir: &str) {
+        self.push_unique(flags, LinkerFlag::LinkerArg(format!("-Wl,-rpath,{}", dir)));
+    }
+
+    /// Adds `flag` unless [`dedup`](Self::dedup) is enabled and it is
+    /// already present.
+    fn push_unique(&self, flags: &mut Vec<LinkerFlag>, flag: LinkerFlag) {
+        if !self.dedup || !flags.contains(&flag) {
+            flags.push(flag);
+        }
+    }
+
+    /// Handles a `-l<name>` or `-l:<file>` argument.
+    fn handle_library_flag(
+        &self,
+        flags: &mut Vec<LinkerFlag>,
+        libs: &mut LibraryState,
+        flag: &str,
+        region: Region,
+        lib_dirs: &[PathBuf],
+        overrides: &EnvOverrides,
+    ) -> Result<(), ProbeError> {
+        if let Some(rest) = flag.strip_prefix("-l:") {
+            if is_shared_object(rest) {
+                // Exact shared object like -l:liblz4.so.1
+                flags.push(LinkerFlag::VerbatimLibrary(rest.to_string()));
+                return Ok(());
+            }
+
+            // Explicit static archive like -l:libfoo.a
+            let stem = rest.strip_suffix(".a").unwrap_or(rest);
+            let lib_name = stem.strip_prefix("lib").unwrap_or(stem);
+
+            self.handle_library(flags, libs, lib_name, region, lib_dirs, overrides)
+        } else {
+            let lib_name = flag.strip_prefix("-l").unwrap_or(flag);
+            if is_shared_object(lib_name) {
+                // Malformed -lfoo.so.1: the linker would search for
+                // libfoo.so.1.so, so link the intended file verbatim
+                flags.push(LinkerFlag::VerbatimLibrary(format!("lib{}", lib_name)));
+                return Ok(());
+            }
+
+            self.handle_library(flags, libs, lib_name, region, lib_dirs, overrides)
+        }
+    }
+
+    /// Brackets the static library block with `--start-group`/`--end-group`.
+    fn insert_group_markers(flags: &mut Vec<LinkerFlag>) {
+        let is_static = |flag: &LinkerFlag| {
+            matches!(
+                flag,
+                LinkerFlag::Library {
+                    kind: LinkKind::Static | LinkKind::WholeArchive,
+                    ..
+                }
+            )
+        };
+
+        let Some(first) = flags.iter().position(is_static) else {
+            return;
+        };
+        let last = flags.iter().rposition(is_static).unwrap_or(first);
+
+        flags.insert(
+            last + 1,
+            LinkerFlag::LinkerArg("-Wl,--end-group".to_string()),
+        );
+        flags.insert(
+            first,
+            LinkerFlag::LinkerArg("-Wl,--start-group".to_string()),
+        );
+    }
+
+    /// Handles adding a library to the flags list, with deduplication and upgrade logic.
+    ///
     /// If the library was already seen, checks if it needs to be upgraded from
     /// [`LinkKind::Static`] to [`LinkKind::WholeArchive`] (when it reappears inside
     /// a whole-archive region). Otherwise, adds the library with the appropriate
@@ -602,47 +2834,98 @@ impl PkgConfigParser {
     fn handle_library(
         &self,
         flags: &mut Vec<LinkerFlag>,
-        seen_libs: &mut HashSet<String>,
-        lib_indices: &mut std::collections::HashMap<String, usize>,
+        libs: &mut LibraryState,
         lib_name: &str,
-        in_whole_archive_region: bool,
+        region: Region,
         lib_dirs: &[PathBuf],
-    ) {
-        if seen_libs.contains(lib_name) {
-            // Library already seen - check if we need to upgrade to WholeArchive
-            if in_whole_archive_region
-                && let Some(&idx) = lib_indices.get(lib_name)
-                && let LinkerFlag::Library { kind, .. } = &mut flags[idx]
-                && *kind == LinkKind::Static
+        overrides: &EnvOverrides,
+    ) -> Result<(), ProbeError> {
+        if let Some(&(flag_idx, decision_idx)) = libs.indices.get(lib_name) {
+            // Library already seen - upgrade to WholeArchive if it was only
+            // static by detection or -Bstatic (explicit choices are never upgraded)
+            let decision = &mut libs.decisions[decision_idx];
+            if region.whole_archive
+                && matches!(
+                    decision.2,
+                    DecisionReason::StaticAvailable | DecisionReason::StaticRegion
+                )
+                && let LinkerFlag::Library { kind, .. } = &mut flags[flag_idx]
             {
                 *kind = LinkKind::WholeArchive;
+                decision.1 = LinkKind::WholeArchive;
+                decision.2 = DecisionReason::WholeArchiveRegion;
             }
-            return;
+            return Ok(());
         }
 
-        // Determine link kind based on:
-        // 1. Is it forced to be whole-archive?
-        // 2. Is it in a whole-archive region?
-        // 3. Does a static library (.a) exist in a non-system directory?
-        let has_static = self.is_static_available(lib_name, lib_dirs);
-        let forced_whole_archive = self.force_whole_archive.contains(lib_name);
+        // Determine link kind, in order of precedence:
+        // 1. Per-library environment overrides (static, then whole-archive)
+        // 2. SPDK_PKGCONF_ALL_DYNAMIC
+        // 3. Builder override_link_kind (and force_whole_archive)
+        // 4. Builder prefer_dynamic
+        // 5. A -Bstatic region (the .a must exist, unless allowed missing;
+        //    an .a only under the system roots still links dynamically)
+        // 6. A pkg-config whole-archive region
+        // 7. Does a static library (.a) exist in a non-system directory?
+        let has_static = !self.prefer_dynamic && self.is_static_available(lib_name, lib_dirs);
 
-        let kind = if (in_whole_archive_region || forced_whole_archive) && has_static {
-            LinkKind::WholeArchive
+        let (kind, reason) = if overrides.static_libs.contains(lib_name) {
+            (LinkKind::Static, DecisionReason::Environment)
+        } else if overrides.whole_archive.contains(lib_name) && has_static {
+            (LinkKind::WholeArchive, DecisionReason::Environment)
+        } else if overrides.all_dynamic {
+            (LinkKind::Default, DecisionReason::Environment)
+        } else if let Some(&kind) = self.link_kind_overrides.get(lib_name) {
+            (kind, DecisionReason::Override)
+        } else if self.prefer_dynamic {
+            (LinkKind::Default, DecisionReason::PreferDynamic)
+        } else if region.static_only {
+            let searched: Vec<PathBuf> = lib_dirs
+                .iter()
+                .cloned()
+                .chain(self.system_lib_dirs())
+                .collect();
+            let archives = self.target_env.static_archive_names(lib_name);
+            let found: Vec<&PathBuf> = searched
+                .iter()
+                .filter(|dir| {
+                    let host_dir = self.host_dir(dir);
+                    archives
+                        .iter()
+                        .any(|archive| host_dir.join(archive).exists())
+                })
+                .collect();
+            let kind = if region.whole_archive {
+                LinkKind::WholeArchive
+            } else {
+                LinkKind::Static
+            };
+
+            if found.is_empty() {
+                if !self.allow_missing_static_archive {
+                    return Err(ProbeError::StaticArchiveNotFound {
+                        library: lib_name.to_string(),
+                        searched,
+                    });
+                }
+                (kind, DecisionReason::StaticRegion)
+            } else if found.iter().all(|dir| self.is_system_dir(dir)) {
+                // System roots keep system libraries on their shared objects
+                (LinkKind::Default, DecisionReason::NoStaticArchive)
+            } else {
+                (kind, DecisionReason::StaticRegion)
+            }
+        } else if region.whole_archive && has_static {
+            (LinkKind::WholeArchive, DecisionReason::WholeArchiveRegion)
         } else if has_static {
-            LinkKind::Static
+            (LinkKind::Static, DecisionReason::StaticAvailable)
         } else {
             // No .a found or only in system dirs - let linker find .so
-            LinkKind::Default
+            (LinkKind::Default, DecisionReason::NoStaticArchive)
         };
 
-        let idx = flags.len();
-        flags.push(LinkerFlag::Library {
-            name: lib_name.to_string(),
-            kind,
-        });
-        seen_libs.insert(lib_name.to_string());
-        lib_indices.insert(lib_name.to_string(), idx);
+        libs.push(flags, lib_name, kind, reason);
+        Ok(())
     }
 
     /// Parses `pkg-config --cflags` output into structured compiler flags.
@@ -651,42 +2934,82 @@ impl PkgConfigParser {
     /// - `-I/path` → [`CompilerFlag::IncludePath`]
     /// - `-DFOO` → [`CompilerFlag::Define`] `{ key: "FOO", value: None }`
     /// - `-DFOO=bar` → [`CompilerFlag::Define`] `{ key: "FOO", value: Some("bar") }`
+    /// - `-UFOO` → [`CompilerFlag::Undefine`] `("FOO")`
+    /// - `-isystem /path` → [`CompilerFlag::SystemIncludePath`]
+    /// - `-iquote /path` → [`CompilerFlag::QuoteIncludePath`]
+    /// - `-include file.h` → [`CompilerFlag::ForcedInclude`]
+    /// - `-pthread` → [`CompilerFlag::Pthread`]
+    /// - `-m64`, `-march=...` → [`CompilerFlag::Codegen`], only with
+    ///   [`keep_codegen_flags`](Self::keep_codegen_flags)
+    ///
+    /// The last three accept both the split form and the fused form
+    /// (`-isystem/path`).
     ///
-    /// Deduplicates flags (preserving first occurrence order).
-    /// Unknown flags are silently ignored.
+    /// Arguments are split and unescaped, and include directories are
+    /// canonicalized, like `-L` paths in [`parse`](Self::parse). Deduplicates flags (preserving first
+    /// occurrence order). Unknown flags are silently ignored.
     pub fn parse_cflags(&self, output: &str) -> Vec<CompilerFlag> {
         let mut flags = Vec::new();
         let mut seen = HashSet::new();
 
-        for token in output.split_whitespace() {
-            if let Some(path) = token.strip_prefix("-I") {
-                if seen.insert(token.to_string()) {
-                    flags.push(CompilerFlag::IncludePath(PathBuf::from(path)));
-                }
-            } else if let Some(define) = token.strip_prefix("-D")
-                && seen.insert(token.to_string())
-            {
-                if let Some((key, val)) = define.split_once('=') {
-                    flags.push(CompilerFlag::Define {
+        let args = split_args(output);
+        let mut tokens = args.iter().map(String::as_str);
+        while let Some(token) = tokens.next() {
+            let flag = if let Some(path) = token.strip_prefix("-I") {
+                CompilerFlag::IncludePath(normalize_path(path))
+            } else if let Some(define) = token.strip_prefix("-D") {
+                match define.split_once('=') {
+                    Some((key, val)) => CompilerFlag::Define {
                         key: key.to_string(),
                         value: Some(val.to_string()),
-                    });
-                } else {
-                    flags.push(CompilerFlag::Define {
+                    },
+                    None => CompilerFlag::Define {
                         key: define.to_string(),
                         value: None,
-                    });
+                    },
                 }
+            } else if let Some(key) = token.strip_prefix("-U") {
+                CompilerFlag::Undefine(key.to_string())
+            } else if let Some(path) = Self::path_operand(token, "-isystem", &mut tokens) {
+                CompilerFlag::SystemIncludePath(normalize_path(&path.to_string_lossy()))
+            } else if let Some(path) = Self::path_operand(token, "-iquote", &mut tokens) {
+                CompilerFlag::QuoteIncludePath(normalize_path(&path.to_string_lossy()))
+            } else if let Some(path) = Self::path_operand(token, "-include", &mut tokens) {
+                CompilerFlag::ForcedInclude(path)
+            } else if token == "-pthread" {
+                CompilerFlag::Pthread
+            } else if self.keep_codegen_flags && token.starts_with("-m") {
+                CompilerFlag::Codegen(token.to_string())
+            } else {
+                // Unknown flags (e.g., -std=c11) are silently ignored
+                continue;
+            };
+
+            if seen.insert(flag.to_clang_arg()) {
+                flags.push(flag);
             }
-            // Unknown flags (e.g., -std=c11) are silently ignored
         }
 
         flags
     }
 
+    /// Extracts the path of a `-isystem`-style option from either the fused
+    /// form (`-isystem/path`) or the split form (`-isystem /path`).
+    fn path_operand<'a>(
+        token: &str,
+        option: &str,
+        tokens: &mut impl Iterator<Item = &'a str>,
+    ) -> Option<PathBuf> {
+        match token.strip_prefix(option)? {
+            "" => tokens.next().map(PathBuf::from),
+            path => Some(PathBuf::from(path)),
+        }
+    }
+
     /// Runs pkg-config and parses both linker and compiler flags.
     ///
-    /// Executes `pkg-config --static --libs` and `pkg-config --cflags`
+    /// Executes `pkg-config --static --libs` (without `--static` under
+    /// [`prefer_dynamic`](Self::prefer_dynamic)) and `pkg-config --cflags`
     /// and returns the combined parsed result as a [`PkgConfig`].
     ///
     /// # Arguments
@@ -694,6 +3017,13 @@ impl PkgConfigParser {
     /// * `packages` - Package names to query
     /// * `pkg_config_path` - Optional `PKG_CONFIG_PATH` override
     ///
+    /// # Errors
+    ///
+    /// If pkg-config fails, each package is re-probed individually and a
+    /// [`ProbeError::MissingPackages`] lists which ones were not found.
+    /// With [`atleast_version`](Self::atleast_version), an older package
+    /// fails with [`ProbeError::PackageTooOld`].
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -713,19 +3043,589 @@ impl PkgConfigParser {
         &self,
         packages: I,
         pkg_config_path: Option<&str>,
-    ) -> Result<PkgConfig, String>
+    ) -> Result<PkgConfig, ProbeError>
     where
         I: IntoIterator<Item = S> + Clone,
         S: AsRef<str>,
     {
-        let libs_output = Self::run_pkg_config(packages.clone(), pkg_config_path)?;
-        let cflags_output = Self::run_pkg_config_cflags(packages, pkg_config_path)?;
+        let tool = self.check_tool()?;
+        let packages: Vec<String> = packages
+            .into_iter()
+            .map(|s| s.as_ref().to_string())
+            .collect();
+        self.check_package_versions(&packages, pkg_config_path)?;
+
+        let mut pkg = match &self.cache_dir {
+            Some(cache_dir) => {
+                let env: Vec<(String, Option<String>)> = PKG_CONFIG_ENV_VARS
+                    .iter()
+                    .chain(LINK_OVERRIDE_ENV_VARS)
+                    .map(|var| (var.to_string(), std::env::var(var).ok()))
+                    .collect();
+                self.probe_cached(cache_dir, &packages, pkg_config_path, &env)
+            }
+            None => self.probe_uncached(&packages, pkg_config_path),
+        }?;
+        pkg.tool = Some(tool);
+        Ok(pkg)
+    }
+
+    /// Checks that pkg-config can be run and is new enough.
+    ///
+    /// Runs the pkg-config executable (see
+    /// [`pkg_config_exe`](Self::pkg_config_exe)) with `--version` and
+    /// compares it against [`min_tool_version`](Self::min_tool_version).
+    /// Called by [`probe`](Self::probe) before any query, so a missing or
+    /// outdated tool is reported as such rather than as a failed query.
+    ///
+    /// # Errors
+    ///
+    /// - [`ProbeError::ToolNotFound`] if the executable does not exist
+    /// - [`ProbeError::ToolTooOld`] if it is older than the minimum
+    /// - [`ProbeError::Spawn`] or [`ProbeError::Failed`] if it can't be run
+    ///   or its version can't be parsed
+    pub fn check_tool(&self) -> Result<ToolInfo, ProbeError> {
+        Self::check_tool_at(&self.program(), &self.min_tool_version)
+    }
+
+    /// [`check_tool`](Self::check_tool) for an explicit executable.
+    fn check_tool_at(program: &str, min_version: &str) -> Result<ToolInfo, ProbeError> {
+        let not_found = || ProbeError::ToolNotFound {
+            program: program.to_string(),
+        };
+        let path = find_in_path(program).ok_or_else(not_found)?;
+
+        let output = match Command::new(&path).arg("--version").output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(not_found()),
+            Err(e) => return Err(ProbeError::Spawn(e.to_string())),
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let version = stdout.lines().next().unwrap_or_default().trim().to_string();
+        let parsed = match parse_version(&version) {
+            Some(parsed) if output.status.success() => parsed,
+            _ => {
+                return Err(ProbeError::Failed(format!(
+                    "`{} --version` did not report a version: {}",
+                    program,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+        };
+
+        if parse_version(min_version).is_some_and(|min| parsed < min) {
+            return Err(ProbeError::ToolTooOld {
+                version,
+                required: min_version.to_string(),
+            });
+        }
+
+        Ok(ToolInfo { path, version })
+    }
+
+    /// Returns the version of `package` (`pkg-config --modversion`).
+    ///
+    /// Use [`Version::parse`] to compare it.
+    ///
+    /// # Errors
+    ///
+    /// As for [`run_pkg_config`](Self::run_pkg_config), e.g.
+    /// [`ProbeError::PackageNotFound`] if `package` is missing.
+    pub fn modversion(
+        &self,
+        package: &str,
+        pkg_config_path: Option<&str>,
+    ) -> Result<String, ProbeError> {
+        let output = Self::run_pkg_config_raw(
+            &self.program(),
+            &["--modversion"],
+            [package],
+            pkg_config_path,
+            self.allow_system_libs,
+        )?;
+        Ok(output.trim().to_string())
+    }
+
+    /// Checks each package against [`atleast_version`](Self::atleast_version).
+    fn check_package_versions(
+        &self,
+        packages: &[String],
+        pkg_config_path: Option<&str>,
+    ) -> Result<(), ProbeError> {
+        let Some(required) = &self.min_package_version else {
+            return Ok(());
+        };
+        for package in packages {
+            let atleast = format!("--atleast-version={}", required);
+            let result = Self::run_pkg_config_raw(
+                &self.program(),
+                &[&atleast],
+                [package],
+                pkg_config_path,
+                self.allow_system_libs,
+            );
+            if result.is_ok() {
+                continue;
+            }
+            // A missing package is reported like any other failed query
+            let version = self
+                .modversion(package, pkg_config_path)
+                .map_err(|err| self.diagnose(packages, pkg_config_path, err))?;
+            return Err(ProbeError::PackageTooOld {
+                package: package.clone(),
+                version,
+                required: required.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Runs pkg-config and parses the output, bypassing the cache.
+    fn probe_uncached(
+        &self,
+        packages: &[String],
+        pkg_config_path: Option<&str>,
+    ) -> Result<PkgConfig, ProbeError> {
+        let program = self.program();
+        let libs_args: &[&str] = if self.prefer_dynamic {
+            &["--libs"]
+        } else {
+            &["--static", "--libs"]
+        };
+        let libs_output = Self::run_pkg_config_raw(
+            &program,
+            libs_args,
+            packages,
+            pkg_config_path,
+            self.allow_system_libs,
+        )
+        .map_err(|err| self.diagnose(packages, pkg_config_path, err))?;
+        let cflags_output =
+            Self::run_pkg_config_raw(&program, &["--cflags"], packages, pkg_config_path, false)
+                .map_err(|err| self.diagnose(packages, pkg_config_path, err))?;
+
+        let mut pkg = self.try_probe_from_output(&libs_output, &cflags_output)?;
+        pkg.pc_files = self.resolve_pc_files(packages, pkg_config_path);
+        Ok(pkg)
+    }
+
+    /// Builds a [`PkgConfig`] from previously captured pkg-config output,
+    /// without running pkg-config.
+    ///
+    /// `libs_output` is the output of `pkg-config --static --libs` (or
+    /// `--libs` with [`prefer_dynamic`](Self::prefer_dynamic)) and
+    /// `cflags_output` that of `pkg-config --cflags`. Both are parsed exactly
+    /// as [`probe`](Self::probe) parses them: static detection,
+    /// [`system_roots`](Self::system_roots),
+    /// [`force_whole_archive`](Self::force_whole_archive) and the environment
+    /// overrides all apply. `pc_files` and `tool` are left empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a library in a `-Wl,-Bstatic` region has no `lib<name>.a`.
+    /// Use [`try_probe_from_output`](Self::try_probe_from_output) to handle
+    /// that case.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// // Recorded with `pkg-config --static --libs` / `--cflags`
+    /// let libs = std::fs::read_to_string("spdk.libs").unwrap();
+    /// let cflags = std::fs::read_to_string("spdk.cflags").unwrap();
+    ///
+    /// let pkg = PkgConfigParser::new()
+    ///     .force_whole_archive(["rte_mempool_ring"])
+    ///     .probe_from_output(&libs, &cflags);
+    /// pkgconf::emit_cargo_metadata(&pkg.libs, true);
+    /// ```
+    pub fn probe_from_output(&self, libs_output: &str, cflags_output: &str) -> PkgConfig {
+        self.try_probe_from_output(libs_output, cflags_output)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`probe_from_output`](Self::probe_from_output), but returns an
+    /// error instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// As for [`try_parse`](Self::try_parse).
+    pub fn try_probe_from_output(
+        &self,
+        libs_output: &str,
+        cflags_output: &str,
+    ) -> Result<PkgConfig, ProbeError> {
+        let (libs, decisions, warnings) =
+            self.parse_detailed(libs_output, &EnvOverrides::from_env())?;
+
+        Ok(PkgConfig {
+            libs,
+            cflags: self.parse_cflags(cflags_output),
+            pc_files: Vec::new(),
+            tool: None,
+            decisions,
+            warnings,
+        })
+    }
+
+    /// [`try_probe_from_output`](Self::try_probe_from_output) with the
+    /// output read from `libs_path` and `cflags_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProbeError::ReadOutput`] if either file can't be read, or
+    /// an error from [`try_parse`](Self::try_parse).
+    pub fn probe_from_files(
+        &self,
+        libs_path: impl AsRef<Path>,
+        cflags_path: impl AsRef<Path>,
+    ) -> Result<PkgConfig, ProbeError> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path).map_err(|err| ProbeError::ReadOutput {
+                path: path.to_path_buf(),
+                message: err.to_string(),
+            })
+        };
+        let libs_output = read(libs_path.as_ref())?;
+        let cflags_output = read(cflags_path.as_ref())?;
+        self.try_probe_from_output(&libs_output, &cflags_output)
+    }
+
+    /// Like [`probe`](Self::probe), but queries `-L`, `-l` and other linker
+    /// flags separately.
+    ///
+    /// Runs `pkg-config --libs-only-L`, `--libs-only-l` and
+    /// `--libs-only-other` and checks each token of the full `--libs` output
+    /// against them: only tokens reported by one of the split queries are
+    /// parsed, in the order of the full output, so whole-archive regions are
+    /// still attributed correctly. A stray token in the other flags can't be
+    /// mistaken for a library, and a failure names the query that produced
+    /// it. Libraries or directories missing from the full output are
+    /// appended (directories first).
+    ///
+    /// Produces the same result as [`probe`](Self::probe) for well-formed
+    /// output. Results are not cached.
+    ///
+    /// # Errors
+    ///
+    /// As for [`probe`](Self::probe); [`ProbeError::Failed`] messages are
+    /// prefixed with the failing query.
+    pub fn probe_split<I, S>(
+        &self,
+        packages: I,
+        pkg_config_path: Option<&str>,
+    ) -> Result<PkgConfig, ProbeError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let tool = self.check_tool()?;
+        let packages: Vec<String> = packages
+            .into_iter()
+            .map(|s| s.as_ref().to_string())
+            .collect();
+        self.check_package_versions(&packages, pkg_config_path)?;
+
+        let program = self.program();
+        let run = |query: &str| {
+            let args: &[&str] = if self.prefer_dynamic {
+                &[query]
+            } else {
+                &["--static", query]
+            };
+            Self::run_pkg_config_raw(
+                &program,
+                args,
+                &packages,
+                pkg_config_path,
+                self.allow_system_libs,
+            )
+            .map_err(|err| match self.diagnose(&packages, pkg_config_path, err) {
+                ProbeError::Failed(msg) => ProbeError::Failed(format!("{}: {}", query, msg)),
+                err => err,
+            })
+        };
+        let dirs_output = run("--libs-only-L")?;
+        let names_output = run("--libs-only-l")?;
+        let other_output = run("--libs-only-other")?;
+        let libs_output = run("--libs")?;
+        let cflags_output =
+            Self::run_pkg_config_raw(&program, &["--cflags"], &packages, pkg_config_path, false)
+                .map_err(|err| self.diagnose(&packages, pkg_config_path, err))?;
+
+        let tokens = merge_split_libs(&libs_output, &dirs_output, &names_output, &other_output);
+        let (libs, decisions, warnings) = self.parse_tokens(&tokens, &EnvOverrides::from_env())?;
 
         Ok(PkgConfig {
-            libs: self.parse(&libs_output),
+            libs,
             cflags: self.parse_cflags(&cflags_output),
+            pc_files: self.resolve_pc_files(&packages, pkg_config_path),
+            tool: Some(tool),
+            decisions,
+            warnings,
+        })
+    }
+
+    /// Builds a [`PkgConfig`] from library directories, without pkg-config.
+    ///
+    /// For trees that have the libraries and headers but no `.pc` files,
+    /// such as an uninstalled in-source build. With no `.pc` file to supply
+    /// the link order, `libraries` must already be in it: each library
+    /// before the ones it depends on. Each must have an archive (a shared
+    /// library with [`prefer_dynamic`](Self::prefer_dynamic)) in one of
+    /// `lib_dirs`. `system_libs` follow them and are left to the linker's
+    /// default directories. Link kinds are then chosen as by
+    /// [`parse`](Self::parse), so [`force_whole_archive`](Self::force_whole_archive)
+    /// and the environment overrides apply.
+    ///
+    /// `include_dirs` become the [`CompilerFlag::IncludePath`] cflags.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProbeError::MissingArchives`] naming every library of
+    /// `libraries` that was not found.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use pkgconf::PkgConfigParser;
+    ///
+    /// let pkg = PkgConfigParser::new()
+    ///     .force_whole_archive(["spdk_bdev_malloc"])
+    ///     .probe_lib_dirs(
+    ///         &[PathBuf::from("/src/spdk/build/lib")],
+    ///         &[PathBuf::from("/src/spdk/include")],
+    ///         &["spdk_bdev_malloc", "spdk_bdev", "spdk_util"],
+    ///         &["uuid", "numa"],
+    ///     )
+    ///     .unwrap();
+    /// pkgconf::emit_cargo_metadata(&pkg.libs, true);
+    /// ```
+    pub fn probe_lib_dirs(
+        &self,
+        lib_dirs: &[PathBuf],
+        include_dirs: &[PathBuf],
+        libraries: &[&str],
+        system_libs: &[&str],
+    ) -> Result<PkgConfig, ProbeError> {
+        let missing: Vec<String> = libraries
+            .iter()
+            .filter(|name| !self.has_library_file(name, lib_dirs))
+            .map(|name| name.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(ProbeError::MissingArchives {
+                missing,
+                searched: lib_dirs.to_vec(),
+            });
+        }
+
+        let tokens: Vec<String> = lib_dirs
+            .iter()
+            .map(|dir| format!("-L{}", dir.display()))
+            .chain(
+                libraries
+                    .iter()
+                    .chain(system_libs)
+                    .map(|name| format!("-l{}", name)),
+            )
+            .collect();
+        let (libs, decisions, warnings) = self.parse_tokens(&tokens, &EnvOverrides::from_env())?;
+
+        Ok(PkgConfig {
+            libs,
+            cflags: include_dirs
+                .iter()
+                .map(|dir| CompilerFlag::IncludePath(dir.clone()))
+                .collect(),
+            pc_files: Vec::new(),
+            tool: None,
+            decisions,
+            warnings,
+        })
+    }
+
+    /// Whether `name` has a library file in one of `dirs`: a static archive,
+    /// or with [`prefer_dynamic`](Self::prefer_dynamic) a shared library.
+    fn has_library_file(&self, name: &str, dirs: &[PathBuf]) -> bool {
+        let files = if self.prefer_dynamic {
+            vec![format!("lib{}.so", name), format!("lib{}.dylib", name)]
+        } else {
+            self.target_env.static_archive_names(name)
+        };
+        dirs.iter().any(|dir| {
+            let host_dir = self.host_dir(dir);
+            files.iter().any(|file| host_dir.join(file).exists())
         })
     }
+
+    /// Explains a failed query by re-probing each package on its own.
+    ///
+    /// `err` is the error from the combined query. It is returned as-is if
+    /// pkg-config itself could not be run, and as [`ProbeError::Failed`] if
+    /// every package exists individually.
+    fn diagnose(
+        &self,
+        packages: &[String],
+        pkg_config_path: Option<&str>,
+        err: ProbeError,
+    ) -> ProbeError {
+        if matches!(
+            err,
+            ProbeError::ToolNotFound { .. } | ProbeError::Spawn(_) | ProbeError::InvalidOutput(_)
+        ) {
+            return err;
+        }
+
+        let mut missing = Vec::new();
+        let mut present = Vec::new();
+
+        for package in packages {
+            let mut cmd = Command::new(self.program());
+            if let Some(path) = pkg_config_path {
+                cmd.env("PKG_CONFIG_PATH", path);
+            }
+            let output = match cmd.args(["--exists", "--print-errors", package]).output() {
+                Ok(output) => output,
+                Err(e) => return ProbeError::Spawn(e.to_string()),
+            };
+
+            if output.status.success() {
+                present.push(package.clone());
+            } else {
+                let errors = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                missing.push((package.clone(), errors));
+            }
+        }
+
+        if missing.is_empty() {
+            return ProbeError::Failed(err.to_string());
+        }
+
+        ProbeError::MissingPackages {
+            missing,
+            present,
+            pkg_config_path: pkg_config_path
+                .map(str::to_string)
+                .or_else(|| std::env::var("PKG_CONFIG_PATH").ok()),
+        }
+    }
+
+    /// Locates the `.pc` files for `packages` and everything they require.
+    ///
+    /// Walks `Requires` and `Requires.private` via `pkg-config
+    /// --print-requires --print-requires-private` and resolves each package
+    /// against `PKG_CONFIG_PATH` followed by pkg-config's default search
+    /// path. Packages that cannot be found are skipped.
+    fn resolve_pc_files(&self, packages: &[String], pkg_config_path: Option<&str>) -> Vec<PathBuf> {
+        let env_path = std::env::var("PKG_CONFIG_PATH").ok();
+        let default_path = Self::run_pkg_config_raw(
+            &self.program(),
+            &["--variable", "pc_path"],
+            ["pkg-config"],
+            pkg_config_path,
+            false,
+        )
+        .unwrap_or_default();
+        let search_dirs: Vec<PathBuf> = pkg_config_path
+            .or(env_path.as_deref())
+            .into_iter()
+            .chain([default_path.trim()])
+            .flat_map(|path| path.split(':'))
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .collect();
+
+        let mut seen: HashSet<String> = packages.iter().cloned().collect();
+        let mut queue: Vec<String> = packages.to_vec();
+        let mut pc_files = Vec::new();
+
+        while let Some(package) = queue.pop() {
+            pc_files.extend(
+                search_dirs
+                    .iter()
+                    .map(|dir| dir.join(format!("{}.pc", package)))
+                    .find(|path| path.exists()),
+            );
+
+            let requires = Self::run_pkg_config_raw(
+                &self.program(),
+                &["--print-requires", "--print-requires-private"],
+                [&package],
+                pkg_config_path,
+                false,
+            )
+            .unwrap_or_default();
+            for line in requires.lines() {
+                // Lines look like "libfoo >= 1.0"
+                if let Some(name) = line.split_whitespace().next()
+                    && seen.insert(name.to_string())
+                {
+                    queue.push(name.to_string());
+                }
+            }
+        }
+
+        pc_files.sort();
+        pc_files
+    }
+
+    /// Probes through the on-disk cache in `cache_dir`.
+    ///
+    /// `env` is the snapshot of pkg-config environment variables used in
+    /// the cache key.
+    fn probe_cached(
+        &self,
+        cache_dir: &Path,
+        packages: &[String],
+        pkg_config_path: Option<&str>,
+        env: &[(String, Option<String>)],
+    ) -> Result<PkgConfig, ProbeError> {
+        let key = self.cache_key(packages, pkg_config_path, env);
+        if let Some(pkg) = cache::read(cache_dir, key) {
+            return Ok(pkg);
+        }
+
+        let pkg = self.probe_uncached(packages, pkg_config_path)?;
+
+        // A failed cache write only costs a re-probe next time
+        let _ = cache::write(cache_dir, key, &pkg.rerun_paths(), &pkg);
+
+        Ok(pkg)
+    }
+
+    /// Hashes every input that affects the result of [`probe`](Self::probe).
+    fn cache_key(
+        &self,
+        packages: &[String],
+        pkg_config_path: Option<&str>,
+        env: &[(String, Option<String>)],
+    ) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        packages.hash(&mut hasher);
+        pkg_config_path.hash(&mut hasher);
+        env.hash(&mut hasher);
+        self.system_roots.hash(&mut hasher);
+        self.detected_system_roots().hash(&mut hasher);
+        self.group_static_libs.hash(&mut hasher);
+        self.keep_default_lib_dirs.hash(&mut hasher);
+        self.keep_codegen_flags.hash(&mut hasher);
+        self.prefer_dynamic.hash(&mut hasher);
+        self.rpath.hash(&mut hasher);
+        self.dedup.hash(&mut hasher);
+        self.allow_missing_static_archive.hash(&mut hasher);
+        self.allow_system_libs.hash(&mut hasher);
+        self.target_env.hash(&mut hasher);
+        self.pkg_config_exe.hash(&mut hasher);
+        self.sysroot.hash(&mut hasher);
+        for (name, kind) in &self.link_kind_overrides {
+            name.hash(&mut hasher);
+            (*kind as u8).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
 }
 
 #[cfg(test)]
@@ -744,303 +3644,2714 @@ mod tests {
     }
 
     #[test]
-    fn test_is_static_available() {
-        let dir = create_test_dir_with_libs(&["foo", "bar"]);
-        let parser = PkgConfigParser::new();
-        let dirs = vec![dir.path().to_path_buf()];
+    fn test_is_static_available() {
+        let dir = create_test_dir_with_libs(&["foo", "bar"]);
+        let parser = PkgConfigParser::new();
+        let dirs = vec![dir.path().to_path_buf()];
+
+        assert!(parser.is_static_available("foo", &dirs));
+        assert!(parser.is_static_available("bar", &dirs));
+        assert!(!parser.is_static_available("baz", &dirs));
+    }
+
+    #[test]
+    fn test_system_root_exclusion() {
+        // Create a temp dir inside /tmp (not a system root)
+        let dir = create_test_dir_with_libs(&["mylib"]);
+        let parser = PkgConfigParser::new(); // default system_roots = ["/usr"]
+        let dirs = vec![dir.path().to_path_buf()];
+
+        // Should find it since /tmp is not under /usr
+        assert!(parser.is_static_available("mylib", &dirs));
+
+        // Now test with the dir as a system root
+        let parser_with_root = PkgConfigParser::new().system_roots([dir.path()]);
+        assert!(!parser_with_root.is_static_available("mylib", &dirs));
+    }
+
+    #[test]
+    fn test_parse_with_static_detection() {
+        let dir = create_test_dir_with_libs(&["spdk_env", "rte_mempool"]);
+        let parser = PkgConfigParser::new();
+
+        let output = format!("-L{} -lspdk_env -lpthread -lnuma", dir.path().display());
+        let flags = parser.parse(&output);
+
+        assert_eq!(flags.len(), 4);
+        // spdk_env has .a → Static
+        assert!(
+            matches!(&flags[1], LinkerFlag::Library { name, kind } if name == "spdk_env" && *kind == LinkKind::Static)
+        );
+        // pthread has no .a in test dir → Default
+        assert!(
+            matches!(&flags[2], LinkerFlag::Library { name, kind } if name == "pthread" && *kind == LinkKind::Default)
+        );
+        // numa has no .a in test dir → Default
+        assert!(
+            matches!(&flags[3], LinkerFlag::Library { name, kind } if name == "numa" && *kind == LinkKind::Default)
+        );
+    }
+
+    #[test]
+    fn test_whole_archive_region_with_static_detection() {
+        let dir = create_test_dir_with_libs(&["spdk_log", "rte_mempool_ring", "rte_eal"]);
+        let parser = PkgConfigParser::new();
+
+        let output = format!(
+            "-L{} -lspdk_log -Wl,--whole-archive -lrte_mempool_ring -lrte_eal -Wl,--no-whole-archive -lpthread",
+            dir.path().display()
+        );
+        let flags = parser.parse(&output);
+
+        assert_eq!(flags.len(), 5);
+        // spdk_log before --whole-archive, has .a → Static
+        assert!(
+            matches!(&flags[1], LinkerFlag::Library { name, kind } if name == "spdk_log" && *kind == LinkKind::Static)
+        );
+        // rte_mempool_ring inside --whole-archive, has .a → WholeArchive
+        assert!(
+            matches!(&flags[2], LinkerFlag::Library { name, kind } if name == "rte_mempool_ring" && *kind == LinkKind::WholeArchive)
+        );
+        // rte_eal inside --whole-archive, has .a → WholeArchive
+        assert!(
+            matches!(&flags[3], LinkerFlag::Library { name, kind } if name == "rte_eal" && *kind == LinkKind::WholeArchive)
+        );
+        // pthread after --no-whole-archive, no .a → Default
+        assert!(
+            matches!(&flags[4], LinkerFlag::Library { name, kind } if name == "pthread" && *kind == LinkKind::Default)
+        );
+    }
+
+    #[test]
+    fn test_upgrade_to_whole_archive_on_duplicate() {
+        let dir = create_test_dir_with_libs(&["rte_mempool_ring"]);
+        let parser = PkgConfigParser::new();
+
+        let output = format!(
+            "-L{} -lrte_mempool_ring -Wl,--whole-archive -l:librte_mempool_ring.a -Wl,--no-whole-archive",
+            dir.path().display()
+        );
+        let flags = parser.parse(&output);
+
+        // Should only have 2 entries (SearchPath + one library)
+        assert_eq!(flags.len(), 2);
+        // Should be upgraded to WholeArchive
+        assert!(
+            matches!(&flags[1], LinkerFlag::Library { name, kind } if name == "rte_mempool_ring" && *kind == LinkKind::WholeArchive)
+        );
+    }
+
+    #[test]
+    fn test_combined_wl_arguments() {
+        let dir = create_test_dir_with_libs(&["rte_bus_pci", "rte_eal", "rte_ring"]);
+        let parser = PkgConfigParser::new();
+
+        let output = format!(
+            "-L{dir} -Wl,--whole-archive,-lrte_bus_pci,--no-whole-archive -lrte_eal              -Wl,-rpath,/opt/spdk/lib -Wl,--as-needed,--whole-archive,{dir}/librte_ring.a,--no-whole-archive",
+            dir = dir.path().display()
+        );
+        let flags = parser.parse(&output);
+
+        assert_eq!(
+            flags[1..],
+            [
+                lib("rte_bus_pci", LinkKind::WholeArchive),
+                // The region closed inside the combined token
+                lib("rte_eal", LinkKind::Static),
+                LinkerFlag::LinkerArg("-Wl,-rpath,/opt/spdk/lib".to_string()),
+                LinkerFlag::LinkerArg("-Wl,--as-needed".to_string()),
+                lib("rte_ring", LinkKind::WholeArchive),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_archive_path() {
+        assert_eq!(split_archive_path("librte_eal.a"), Some((None, "rte_eal")));
+        assert_eq!(
+            split_archive_path("/opt/dpdk/lib/librte_eal.a"),
+            Some((Some("/opt/dpdk/lib"), "rte_eal"))
+        );
+        assert_eq!(
+            split_archive_path("/librte_eal.a"),
+            Some((Some("/"), "rte_eal"))
+        );
+        assert_eq!(split_archive_path("--as-needed"), None);
+        assert_eq!(split_archive_path("lib.a"), None);
+        assert_eq!(split_archive_path("rte_eal.a"), None);
+    }
+
+    #[test]
+    fn test_dedup_libs() {
+        let dir = create_test_dir_with_libs(&["spdk_log"]);
+        let parser = PkgConfigParser::new();
+
+        let output = format!(
+            "-L{} -lspdk_log -l:libspdk_log.a -lspdk_log",
+            dir.path().display()
+        );
+        let flags = parser.parse(&output);
+
+        // Should only have 2 entries (SearchPath + one library)
+        assert_eq!(flags.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_frameworks() {
+        let dir = create_test_dir_with_libs(&["spdk_env"]);
+        let parser = PkgConfigParser::new();
+
+        let output = format!(
+            "-L{} -F/Library/Frameworks -lspdk_env -framework CoreFoundation -lpthread -framework IOKit",
+            dir.path().display()
+        );
+        let flags = parser.parse(&output);
+
+        assert_eq!(flags.len(), 6);
+        assert!(
+            matches!(&flags[1], LinkerFlag::FrameworkSearchPath(path) if path == "/Library/Frameworks")
+        );
+        assert!(
+            matches!(&flags[2], LinkerFlag::Library { name, kind } if name == "spdk_env" && *kind == LinkKind::Static)
+        );
+        assert!(matches!(&flags[3], LinkerFlag::Framework(name) if name == "CoreFoundation"));
+        // The framework name must not be misread as a separate token
+        assert!(
+            matches!(&flags[4], LinkerFlag::Library { name, kind } if name == "pthread" && *kind == LinkKind::Default)
+        );
+        assert!(matches!(&flags[5], LinkerFlag::Framework(name) if name == "IOKit"));
+
+        let directives = to_cargo_directives(&flags, true);
+        assert_eq!(
+            directives[1],
+            "cargo:rustc-link-search=framework=/Library/Frameworks"
+        );
+        assert_eq!(
+            directives[3],
+            "cargo:rustc-link-lib=framework=CoreFoundation"
+        );
+        assert_eq!(directives[5], "cargo:rustc-link-lib=framework=IOKit");
+    }
+
+    #[test]
+    fn test_parse_cflags_include_paths() {
+        let parser = PkgConfigParser::new();
+        let output = "-I/opt/spdk/include -I/usr/include/dpdk";
+        let flags = parser.parse_cflags(output);
+
+        assert_eq!(flags.len(), 2);
+        assert_eq!(
+            flags[0],
+            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include"))
+        );
+        assert_eq!(
+            flags[1],
+            CompilerFlag::IncludePath(PathBuf::from("/usr/include/dpdk"))
+        );
+    }
+
+    #[test]
+    fn test_parse_cflags_defines() {
+        let parser = PkgConfigParser::new();
+        let output = "-DFOO -DBAR=1 -DBAZ=hello";
+        let flags = parser.parse_cflags(output);
+
+        assert_eq!(flags.len(), 3);
+        assert_eq!(
+            flags[0],
+            CompilerFlag::Define {
+                key: "FOO".to_string(),
+                value: None
+            }
+        );
+        assert_eq!(
+            flags[1],
+            CompilerFlag::Define {
+                key: "BAR".to_string(),
+                value: Some("1".to_string())
+            }
+        );
+        assert_eq!(
+            flags[2],
+            CompilerFlag::Define {
+                key: "BAZ".to_string(),
+                value: Some("hello".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_cflags_undefine() {
+        let parser = PkgConfigParser::new();
+        let flags = parser.parse_cflags("-UDEBUG -DFOO -UDEBUG");
+
+        assert_eq!(
+            flags,
+            [
+                CompilerFlag::Undefine("DEBUG".to_string()),
+                CompilerFlag::Define {
+                    key: "FOO".to_string(),
+                    value: None
+                },
+            ]
+        );
+        assert_eq!(to_clang_args(&flags), ["-UDEBUG", "-DFOO"]);
+    }
+
+    #[test]
+    fn test_parse_cflags_mixed_with_unknown() {
+        let parser = PkgConfigParser::new();
+        let output = "-I/opt/spdk/include -std=c11 -DFOO -Wall -I/usr/include/dpdk";
+        let flags = parser.parse_cflags(output);
+
+        // Unknown flags (-std=c11, -Wall) are silently ignored
+        assert_eq!(flags.len(), 3);
+        assert_eq!(
+            flags[0],
+            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include"))
+        );
+        assert_eq!(
+            flags[1],
+            CompilerFlag::Define {
+                key: "FOO".to_string(),
+                value: None
+            }
+        );
+        assert_eq!(
+            flags[2],
+            CompilerFlag::IncludePath(PathBuf::from("/usr/include/dpdk"))
+        );
+    }
+
+    #[test]
+    fn test_parse_cflags_dedup() {
+        let parser = PkgConfigParser::new();
+        let output = "-I/opt/spdk/include -I/opt/spdk/include -DFOO -DFOO";
+        let flags = parser.parse_cflags(output);
+
+        assert_eq!(flags.len(), 2);
+    }
+
+    #[test]
+    fn test_pthread_in_cflags_and_libs() {
+        let parser = PkgConfigParser::new();
+        let line = "-I/opt/spdk/include -pthread -lnuma";
+
+        // cflags: forwarded to clang, library ignored
+        let cflags = parser.parse_cflags(line);
+        assert_eq!(
+            cflags,
+            [
+                CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+                CompilerFlag::Pthread,
+            ]
+        );
+        assert_eq!(to_clang_args(&cflags)[1], "-pthread");
+
+        // libs: still becomes a pthread library link
+        let libs = parser.parse(line);
+        assert_eq!(
+            library_names(&pkg_with_libs(libs.clone())),
+            ["pthread", "numa"]
+        );
+        assert_eq!(link_kind(&libs, "pthread"), Some(LinkKind::Default));
+    }
+
+    #[test]
+    fn test_parse_cflags_codegen_flags_opt_in() {
+        let output = "-I/opt/spdk/include -m64 -march=native -mno-red-zone -fPIC";
+
+        // Dropped by default
+        let flags = PkgConfigParser::new().parse_cflags(output);
+        assert_eq!(
+            flags,
+            [CompilerFlag::IncludePath(PathBuf::from(
+                "/opt/spdk/include"
+            ))]
+        );
+
+        let flags = PkgConfigParser::new()
+            .keep_codegen_flags(true)
+            .parse_cflags(output);
+        assert_eq!(
+            to_clang_args(&flags),
+            [
+                "-I/opt/spdk/include",
+                "-m64",
+                "-march=native",
+                "-mno-red-zone"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_clang_arg() {
+        assert_eq!(
+            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")).to_clang_arg(),
+            "-I/opt/spdk/include"
+        );
+        assert_eq!(
+            CompilerFlag::Define {
+                key: "FOO".to_string(),
+                value: None
+            }
+            .to_clang_arg(),
+            "-DFOO"
+        );
+        assert_eq!(
+            CompilerFlag::Define {
+                key: "FOO".to_string(),
+                value: Some("1".to_string())
+            }
+            .to_clang_arg(),
+            "-DFOO=1"
+        );
+    }
+
+    #[test]
+    fn test_to_clang_args() {
+        let flags = vec![
+            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+            CompilerFlag::Define {
+                key: "FOO".to_string(),
+                value: None,
+            },
+        ];
+        let args = to_clang_args(&flags);
+        assert_eq!(args, vec!["-I/opt/spdk/include", "-DFOO"]);
+    }
+
+    #[test]
+    fn test_to_cargo_directive_search_path() {
+        let flag = LinkerFlag::SearchPath("/opt/spdk/lib".to_string());
+        assert_eq!(
+            flag.to_cargo_directive(true),
+            "cargo:rustc-link-search=native=/opt/spdk/lib"
+        );
+        assert_eq!(
+            flag.to_cargo_directive(false),
+            "cargo:rustc-link-search=native=/opt/spdk/lib"
+        );
+    }
+
+    #[test]
+    fn test_to_cargo_directive_default_lib() {
+        let flag = LinkerFlag::Library {
+            name: "pthread".to_string(),
+            kind: LinkKind::Default,
+        };
+        assert_eq!(
+            flag.to_cargo_directive(true),
+            "cargo:rustc-link-lib=pthread"
+        );
+        assert_eq!(
+            flag.to_cargo_directive(false),
+            "cargo:rustc-link-lib=pthread"
+        );
+    }
+
+    #[test]
+    fn test_to_cargo_directive_static_lib() {
+        let flag = LinkerFlag::Library {
+            name: "spdk_log".to_string(),
+            kind: LinkKind::Static,
+        };
+        assert_eq!(
+            flag.to_cargo_directive(true),
+            "cargo:rustc-link-lib=static:-bundle=spdk_log"
+        );
+        assert_eq!(
+            flag.to_cargo_directive(false),
+            "cargo:rustc-link-lib=static=spdk_log"
+        );
+    }
+
+    #[test]
+    fn test_to_cargo_directive_whole_archive() {
+        let flag = LinkerFlag::Library {
+            name: "rte_eal".to_string(),
+            kind: LinkKind::WholeArchive,
+        };
+        assert_eq!(
+            flag.to_cargo_directive(true),
+            "cargo:rustc-link-lib=static:+whole-archive,-bundle=rte_eal"
+        );
+        assert_eq!(
+            flag.to_cargo_directive(false),
+            "cargo:rustc-link-lib=static:+whole-archive=rte_eal"
+        );
+    }
+
+    #[test]
+    fn test_to_cargo_directive_linker_arg() {
+        let flag = LinkerFlag::LinkerArg("-Wl,--export-dynamic".to_string());
+        assert_eq!(
+            flag.to_cargo_directive(true),
+            "cargo:rustc-link-arg=-Wl,--export-dynamic"
+        );
+    }
+
+    /// Writes `<name>.pc` into `dir` linking against `-l<lib>` from `dir`.
+    fn write_pc_file(dir: &Path, name: &str, lib: &str) -> PathBuf {
+        let path = dir.join(format!("{}.pc", name));
+        let contents = format!(
+            "Name: {name}\nDescription: test\nVersion: 1.0\nLibs: -L{} -l{lib}\n",
+            dir.display()
+        );
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn library_names(pkg: &PkgConfig) -> Vec<String> {
+        pkg.libs
+            .iter()
+            .filter_map(|flag| match flag {
+                LinkerFlag::Library { name, .. } => Some(name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Rewrites a `.pc` file without changing its modification time.
+    fn rewrite_keeping_mtime(path: &Path, name: &str, lib: &str) {
+        let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+        write_pc_file(path.parent().unwrap(), name, lib);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cached_probe_hit() {
+        let dir = create_test_dir_with_libs(&["first", "second"]);
+        let cache_dir = tempfile::tempdir().unwrap();
+        let pc = write_pc_file(dir.path(), "cachetest", "first");
+        let search = dir.path().to_str().unwrap();
+        let packages = vec!["cachetest".to_string()];
+        let parser = PkgConfigParser::new().cached(cache_dir.path());
+
+        let pkg = parser
+            .probe_cached(cache_dir.path(), &packages, Some(search), &[])
+            .unwrap();
+        assert_eq!(library_names(&pkg), ["first"]);
+        assert!(matches!(
+            pkg.libs.last(),
+            Some(LinkerFlag::Library {
+                kind: LinkKind::Static,
+                ..
+            })
+        ));
+
+        // Same inputs and mtimes: the stale cached result is returned
+        rewrite_keeping_mtime(&pc, "cachetest", "second");
+        let pkg = parser
+            .probe_cached(cache_dir.path(), &packages, Some(search), &[])
+            .unwrap();
+        assert_eq!(library_names(&pkg), ["first"]);
+    }
+
+    #[test]
+    fn test_cached_probe_miss_on_env_change() {
+        let dir = create_test_dir_with_libs(&["first", "second"]);
+        let cache_dir = tempfile::tempdir().unwrap();
+        let pc = write_pc_file(dir.path(), "cachetest", "first");
+        let search = dir.path().to_str().unwrap();
+        let packages = vec!["cachetest".to_string()];
+        let parser = PkgConfigParser::new().cached(cache_dir.path());
+
+        let env_a = [("PKG_CONFIG_SYSROOT_DIR".to_string(), None)];
+        let env_b = [(
+            "PKG_CONFIG_SYSROOT_DIR".to_string(),
+            Some("/nonexistent".to_string()),
+        )];
+        assert_ne!(
+            parser.cache_key(&packages, Some(search), &env_a),
+            parser.cache_key(&packages, Some(search), &env_b)
+        );
+
+        let pkg = parser
+            .probe_cached(cache_dir.path(), &packages, Some(search), &env_a)
+            .unwrap();
+        assert_eq!(library_names(&pkg), ["first"]);
+
+        rewrite_keeping_mtime(&pc, "cachetest", "second");
+        let pkg = parser
+            .probe_cached(cache_dir.path(), &packages, Some(search), &env_b)
+            .unwrap();
+        assert_eq!(library_names(&pkg), ["second"]);
+    }
+
+    #[test]
+    fn test_cached_probe_miss_on_mtime_change() {
+        let dir = create_test_dir_with_libs(&["first", "second"]);
+        let cache_dir = tempfile::tempdir().unwrap();
+        let pc = write_pc_file(dir.path(), "cachetest", "first");
+        let search = dir.path().to_str().unwrap();
+        let packages = vec!["cachetest".to_string()];
+        let parser = PkgConfigParser::new().cached(cache_dir.path());
+
+        let pkg = parser
+            .probe_cached(cache_dir.path(), &packages, Some(search), &[])
+            .unwrap();
+        assert_eq!(library_names(&pkg), ["first"]);
+
+        let modified = std::fs::metadata(&pc).unwrap().modified().unwrap();
+        write_pc_file(dir.path(), "cachetest", "second");
+        File::options()
+            .write(true)
+            .open(&pc)
+            .unwrap()
+            .set_modified(modified + std::time::Duration::from_secs(10))
+            .unwrap();
+
+        let pkg = parser
+            .probe_cached(cache_dir.path(), &packages, Some(search), &[])
+            .unwrap();
+        assert_eq!(library_names(&pkg), ["second"]);
+    }
+
+    /// Serializes tests that modify the process environment.
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Sets environment variables for the lifetime of the guard.
+    struct ScopedEnv<'a> {
+        vars: Vec<&'static str>,
+        _lock: std::sync::MutexGuard<'a, ()>,
+    }
+
+    impl ScopedEnv<'_> {
+        fn set(vars: &[(&'static str, &str)]) -> Self {
+            let lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            for (var, value) in vars {
+                // SAFETY: ENV_LOCK serializes all tests that touch the environment
+                unsafe { std::env::set_var(var, value) };
+            }
+            Self {
+                vars: vars.iter().map(|(var, _)| *var).collect(),
+                _lock: lock,
+            }
+        }
+    }
+
+    impl Drop for ScopedEnv<'_> {
+        fn drop(&mut self) {
+            for var in &self.vars {
+                // SAFETY: still holding ENV_LOCK
+                unsafe { std::env::remove_var(var) };
+            }
+        }
+    }
+
+    fn link_kind(flags: &[LinkerFlag], lib: &str) -> Option<LinkKind> {
+        flags.iter().find_map(|flag| match flag {
+            LinkerFlag::Library { name, kind } if name == lib => Some(*kind),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_env_static_and_whole_archive() {
+        // Library names are unique to this test so concurrent tests that
+        // call parse() are unaffected by the variables
+        let dir = create_test_dir_with_libs(&["envwa_a", "envwa_b"]);
+        let output = format!(
+            "-L{} -lenvwa_a -lenvwa_b -lenvwa_shared",
+            dir.path().display()
+        );
+
+        let _env = ScopedEnv::set(&[(ENV_STATIC, "envwa_shared"), (ENV_WHOLE_ARCHIVE, "envwa_a")]);
+        let flags = PkgConfigParser::new().parse(&output);
+
+        assert_eq!(link_kind(&flags, "envwa_a"), Some(LinkKind::WholeArchive));
+        assert_eq!(link_kind(&flags, "envwa_b"), Some(LinkKind::Static));
+        // Forced static even though no .a was found
+        assert_eq!(link_kind(&flags, "envwa_shared"), Some(LinkKind::Static));
+    }
+
+    #[test]
+    fn test_env_static_beats_builder() {
+        let dir = create_test_dir_with_libs(&["envbeat"]);
+        let output = format!(
+            "-L{} -Wl,--whole-archive -lenvbeat -Wl,--no-whole-archive",
+            dir.path().display()
+        );
+
+        let _env = ScopedEnv::set(&[(ENV_STATIC, "envbeat")]);
+        let flags = PkgConfigParser::new()
+            .force_whole_archive(["envbeat"])
+            .parse(&output);
+
+        assert_eq!(link_kind(&flags, "envbeat"), Some(LinkKind::Static));
+    }
+
+    #[test]
+    fn test_env_all_dynamic() {
+        let dir = create_test_dir_with_libs(&["spdk_log", "spdk_env", "rte_eal"]);
+        let output = format!(
+            "-L{} -lspdk_log -Wl,--whole-archive -lspdk_env -Wl,--no-whole-archive -lrte_eal",
+            dir.path().display()
+        );
+
+        // ALL_DYNAMIC is process-wide, so use an explicit lookup rather than
+        // affecting concurrently running tests
+        let overrides = EnvOverrides::from_lookup(|var| match var {
+            ENV_ALL_DYNAMIC => Some("1".to_string()),
+            ENV_WHOLE_ARCHIVE => Some("rte_eal".to_string()),
+            _ => None,
+        });
+        let flags = PkgConfigParser::new()
+            .force_whole_archive(["spdk_log"])
+            .parse_with_overrides(&output, &overrides)
+            .unwrap();
+
+        // Env beats builder and detection
+        assert_eq!(link_kind(&flags, "spdk_log"), Some(LinkKind::Default));
+        assert_eq!(link_kind(&flags, "spdk_env"), Some(LinkKind::Default));
+        // Per-library env beats ALL_DYNAMIC
+        assert_eq!(link_kind(&flags, "rte_eal"), Some(LinkKind::WholeArchive));
+    }
+
+    #[test]
+    fn test_group_static_libs() {
+        let dir = create_test_dir_with_libs(&["spdk_log", "spdk_env", "rte_eal"]);
+        let output = format!(
+            "-L{} -lspdk_log -Wl,--whole-archive -lspdk_env -Wl,--no-whole-archive -lrte_eal -lnuma",
+            dir.path().display()
+        );
+
+        let parser = PkgConfigParser::new().group_static_libs(true);
+        let directives = to_cargo_directives(&parser.parse(&output), true);
+
+        assert_eq!(
+            directives,
+            [
+                format!("cargo:rustc-link-search=native={}", dir.path().display()),
+                "cargo:rustc-link-arg=-Wl,--start-group".to_string(),
+                "cargo:rustc-link-lib=static:-bundle=spdk_log".to_string(),
+                "cargo:rustc-link-lib=static:+whole-archive,-bundle=spdk_env".to_string(),
+                "cargo:rustc-link-lib=static:-bundle=rte_eal".to_string(),
+                "cargo:rustc-link-arg=-Wl,--end-group".to_string(),
+                "cargo:rustc-link-lib=numa".to_string(),
+            ]
+        );
+
+        // No static libraries, no markers
+        let flags = parser.parse("-lnuma -lpthread");
+        assert!(!flags.iter().any(|f| matches!(f, LinkerFlag::LinkerArg(_))));
+    }
+
+    #[test]
+    fn test_parse_colon_archive() {
+        let dir = create_test_dir_with_libs(&["foo", "bar"]);
+        let parser = PkgConfigParser::new();
+        let output = format!("-L{} -l:libfoo.a -l:bar.a", dir.path().display());
+        let flags = parser.parse(&output);
+
+        assert_eq!(link_kind(&flags, "foo"), Some(LinkKind::Static));
+        // No lib prefix
+        assert_eq!(link_kind(&flags, "bar"), Some(LinkKind::Static));
+    }
+
+    #[test]
+    fn test_parse_verbatim_shared_object() {
+        let parser = PkgConfigParser::new();
+        let flags = parser.parse("-l:liblz4.so.1 -l:libfoo.so -l:libbar.so.1.2.3 -l:baz.so.2");
+
+        let verbatim: Vec<&str> = flags
+            .iter()
+            .filter_map(|f| match f {
+                LinkerFlag::VerbatimLibrary(file) => Some(file.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            verbatim,
+            ["liblz4.so.1", "libfoo.so", "libbar.so.1.2.3", "baz.so.2"]
+        );
+        assert_eq!(
+            flags[0].to_cargo_directive(true),
+            "cargo:rustc-link-lib=dylib:+verbatim=liblz4.so.1"
+        );
+    }
+
+    #[test]
+    fn test_parse_exact_file_static_vs_shared() {
+        let dir = create_test_dir_with_libs(&["lz4"]);
+        let parser = PkgConfigParser::new();
+        let output = format!(
+            "-L{} -l:liblz4.a -l:liblz4.so.1 -l:libzstd.so",
+            dir.path().display()
+        );
+        let flags = parser.parse(&output);
+
+        // .a goes through static detection, .so/.so.N are kept verbatim
+        assert_eq!(
+            flags[1..],
+            [
+                lib("lz4", LinkKind::Static),
+                LinkerFlag::VerbatimLibrary("liblz4.so.1".to_string()),
+                LinkerFlag::VerbatimLibrary("libzstd.so".to_string()),
+            ]
+        );
+        assert_eq!(
+            to_cargo_directives(&flags[1..], true),
+            [
+                "cargo:rustc-link-lib=static:-bundle=lz4",
+                "cargo:rustc-link-lib=dylib:+verbatim=liblz4.so.1",
+                "cargo:rustc-link-lib=dylib:+verbatim=libzstd.so",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_shared_object() {
+        let parser = PkgConfigParser::new();
+        let flags = parser.parse("-llz4.so.1 -lsomething.socket");
+
+        assert!(matches!(&flags[0], LinkerFlag::VerbatimLibrary(file) if file == "liblz4.so.1"));
+        // ".so" followed by a non-version suffix is an ordinary library name
+        assert_eq!(
+            link_kind(&flags, "something.socket"),
+            Some(LinkKind::Default)
+        );
+    }
+
+    #[test]
+    fn test_parse_cflags_include_variants_split() {
+        let parser = PkgConfigParser::new();
+        let output = "-isystem /usr/include/dpdk -iquote /opt/quote -include rte_config.h -I/opt/spdk/include";
+        let flags = parser.parse_cflags(output);
+
+        assert_eq!(
+            flags,
+            [
+                CompilerFlag::SystemIncludePath(PathBuf::from("/usr/include/dpdk")),
+                CompilerFlag::QuoteIncludePath(PathBuf::from("/opt/quote")),
+                CompilerFlag::ForcedInclude(PathBuf::from("rte_config.h")),
+                CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_cflags_include_variants_fused() {
+        let parser = PkgConfigParser::new();
+        // Fused and split forms of the same flag are deduplicated
+        let output = "-isystem/usr/include/dpdk -iquote/opt/quote -includerte_config.h -isystem /usr/include/dpdk";
+        let flags = parser.parse_cflags(output);
+
+        assert_eq!(
+            flags,
+            [
+                CompilerFlag::SystemIncludePath(PathBuf::from("/usr/include/dpdk")),
+                CompilerFlag::QuoteIncludePath(PathBuf::from("/opt/quote")),
+                CompilerFlag::ForcedInclude(PathBuf::from("rte_config.h")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_clang_args_multi_arg() {
+        let flags = vec![
+            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+            CompilerFlag::SystemIncludePath(PathBuf::from("/usr/include/dpdk")),
+            CompilerFlag::QuoteIncludePath(PathBuf::from("/opt/quote")),
+            CompilerFlag::ForcedInclude(PathBuf::from("rte_config.h")),
+            CompilerFlag::Define {
+                key: "FOO".to_string(),
+                value: None,
+            },
+        ];
+        let args = to_clang_args(&flags);
+        assert_eq!(
+            args,
+            [
+                "-I/opt/spdk/include",
+                "-isystem",
+                "/usr/include/dpdk",
+                "-iquote",
+                "/opt/quote",
+                "-include",
+                "rte_config.h",
+                "-DFOO",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rerun_paths() {
+        let dir = create_test_dir_with_libs(&["rerun_a"]);
+        let include = dir.path().join("include");
+        std::fs::create_dir(&include).unwrap();
+        std::fs::write(
+            dir.path().join("rerun_a.pc"),
+            format!(
+                "Name: rerun_a\nDescription: test\nVersion: 1.0\nRequires: rerun_b\nLibs: -L{} -lrerun_a -lrerun_dyn\n",
+                dir.path().display()
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("rerun_b.pc"),
+            format!(
+                "Name: rerun_b\nDescription: test\nVersion: 1.0\nCflags: -I{}\n",
+                include.display()
+            ),
+        )
+        .unwrap();
+
+        let pkg = PkgConfigParser::new()
+            .probe(["rerun_a"], Some(dir.path().to_str().unwrap()))
+            .unwrap();
+
+        assert_eq!(
+            pkg.rerun_paths(),
+            [
+                dir.path().join("rerun_a.pc"),
+                dir.path().join("rerun_b.pc"),
+                dir.path().to_path_buf(),
+                // rerun_dyn has no archive, so only rerun_a's is tracked
+                dir.path().join("librerun_a.a"),
+                include,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_override_link_kind_to_default() {
+        let dir = create_test_dir_with_libs(&["isal", "spdk_log"]);
+        let output = format!(
+            "-L{} -lisal -Wl,--whole-archive -lisal -lspdk_log -Wl,--no-whole-archive",
+            dir.path().display()
+        );
+
+        let flags = PkgConfigParser::new()
+            .override_link_kind([("isal", LinkKind::Default)])
+            .parse_with_overrides(&output, &EnvOverrides::default())
+            .unwrap();
+
+        // Local .a ignored, and not upgraded in the whole-archive region
+        assert_eq!(link_kind(&flags, "isal"), Some(LinkKind::Default));
+        assert_eq!(link_kind(&flags, "spdk_log"), Some(LinkKind::WholeArchive));
+        let isal = flags
+            .iter()
+            .find(|flag| matches!(flag, LinkerFlag::Library { name, .. } if name == "isal"))
+            .unwrap();
+        assert_eq!(isal.to_cargo_directive(true), "cargo:rustc-link-lib=isal");
+    }
+
+    #[test]
+    fn test_override_link_kind_beats_force_whole_archive() {
+        let dir = create_test_dir_with_libs(&["isal", "spdk_util", "spdk_log"]);
+        let output = format!(
+            "-L{} -Wl,--whole-archive -lspdk_util -Wl,--no-whole-archive -lisal -lspdk_log",
+            dir.path().display()
+        );
+
+        let flags = PkgConfigParser::new()
+            .force_whole_archive(["isal", "spdk_log"])
+            .override_link_kind([("isal", LinkKind::Default), ("spdk_util", LinkKind::Static)])
+            .parse_with_overrides(&output, &EnvOverrides::default())
+            .unwrap();
+
+        assert_eq!(link_kind(&flags, "isal"), Some(LinkKind::Default));
+        assert_eq!(link_kind(&flags, "spdk_util"), Some(LinkKind::Static));
+        assert_eq!(link_kind(&flags, "spdk_log"), Some(LinkKind::WholeArchive));
+
+        // force_whole_archive is itself an override, so the later call wins
+        let (flags, decisions, _) = PkgConfigParser::new()
+            .override_link_kind([("isal", LinkKind::Default)])
+            .force_whole_archive(["isal"])
+            .parse_detailed(&output, &EnvOverrides::default())
+            .unwrap();
+        assert_eq!(link_kind(&flags, "isal"), Some(LinkKind::WholeArchive));
+        assert_eq!(decisions[1].2, DecisionReason::Override);
+    }
+
+    #[test]
+    fn test_force_whole_archive_is_an_override() {
+        let dir = create_test_dir_with_libs(&["fwa_static"]);
+        let output = format!(
+            "-L{} -lfwa_static -lfwa_shared -Wl,-Bstatic -lfwa_missing -Wl,-Bdynamic",
+            dir.path().display()
+        );
+        let parser =
+            PkgConfigParser::new().force_whole_archive(["fwa_static", "fwa_shared", "fwa_missing"]);
+
+        // Applies without a static archive, even where -Bstatic would fail
+        let (flags, decisions, _) = parser
+            .parse_detailed(&output, &EnvOverrides::default())
+            .unwrap();
+        for name in ["fwa_static", "fwa_shared", "fwa_missing"] {
+            assert_eq!(link_kind(&flags, name), Some(LinkKind::WholeArchive));
+        }
+        assert!(
+            decisions
+                .iter()
+                .all(|(_, _, reason)| *reason == DecisionReason::Override)
+        );
+
+        // prefer_dynamic no longer downgrades it
+        let flags = parser
+            .prefer_dynamic(true)
+            .parse_with_overrides(&output, &EnvOverrides::default())
+            .unwrap();
+        assert_eq!(
+            link_kind(&flags, "fwa_static"),
+            Some(LinkKind::WholeArchive)
+        );
+    }
+
+    #[test]
+    fn test_unterminated_whole_archive_warning() {
+        let parser = PkgConfigParser::new();
+
+        let pkg = parser.probe_from_output("-lbar -Wl,--whole-archive -lfoo", "");
+        assert_eq!(pkg.warnings().len(), 1);
+        assert!(pkg.warnings()[0].contains("--whole-archive is never closed"));
+        assert!(pkg.warnings()[0].ends_with("(foo)"));
+
+        let pkg =
+            parser.probe_from_output("-Wl,--whole-archive -lfoo -Wl,--no-whole-archive -lbar", "");
+        assert!(pkg.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_decisions_report() {
+        let dir = create_test_dir_with_libs(&["spdk_log", "spdk_env", "rte_eal", "isal"]);
+        let output = format!(
+            "-L{} -lspdk_log -lrte_eal -lisal -Wl,--whole-archive -lspdk_log -lspdk_env -Wl,--no-whole-archive -lnuma -pthread",
+            dir.path().display()
+        );
+
+        let (_, decisions, _) = PkgConfigParser::new()
+            .force_whole_archive(["rte_eal"])
+            .override_link_kind([("isal", LinkKind::Static)])
+            .parse_detailed(&output, &EnvOverrides::default())
+            .unwrap();
+
+        let expected = [
+            (
+                "spdk_log",
+                LinkKind::WholeArchive,
+                DecisionReason::WholeArchiveRegion,
+            ),
+            ("rte_eal", LinkKind::WholeArchive, DecisionReason::Override),
+            ("isal", LinkKind::Static, DecisionReason::Override),
+            (
+                "spdk_env",
+                LinkKind::WholeArchive,
+                DecisionReason::WholeArchiveRegion,
+            ),
+            ("numa", LinkKind::Default, DecisionReason::NoStaticArchive),
+            (
+                "pthread",
+                LinkKind::Default,
+                DecisionReason::NoStaticArchive,
+            ),
+        ];
+        assert_eq!(decisions.len(), expected.len());
+        for ((name, kind, reason), (exp_name, exp_kind, exp_reason)) in
+            decisions.iter().zip(expected)
+        {
+            assert_eq!(
+                (name.as_str(), *kind, *reason),
+                (exp_name, exp_kind, exp_reason)
+            );
+        }
+    }
+
+    fn search_paths(flags: &[LinkerFlag]) -> Vec<&str> {
+        flags
+            .iter()
+            .filter_map(|f| match f {
+                LinkerFlag::SearchPath(path) => Some(path.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_dedup_messy_search_paths() {
+        let parser = PkgConfigParser::new();
+        let output = "-L/opt/spdk/lib -L/opt/spdk//lib -L/opt/dpdk/lib/../lib64 -L/opt/spdk/./lib \
+                      -L/usr/lib -L/usr//lib64 -L/opt/dpdk/lib64 -lspdk_log";
+        let flags = parser.parse(output);
+
+        assert_eq!(search_paths(&flags), ["/opt/spdk/lib", "/opt/dpdk/lib64"]);
+    }
+
+    #[test]
+    fn test_parse_dedup_repeated_flags() {
+        let output = "-L/opt/spdk/lib -lspdk_log -L/opt/spdk/lib -Wl,--as-needed \
+                      -L/opt/spdk/lib -lspdk_util -Wl,--as-needed";
+
+        let flags = PkgConfigParser::new().parse(output);
+        assert_eq!(search_paths(&flags), ["/opt/spdk/lib"]);
+        let linker_args = |flags: &[LinkerFlag]| {
+            flags
+                .iter()
+                .filter(|flag| matches!(flag, LinkerFlag::LinkerArg(_)))
+                .count()
+        };
+        assert_eq!(linker_args(&flags), 1);
+
+        // Raw order with dedup disabled
+        let flags = PkgConfigParser::new().dedup(false).parse(output);
+        assert_eq!(
+            search_paths(&flags),
+            ["/opt/spdk/lib", "/opt/spdk/lib", "/opt/spdk/lib"]
+        );
+        assert_eq!(linker_args(&flags), 2);
+    }
+
+    #[test]
+    fn test_parse_keep_default_lib_dirs() {
+        let parser = PkgConfigParser::new().keep_default_lib_dirs(true);
+        let flags = parser.parse("-L/usr/lib -L/opt/spdk/lib -L/usr/lib/ -lspdk_log");
+
+        assert_eq!(search_paths(&flags), ["/usr/lib", "/opt/spdk/lib"]);
+    }
+
+    #[test]
+    fn test_parse_canonicalizes_existing_search_paths() {
+        let dir = create_test_dir_with_libs(&["spdk_log"]);
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let output = format!("-L{0}/sub/.. -L{0} -lspdk_log", dir.path().display());
+        let flags = PkgConfigParser::new().parse(&output);
+
+        let canonical = std::fs::canonicalize(dir.path()).unwrap();
+        assert_eq!(search_paths(&flags), [canonical.to_str().unwrap()]);
+        assert_eq!(link_kind(&flags, "spdk_log"), Some(LinkKind::Static));
+    }
+
+    #[test]
+    fn test_parse_cflags_dedup_messy_include_paths() {
+        let parser = PkgConfigParser::new();
+        let output = "-I/opt/spdk/include -I/opt/spdk//include -I/opt/dpdk/include/../include/dpdk -I/opt/spdk/include/";
+        let flags = parser.parse_cflags(output);
+
+        assert_eq!(
+            flags,
+            [
+                CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+                CompilerFlag::IncludePath(PathBuf::from("/opt/dpdk/include/dpdk")),
+            ]
+        );
+    }
+
+    fn lib(name: &str, kind: LinkKind) -> LinkerFlag {
+        LinkerFlag::Library {
+            name: name.to_string(),
+            kind,
+        }
+    }
+
+    fn pkg_with_libs(libs: Vec<LinkerFlag>) -> PkgConfig {
+        PkgConfig {
+            libs,
+            cflags: Vec::new(),
+            pc_files: Vec::new(),
+            tool: None,
+            decisions: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_merge_conflicting_kinds() {
+        let mut a = pkg_with_libs(vec![
+            LinkerFlag::SearchPath("/opt/spdk/lib".to_string()),
+            lib("spdk_log", LinkKind::Static),
+            lib("numa", LinkKind::Default),
+        ]);
+        a.decisions = vec![
+            (
+                "spdk_log".to_string(),
+                LinkKind::Static,
+                DecisionReason::StaticAvailable,
+            ),
+            (
+                "numa".to_string(),
+                LinkKind::Default,
+                DecisionReason::NoStaticArchive,
+            ),
+        ];
+        let mut b = pkg_with_libs(vec![
+            LinkerFlag::SearchPath("/opt/vendor/lib".to_string()),
+            LinkerFlag::SearchPath("/opt/spdk/lib".to_string()),
+            lib("spdk_log", LinkKind::WholeArchive),
+            lib("numa", LinkKind::Static),
+            lib("uuid", LinkKind::Default),
+        ]);
+        b.decisions = vec![
+            (
+                "spdk_log".to_string(),
+                LinkKind::WholeArchive,
+                DecisionReason::Override,
+            ),
+            (
+                "numa".to_string(),
+                LinkKind::Static,
+                DecisionReason::StaticAvailable,
+            ),
+            (
+                "uuid".to_string(),
+                LinkKind::Default,
+                DecisionReason::NoStaticArchive,
+            ),
+        ];
+
+        let merged = a.merge(b);
+
+        assert_eq!(
+            merged.libs,
+            [
+                LinkerFlag::SearchPath("/opt/spdk/lib".to_string()),
+                LinkerFlag::SearchPath("/opt/vendor/lib".to_string()),
+                lib("spdk_log", LinkKind::WholeArchive),
+                lib("numa", LinkKind::Static),
+                lib("uuid", LinkKind::Default),
+            ]
+        );
+        assert_eq!(
+            merged.decisions(),
+            [
+                (
+                    "spdk_log".to_string(),
+                    LinkKind::WholeArchive,
+                    DecisionReason::Override
+                ),
+                (
+                    "numa".to_string(),
+                    LinkKind::Static,
+                    DecisionReason::StaticAvailable
+                ),
+                (
+                    "uuid".to_string(),
+                    LinkKind::Default,
+                    DecisionReason::NoStaticArchive
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_interleaved_ordering() {
+        // vendor depends on spdk_util, which depends on spdk_log: a naive
+        // concatenation would place vendor after its dependencies.
+        let a = pkg_with_libs(vec![
+            lib("spdk_util", LinkKind::Static),
+            lib("spdk_log", LinkKind::Static),
+        ]);
+        let b = pkg_with_libs(vec![
+            lib("vendor", LinkKind::Static),
+            lib("spdk_util", LinkKind::Static),
+            lib("rt", LinkKind::Default),
+        ]);
+
+        let merged = a.merge(b);
+
+        assert_eq!(
+            library_names(&merged),
+            ["vendor", "spdk_util", "spdk_log", "rt"]
+        );
+    }
+
+    #[test]
+    fn test_merge_contradictory_order_prefers_self() {
+        let a = pkg_with_libs(vec![lib("x", LinkKind::Static), lib("y", LinkKind::Static)]);
+        let b = pkg_with_libs(vec![lib("y", LinkKind::Static), lib("x", LinkKind::Static)]);
+
+        assert_eq!(library_names(&a.merge(b)), ["x", "y"]);
+    }
+
+    #[test]
+    fn test_merge_regroups_static_libs() {
+        let a = pkg_with_libs(vec![
+            LinkerFlag::LinkerArg("-Wl,--start-group".to_string()),
+            lib("spdk_log", LinkKind::Static),
+            LinkerFlag::LinkerArg("-Wl,--end-group".to_string()),
+            LinkerFlag::LinkerArg("-Wl,--export-dynamic".to_string()),
+        ]);
+        let b = pkg_with_libs(vec![
+            lib("vendor", LinkKind::Static),
+            lib("m", LinkKind::Default),
+            LinkerFlag::LinkerArg("-Wl,--export-dynamic".to_string()),
+        ]);
+
+        let merged = a.merge(b);
+
+        assert_eq!(
+            merged.libs,
+            [
+                LinkerFlag::LinkerArg("-Wl,--start-group".to_string()),
+                lib("spdk_log", LinkKind::Static),
+                lib("vendor", LinkKind::Static),
+                LinkerFlag::LinkerArg("-Wl,--end-group".to_string()),
+                lib("m", LinkKind::Default),
+                LinkerFlag::LinkerArg("-Wl,--export-dynamic".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_dedups_cflags_and_pc_files() {
+        let mut a = pkg_with_libs(Vec::new());
+        a.cflags = vec![
+            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+            CompilerFlag::Define {
+                key: "SPDK".to_string(),
+                value: None,
+            },
+        ];
+        a.pc_files = vec![PathBuf::from("/opt/spdk/lib/pkgconfig/spdk_log.pc")];
+        let mut b = pkg_with_libs(Vec::new());
+        b.cflags = vec![
+            CompilerFlag::IncludePath(PathBuf::from("/opt/vendor/include")),
+            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+            CompilerFlag::Define {
+                key: "SPDK".to_string(),
+                value: Some("1".to_string()),
+            },
+        ];
+        b.pc_files = vec![
+            PathBuf::from("/opt/spdk/lib/pkgconfig/spdk_log.pc"),
+            PathBuf::from("/opt/vendor/lib/pkgconfig/vendor.pc"),
+        ];
+
+        let merged = a.merge(b);
+
+        assert_eq!(
+            merged.cflags,
+            [
+                CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+                CompilerFlag::Define {
+                    key: "SPDK".to_string(),
+                    value: None,
+                },
+                CompilerFlag::IncludePath(PathBuf::from("/opt/vendor/include")),
+                CompilerFlag::Define {
+                    key: "SPDK".to_string(),
+                    value: Some("1".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            merged.pc_files,
+            [
+                PathBuf::from("/opt/spdk/lib/pkgconfig/spdk_log.pc"),
+                PathBuf::from("/opt/vendor/lib/pkgconfig/vendor.pc"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            split_args(r#"  -L/opt/my\ spdk/lib "-I/opt/my spdk/include" -l'spdk log' "#),
+            ["-L/opt/my spdk/lib", "-I/opt/my spdk/include", "-lspdk log"]
+        );
+        // Mixed segments join; escapes inside double quotes
+        assert_eq!(
+            split_args(r#"-I/opt/"a b"/c\ d/'e "f"' "-DX=\"y\"" "\n""#),
+            ["-I/opt/a b/c d/e \"f\"", "-DX=\"y\"", "\\n"]
+        );
+        assert!(split_args(" \t\n ").is_empty());
+        assert_eq!(split_args("''"), [""]);
+    }
+
+    #[test]
+    fn test_parse_escaped_and_quoted_paths() {
+        let parser = PkgConfigParser::new();
+        let output =
+            r#"-L/opt/my\ spdk/lib "-L/opt/vendor libs/lib" -L'/opt/both "x"'\ y/lib -lspdk_log"#;
+        let flags = parser.parse(output);
+
+        assert_eq!(
+            search_paths(&flags),
+            [
+                "/opt/my spdk/lib",
+                "/opt/vendor libs/lib",
+                "/opt/both \"x\" y/lib"
+            ]
+        );
+        assert_eq!(link_kind(&flags, "spdk_log"), Some(LinkKind::Default));
+        assert_eq!(
+            flags[0].to_cargo_directive(true),
+            "cargo:rustc-link-search=native=/opt/my spdk/lib"
+        );
+    }
+
+    #[test]
+    fn test_parse_spaced_prefix_detects_static() {
+        let dir = tempfile::Builder::new()
+            .prefix("my spdk")
+            .tempdir()
+            .unwrap();
+        std::fs::write(dir.path().join("libspdk_log.a"), b"").unwrap();
+        let escaped = dir.path().display().to_string().replace(' ', "\\ ");
+        let flags = PkgConfigParser::new().parse(&format!("-L{} -lspdk_log", escaped));
+
+        assert_eq!(link_kind(&flags, "spdk_log"), Some(LinkKind::Static));
+    }
+
+    #[test]
+    fn test_probe_spaced_prefix() {
+        let dir = tempfile::Builder::new()
+            .prefix("my spdk")
+            .tempdir()
+            .unwrap();
+        let prefix = dir.path().canonicalize().unwrap();
+        std::fs::write(prefix.join("libspaced.a"), b"").unwrap();
+        // pkg-config escapes the spaces in its output
+        let escaped = prefix.display().to_string().replace(' ', "\\ ");
+        std::fs::write(
+            prefix.join("spaced.pc"),
+            format!(
+                "prefix={}\nName: spaced\nDescription: test\nVersion: 1.0\n\
+                 Libs: -L${{prefix}} -lspaced\nCflags: -I${{prefix}}/include\n",
+                escaped
+            ),
+        )
+        .unwrap();
+
+        let pkg = PkgConfigParser::new()
+            .probe(["spaced"], prefix.to_str())
+            .unwrap();
+
+        assert_eq!(search_paths(&pkg.libs), [prefix.to_str().unwrap()]);
+        assert_eq!(link_kind(&pkg.libs, "spaced"), Some(LinkKind::Static));
+        assert_eq!(
+            pkg.cflags,
+            [CompilerFlag::IncludePath(prefix.join("include"))]
+        );
+    }
+
+    #[test]
+    fn test_parse_cflags_escaped_and_quoted_paths() {
+        let parser = PkgConfigParser::new();
+        let output = r#"-I/opt/my\ spdk/include -isystem "/opt/dpdk inc" '-DNAME="a b"'"#;
+        let flags = parser.parse_cflags(output);
+
+        assert_eq!(
+            flags,
+            [
+                CompilerFlag::IncludePath(PathBuf::from("/opt/my spdk/include")),
+                CompilerFlag::SystemIncludePath(PathBuf::from("/opt/dpdk inc")),
+                CompilerFlag::Define {
+                    key: "NAME".to_string(),
+                    value: Some("\"a b\"".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            to_clang_args(&flags),
+            [
+                "-I/opt/my spdk/include",
+                "-isystem",
+                "/opt/dpdk inc",
+                "-DNAME=\"a b\""
+            ]
+        );
+    }
+
+    #[test]
+    fn test_probe_reports_missing_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        write_pc_file(dir.path(), "probe_present_a", "present_a");
+        write_pc_file(dir.path(), "probe_present_b", "present_b");
+        let path = dir.path().to_str().unwrap();
+
+        let err = PkgConfigParser::new()
+            .probe(
+                ["probe_present_a", "spdk_bdev_uring", "probe_present_b"],
+                Some(path),
+            )
+            .unwrap_err();
+
+        let ProbeError::MissingPackages {
+            missing,
+            present,
+            pkg_config_path,
+        } = &err
+        else {
+            panic!("expected MissingPackages, got {:?}", err);
+        };
+        assert_eq!(err.missing_packages(), ["spdk_bdev_uring"]);
+        assert!(missing[0].1.contains("spdk_bdev_uring"));
+        assert_eq!(present, &["probe_present_a", "probe_present_b"]);
+        assert_eq!(pkg_config_path.as_deref(), Some(path));
+
+        let message = String::from(err.clone());
+        assert_eq!(message, err.to_string());
+        assert!(message.contains("could not find 1 of 3 packages"));
+        assert!(message.contains("missing: spdk_bdev_uring"));
+        assert!(message.contains("--with-uring"));
+        assert!(message.contains("present: probe_present_a, probe_present_b"));
+    }
+
+    #[test]
+    fn test_probe_reports_missing_requires() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("probe_needs_dep.pc"),
+            "Name: probe_needs_dep\nDescription: test\nVersion: 1.0\nRequires: probe_absent_dep\n",
+        )
+        .unwrap();
+
+        let err = PkgConfigParser::new()
+            .probe(["probe_needs_dep"], dir.path().to_str())
+            .unwrap_err();
+
+        assert_eq!(err.missing_packages(), ["probe_needs_dep"]);
+        assert!(err.to_string().contains("probe_absent_dep"));
+        // Unknown packages get no configure hint
+        assert!(!err.to_string().contains("configure with"));
+    }
+
+    #[test]
+    fn test_parse_package_not_found() {
+        assert_eq!(
+            error::parse_package_not_found(
+                "Package spdk_nvmf was not found in the pkg-config search path.\n\
+                 Perhaps you should add the directory containing `spdk_nvmf.pc'\n"
+            )
+            .as_deref(),
+            Some("spdk_nvmf")
+        );
+        assert_eq!(
+            error::parse_package_not_found(
+                "Package 'libdpdk', required by 'spdk_env_dpdk', not found\n"
+            )
+            .as_deref(),
+            Some("libdpdk")
+        );
+        assert_eq!(
+            error::parse_package_not_found("Requested 'foo >= 2.0' but version of foo is 1.0\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_run_pkg_config_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        write_pc_file(dir.path(), "run_present", "present");
+        std::fs::write(
+            dir.path().join("run_needs_dep.pc"),
+            "Name: run_needs_dep\nDescription: test\nVersion: 1.0\nRequires: run_absent_dep\n",
+        )
+        .unwrap();
+        let path = dir.path().to_str();
+
+        let output = PkgConfigParser::run_pkg_config(["run_present"], path).unwrap();
+        assert!(output.contains("-lpresent"));
+
+        let err = PkgConfigParser::run_pkg_config(["run_absent"], path).unwrap_err();
+        let ProbeError::PackageNotFound { package, stderr } = &err else {
+            panic!("expected PackageNotFound, got {:?}", err);
+        };
+        assert_eq!(package, "run_absent");
+        assert!(stderr.contains("run_absent"));
+        assert_eq!(err.missing_packages(), ["run_absent"]);
+
+        // A missing dependency is named, not the package requiring it
+        let err = PkgConfigParser::run_pkg_config_cflags(["run_needs_dep"], path).unwrap_err();
+        assert_eq!(err.missing_packages(), ["run_absent_dep"]);
+
+        // An unmet version constraint is not a missing package
+        let err = PkgConfigParser::run_pkg_config(["run_present >= 2.0"], path).unwrap_err();
+        let ProbeError::ExitFailure { status, stderr } = &err else {
+            panic!("expected ExitFailure, got {:?}", err);
+        };
+        assert!(!status.success());
+        assert!(stderr.contains("run_present"));
+        assert!(err.missing_packages().is_empty());
+    }
+
+    #[test]
+    fn test_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("var_pkg.pc"),
+            "prefix=/opt/spdk\nlibdir=${prefix}/lib\nincludedir=${prefix}/include\n\
+             Name: var_pkg\nDescription: test\nVersion: 1.0\nLibs: -L${libdir} -lvar\n",
+        )
+        .unwrap();
+        let path = dir.path().to_str();
+        let parser = PkgConfigParser::new();
+
+        assert_eq!(
+            parser.variable("var_pkg", "libdir", path).unwrap(),
+            "/opt/spdk/lib"
+        );
+        assert_eq!(
+            parser
+                .variables("var_pkg", &["prefix", "includedir"], path)
+                .unwrap(),
+            ["/opt/spdk", "/opt/spdk/include"]
+        );
+
+        let err = parser.variable("var_pkg", "datadir", path).unwrap_err();
+        assert_eq!(
+            err,
+            ProbeError::UndefinedVariable {
+                package: "var_pkg".to_string(),
+                variable: "datadir".to_string(),
+            }
+        );
+        assert!(
+            parser
+                .variables("var_pkg", &["prefix", "datadir"], path)
+                .is_err()
+        );
+
+        let err = parser.variable("var_absent", "libdir", path).unwrap_err();
+        assert_eq!(err.missing_packages(), ["var_absent"]);
+    }
+
+    #[test]
+    fn test_link_line_preview_round_trip() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("my libs");
+        std::fs::create_dir(&dir).unwrap();
+        for lib in ["wa_a", "wa_b", "st_c", "wa_d"] {
+            File::create(dir.join(format!("lib{}.a", lib))).unwrap();
+        }
+        let dir = dir.canonicalize().unwrap().display().to_string();
+
+        let flags = vec![
+            LinkerFlag::SearchPath(dir.clone()),
+            LinkerFlag::LinkerArg("-Wl,--as-needed".to_string()),
+            lib("wa_a", LinkKind::WholeArchive),
+            lib("wa_b", LinkKind::WholeArchive),
+            lib("st_c", LinkKind::Static),
+            lib("pthread", LinkKind::Default),
+            LinkerFlag::VerbatimLibrary("liblz4.so.1".to_string()),
+            lib("wa_d", LinkKind::WholeArchive),
+        ];
+        let preview = link_line_preview(&flags);
+        assert_eq!(
+            preview,
+            format!(
+                "'-L{}' -Wl,--as-needed -Wl,-Bstatic -Wl,--whole-archive -lwa_a -lwa_b \
+                 -Wl,--no-whole-archive -lst_c -Wl,-Bdynamic -lpthread -l:liblz4.so.1 \
+                 -Wl,-Bstatic -Wl,--whole-archive -lwa_d -Wl,--no-whole-archive -Wl,-Bdynamic",
+                dir
+            )
+        );
+
+        assert_eq!(PkgConfigParser::new().parse(&preview), flags);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("-lfoo"), "-lfoo");
+        assert_eq!(shell_quote("-L/a b"), "'-L/a b'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(split_args(&shell_quote("it's a $path")), ["it's a $path"]);
+    }
+
+    #[test]
+    fn test_links_metadata() {
+        let mut pkg = pkg_with_libs(vec![
+            LinkerFlag::LinkerArg("-Wl,--as-needed".to_string()),
+            LinkerFlag::SearchPath("/opt/spdk/lib".to_string()),
+            lib("spdk_log", LinkKind::Static),
+            LinkerFlag::SearchPath("/opt/dpdk/lib".to_string()),
+        ]);
+        pkg.cflags = vec![
+            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+            CompilerFlag::Define {
+                key: "_GNU_SOURCE".to_string(),
+                value: None,
+            },
+            CompilerFlag::SystemIncludePath(PathBuf::from("/opt/dpdk/include")),
+            CompilerFlag::QuoteIncludePath(PathBuf::from("/opt/local")),
+            CompilerFlag::ForcedInclude(PathBuf::from("/opt/rte_config.h")),
+            CompilerFlag::Define {
+                key: "RTE_MAX_LCORE".to_string(),
+                value: Some("128".to_string()),
+            },
+        ];
+
+        assert_eq!(
+            to_links_metadata(&pkg),
+            [
+                "cargo:include=/opt/spdk/include:/opt/dpdk/include",
+                "cargo:lib_dir=/opt/spdk/lib",
+                "cargo:defines=_GNU_SOURCE;RTE_MAX_LCORE=128",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_links_metadata_empty() {
+        assert_eq!(
+            to_links_metadata(&PkgConfig::default()),
+            ["cargo:include=", "cargo:defines="]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    fn every_variant() -> PkgConfig {
+        PkgConfig {
+            libs: vec![
+                LinkerFlag::SearchPath("/opt/spdk/lib".to_string()),
+                LinkerFlag::FrameworkSearchPath("/Library/Frameworks".to_string()),
+                LinkerFlag::LinkerArg("-Wl,--start-group".to_string()),
+                LinkerFlag::Library {
+                    name: "spdk_env_dpdk".to_string(),
+                    kind: LinkKind::WholeArchive,
+                },
+                LinkerFlag::Library {
+                    name: "spdk_log".to_string(),
+                    kind: LinkKind::Static,
+                },
+                LinkerFlag::LinkerArg("-Wl,--end-group".to_string()),
+                LinkerFlag::VerbatimLibrary("libisal.a".to_string()),
+                LinkerFlag::Framework("CoreFoundation".to_string()),
+                LinkerFlag::Library {
+                    name: "numa".to_string(),
+                    kind: LinkKind::Default,
+                },
+            ],
+            cflags: vec![
+                CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
+                CompilerFlag::Define {
+                    key: "FOO".to_string(),
+                    value: None,
+                },
+                CompilerFlag::Define {
+                    key: "BAR".to_string(),
+                    value: Some("1".to_string()),
+                },
+                CompilerFlag::Undefine("NDEBUG".to_string()),
+                CompilerFlag::SystemIncludePath(PathBuf::from("/usr/include/dpdk")),
+                CompilerFlag::QuoteIncludePath(PathBuf::from("/opt/spdk/quote")),
+                CompilerFlag::ForcedInclude(PathBuf::from("rte_config.h")),
+                CompilerFlag::Pthread,
+                CompilerFlag::Codegen("-march=native".to_string()),
+            ],
+            pc_files: vec![PathBuf::from("/opt/spdk/lib/pkgconfig/spdk_env_dpdk.pc")],
+            tool: Some(ToolInfo {
+                path: PathBuf::from("/usr/bin/pkg-config"),
+                version: "1.8.1".to_string(),
+            }),
+            decisions: vec![
+                (
+                    "spdk_env_dpdk".to_string(),
+                    LinkKind::WholeArchive,
+                    DecisionReason::StaticRegion,
+                ),
+                (
+                    "spdk_log".to_string(),
+                    LinkKind::Static,
+                    DecisionReason::StaticAvailable,
+                ),
+                (
+                    "numa".to_string(),
+                    LinkKind::Default,
+                    DecisionReason::Environment,
+                ),
+                ("a".to_string(), LinkKind::Default, DecisionReason::Override),
+                (
+                    "b".to_string(),
+                    LinkKind::WholeArchive,
+                    DecisionReason::WholeArchiveRegion,
+                ),
+                (
+                    "c".to_string(),
+                    LinkKind::Default,
+                    DecisionReason::NoStaticArchive,
+                ),
+                (
+                    "d".to_string(),
+                    LinkKind::Default,
+                    DecisionReason::PreferDynamic,
+                ),
+                (
+                    "e".to_string(),
+                    LinkKind::Static,
+                    DecisionReason::StaticRegion,
+                ),
+            ],
+            warnings: vec!["-Wl,--whole-archive is never closed".to_string()],
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_round_trip_every_variant() {
+        let pkg = every_variant();
+        let json = pkg.to_json().unwrap();
+
+        // Stable names
+        assert!(json.contains(r#""search-path": "/opt/spdk/lib""#));
+        assert!(json.contains(r#""kind": "whole-archive""#));
+        assert!(json.contains(r#""static-region""#));
+        assert!(json.contains(r#""pthread""#));
+
+        let back = PkgConfig::from_json(&json).unwrap();
+        assert_eq!(back.libs, pkg.libs);
+        assert_eq!(back.cflags, pkg.cflags);
+        assert_eq!(back.pc_files, pkg.pc_files);
+        assert_eq!(back.decisions(), pkg.decisions());
+        assert_eq!(back.warnings(), pkg.warnings());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_round_trip_probe() {
+        let dir = create_test_dir_with_libs(&["jsontest"]);
+        write_pc_file(dir.path(), "jsontest", "jsontest");
+        let pkg = PkgConfigParser::new()
+            .probe(["jsontest"], Some(dir.path().to_str().unwrap()))
+            .unwrap();
+
+        let back = PkgConfig::from_json(&pkg.to_json().unwrap()).unwrap();
+        assert_eq!(back.libs, pkg.libs);
+        assert_eq!(back.cflags, pkg.cflags);
+        assert_eq!(back.pc_files, pkg.pc_files);
+        assert_eq!(back.decisions(), pkg.decisions());
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", unix))]
+    fn test_json_non_utf8_path_is_error() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut pkg = PkgConfig::default();
+        pkg.cflags
+            .push(CompilerFlag::IncludePath(PathBuf::from(OsStr::from_bytes(
+                b"/opt/\xff/include",
+            ))));
+        assert!(pkg.to_json().is_err());
+    }
+
+    #[test]
+    fn test_prefer_dynamic_probe() {
+        let dir = create_test_dir_with_libs(&["dynmain", "dynpriv"]);
+        std::fs::write(
+            dir.path().join("dyntest.pc"),
+            format!(
+                "Name: dyntest\nDescription: test\nVersion: 1.0\n\
+                 Libs: -L{} -ldynmain\nLibs.private: -ldynpriv\n",
+                dir.path().display()
+            ),
+        )
+        .unwrap();
+        let search = dir.path().to_str().unwrap();
+        let lib_dir = normalize_path(search).display().to_string();
+
+        // Static: private libs included, archives detected, no rpath
+        let pkg = PkgConfigParser::new()
+            .force_whole_archive(["dynmain"])
+            .probe(["dyntest"], Some(search))
+            .unwrap();
+        assert_eq!(library_names(&pkg), ["dynmain", "dynpriv"]);
+        assert_eq!(
+            link_kind(&pkg.libs, "dynmain"),
+            Some(LinkKind::WholeArchive)
+        );
+        assert_eq!(link_kind(&pkg.libs, "dynpriv"), Some(LinkKind::Static));
+        assert!(
+            !pkg.libs
+                .iter()
+                .any(|f| matches!(f, LinkerFlag::LinkerArg(_)))
+        );
+
+        // Dynamic: no private libs, no forced whole-archive, rpath added
+        let pkg = PkgConfigParser::new()
+            .prefer_dynamic(true)
+            .rpath(true)
+            .probe(["dyntest"], Some(search))
+            .unwrap();
+        assert_eq!(
+            pkg.libs,
+            [
+                LinkerFlag::SearchPath(lib_dir.clone()),
+                LinkerFlag::LinkerArg(format!("-Wl,-rpath,{}", lib_dir)),
+                LinkerFlag::Library {
+                    name: "dynmain".to_string(),
+                    kind: LinkKind::Default,
+                },
+            ]
+        );
+        assert_eq!(
+            pkg.decisions(),
+            [(
+                "dynmain".to_string(),
+                LinkKind::Default,
+                DecisionReason::PreferDynamic
+            )]
+        );
+    }
+
+    #[test]
+    fn test_prefer_dynamic_keeps_explicit_overrides() {
+        let dir = create_test_dir_with_libs(&["keepstatic", "other"]);
+        let output = format!(
+            "-L{} -Wl,--whole-archive -lkeepstatic -lother -Wl,--no-whole-archive",
+            dir.path().display()
+        );
+
+        let flags = PkgConfigParser::new()
+            .prefer_dynamic(true)
+            .override_link_kind([("keepstatic", LinkKind::Static)])
+            .parse_with_overrides(&output, &EnvOverrides::default())
+            .unwrap();
+        assert_eq!(link_kind(&flags, "keepstatic"), Some(LinkKind::Static));
+        assert_eq!(link_kind(&flags, "other"), Some(LinkKind::Default));
+    }
+
+    #[test]
+    fn test_bstatic_region() {
+        let dir = create_test_dir_with_libs(&["bs_a", "bs_b", "bs_c", "bs_d"]);
+        let sys = create_test_dir_with_libs(&["bs_sys"]);
+        let output = format!(
+            "-L{} -L{} -Wl,-Bstatic -lbs_a -Wl,--whole-archive -lbs_b -Wl,--no-whole-archive \
+             -lbs_sys -Wl,-Bdynamic -lbs_c -Wl,-Bstatic,--whole-archive -lbs_d \
+             -Wl,--no-whole-archive,-Bdynamic",
+            dir.path().display(),
+            sys.path().display()
+        );
+
+        let (flags, decisions, _) = PkgConfigParser::new()
+            .system_roots([sys.path()])
+            .parse_detailed(&output, &EnvOverrides::default())
+            .unwrap();
+
+        assert_eq!(link_kind(&flags, "bs_a"), Some(LinkKind::Static));
+        assert_eq!(link_kind(&flags, "bs_b"), Some(LinkKind::WholeArchive));
+        assert_eq!(link_kind(&flags, "bs_c"), Some(LinkKind::Static));
+        assert_eq!(link_kind(&flags, "bs_d"), Some(LinkKind::WholeArchive));
+        assert_eq!(decisions[0].2, DecisionReason::StaticRegion);
+        // -Bdynamic ended the region, so bs_c is static by detection
+        assert_eq!(decisions[3].2, DecisionReason::StaticAvailable);
+
+        // An archive only under a system root still links dynamically
+        assert_eq!(link_kind(&flags, "bs_sys"), Some(LinkKind::Default));
+        assert_eq!(decisions[2].2, DecisionReason::NoStaticArchive);
+
+        // Markers are not emitted
+        assert!(!flags.iter().any(|f| matches!(f, LinkerFlag::LinkerArg(_))));
+    }
+
+    #[test]
+    fn test_bstatic_upgraded_by_later_whole_archive() {
+        let dir = create_test_dir_with_libs(&["bs_up"]);
+        let output = format!(
+            "-L{} -Wl,-Bstatic -lbs_up -Wl,-Bdynamic -Wl,--whole-archive -lbs_up -Wl,--no-whole-archive",
+            dir.path().display()
+        );
+
+        let flags = PkgConfigParser::new()
+            .parse_with_overrides(&output, &EnvOverrides::default())
+            .unwrap();
+        assert_eq!(link_kind(&flags, "bs_up"), Some(LinkKind::WholeArchive));
+    }
+
+    #[test]
+    fn test_bstatic_missing_archive() {
+        let dir = create_test_dir_with_libs(&["bs_present"]);
+        let output = format!(
+            "-L{} -Wl,-Bstatic -lbs_present -lbs_absent -Wl,-Bdynamic",
+            dir.path().display()
+        );
+
+        let err = PkgConfigParser::new()
+            .parse_with_overrides(&output, &EnvOverrides::default())
+            .unwrap_err();
+        match &err {
+            ProbeError::StaticArchiveNotFound { library, searched } => {
+                assert_eq!(library, "bs_absent");
+                assert_eq!(searched[0], normalize_path(dir.path().to_str().unwrap()));
+                // Then the system roots' library directories, nothing hardcoded
+                assert_eq!(searched[1..], PkgConfigParser::new().system_lib_dirs());
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(err.to_string().contains("libbs_absent.a"));
+
+        // prefer_dynamic does not need the archive
+        let flags = PkgConfigParser::new()
+            .prefer_dynamic(true)
+            .parse_with_overrides(&output, &EnvOverrides::default())
+            .unwrap();
+        assert_eq!(link_kind(&flags, "bs_absent"), Some(LinkKind::Default));
+
+        // Left to the linker when missing archives are allowed
+        let (flags, decisions, _) = PkgConfigParser::new()
+            .allow_missing_static_archive(true)
+            .parse_detailed(&output, &EnvOverrides::default())
+            .unwrap();
+        assert_eq!(link_kind(&flags, "bs_absent"), Some(LinkKind::Static));
+        assert_eq!(decisions[1].2, DecisionReason::StaticRegion);
+    }
+
+    #[test]
+    fn test_bstatic_archive_in_multiarch_system_dir() {
+        // A root laid out like /usr, with the archive only in lib/<triple>
+        let root = tempfile::tempdir().unwrap();
+        let multiarch = root.path().join("lib/x86_64-linux-gnu");
+        std::fs::create_dir_all(&multiarch).unwrap();
+        File::create(multiarch.join("libbs_multiarch.a")).unwrap();
+        let output = "-Wl,-Bstatic -lbs_multiarch -Wl,-Bdynamic";
+
+        let parser = PkgConfigParser::new().system_roots([root.path()]);
+        assert!(parser.system_lib_dirs().contains(&multiarch));
+
+        // Found without a -L flag, and linked dynamically like any archive
+        // under a system root
+        let (flags, decisions, _) = parser
+            .parse_detailed(output, &EnvOverrides::default())
+            .unwrap();
+        assert_eq!(link_kind(&flags, "bs_multiarch"), Some(LinkKind::Default));
+        assert_eq!(decisions[0].2, DecisionReason::NoStaticArchive);
+
+        // Not searched unless the root is a system root
+        assert!(matches!(
+            PkgConfigParser::new().parse_with_overrides(output, &EnvOverrides::default()),
+            Err(ProbeError::StaticArchiveNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_probe_lib_dirs() {
+        let spdk = create_test_dir_with_libs(&["ld_event", "ld_bdev", "ld_util"]);
+        let dpdk = create_test_dir_with_libs(&["ld_eal"]);
+        let includes = vec![PathBuf::from("/src/ld/include")];
+
+        let pkg = PkgConfigParser::new()
+            .force_whole_archive(["ld_event"])
+            .probe_lib_dirs(
+                &[spdk.path().to_path_buf(), dpdk.path().to_path_buf()],
+                &includes,
+                &["ld_event", "ld_bdev", "ld_util", "ld_eal"],
+                &["numa"],
+            )
+            .unwrap();
+
+        // Libraries keep the given order, system libraries come last
+        let names: Vec<&str> = pkg
+            .libs
+            .iter()
+            .filter_map(|flag| match flag {
+                LinkerFlag::Library { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, ["ld_event", "ld_bdev", "ld_util", "ld_eal", "numa"]);
+        assert_eq!(
+            link_kind(&pkg.libs, "ld_event"),
+            Some(LinkKind::WholeArchive)
+        );
+        assert_eq!(link_kind(&pkg.libs, "ld_bdev"), Some(LinkKind::Static));
+        assert_eq!(link_kind(&pkg.libs, "ld_eal"), Some(LinkKind::Static));
+        assert_eq!(link_kind(&pkg.libs, "numa"), Some(LinkKind::Default));
+
+        // Both directories are searched at link time
+        let search_paths = pkg
+            .libs
+            .iter()
+            .filter(|flag| matches!(flag, LinkerFlag::SearchPath(_)))
+            .count();
+        assert_eq!(search_paths, 2);
+
+        assert_eq!(pkg.cflags, [CompilerFlag::IncludePath(includes[0].clone())]);
+        assert!(pkg.pc_files.is_empty());
+        assert_eq!(pkg.decisions().len(), 5);
+    }
+
+    #[test]
+    fn test_probe_lib_dirs_missing_archives() {
+        let spdk = create_test_dir_with_libs(&["lm_bdev"]);
+        let dirs = vec![spdk.path().to_path_buf()];
+
+        let err = PkgConfigParser::new()
+            .probe_lib_dirs(&dirs, &[], &["lm_nvme", "lm_bdev", "lm_eal"], &["numa"])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ProbeError::MissingArchives {
+                missing: vec!["lm_nvme".to_string(), "lm_eal".to_string()],
+                searched: dirs.clone(),
+            }
+        );
+        assert!(err.to_string().contains("lm_nvme, lm_eal"));
+
+        // A shared library only counts with prefer_dynamic
+        File::create(spdk.path().join("liblm_nvme.so")).unwrap();
+        File::create(spdk.path().join("liblm_eal.so")).unwrap();
+        let err = PkgConfigParser::new()
+            .probe_lib_dirs(&dirs, &[], &["lm_nvme", "lm_bdev"], &[])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Libraries not found: lm_nvme (searched: {})",
+                spdk.path().display()
+            )
+        );
+        let pkg = PkgConfigParser::new()
+            .prefer_dynamic(true)
+            .probe_lib_dirs(&dirs, &[], &["lm_nvme", "lm_eal"], &[])
+            .unwrap();
+        assert_eq!(link_kind(&pkg.libs, "lm_nvme"), Some(LinkKind::Default));
+    }
+
+    #[test]
+    fn test_split_libs_match_combined() {
+        let dir = create_test_dir_with_libs(&["sp_a", "sp_b", "sp_c"]);
+        let lib_dir = dir.path().display();
+        let full = format!(
+            "-L{lib_dir} -lsp_a -Wl,--whole-archive -lsp_b -Wl,--no-whole-archive -pthread \
+             -Wl,--as-needed -lsp_c -lnuma"
+        );
+        let dirs = format!("-L{lib_dir}");
+        let names = "-lsp_a -lsp_b -lsp_c -lnuma";
+        let other = "-Wl,--whole-archive -Wl,--no-whole-archive -pthread -Wl,--as-needed";
+
+        let parser = PkgConfigParser::new();
+        let combined = parser
+            .parse_detailed(&full, &EnvOverrides::default())
+            .unwrap();
+        let tokens = merge_split_libs(&full, &dirs, names, other);
+        let split = parser
+            .parse_tokens(&tokens, &EnvOverrides::default())
+            .unwrap();
+        assert_eq!(split, combined);
+        assert_eq!(link_kind(&split.0, "sp_b"), Some(LinkKind::WholeArchive));
+    }
+
+    #[test]
+    fn test_split_libs_drop_unreported_tokens() {
+        // "-lbogus" appears in the combined output but no query reports it;
+        // "/opt/late" and "-llate" are only in the split queries
+        let full = "-L/opt/a -la @response.txt -lbogus";
+        let tokens = merge_split_libs(full, "-L/opt/late -L/opt/a", "-la -llate", "@response.txt");
+        assert_eq!(
+            tokens,
+            ["-L/opt/late", "-L/opt/a", "-la", "@response.txt", "-llate"]
+        );
+    }
+
+    #[test]
+    fn test_probe_split_matches_probe() {
+        let dir = create_test_dir_with_libs(&["psplit_a", "psplit_b"]);
+        std::fs::write(
+            dir.path().join("psplit.pc"),
+            format!(
+                "Name: psplit\nDescription: test\nVersion: 1.0\n\
+                 Cflags: -I/opt/psplit/include -DPSPLIT\n\
+                 Libs: -L{} -lpsplit_a -Wl,--whole-archive -lpsplit_b -Wl,--no-whole-archive -pthread\n\
+                 Libs.private: -lnuma\n",
+                dir.path().display()
+            ),
+        )
+        .unwrap();
+        let search = dir.path().to_str().unwrap();
+
+        let parser = PkgConfigParser::new();
+        let combined = parser.probe(["psplit"], Some(search)).unwrap();
+        let split = parser.probe_split(["psplit"], Some(search)).unwrap();
+
+        assert_eq!(split.libs, combined.libs);
+        assert_eq!(split.cflags, combined.cflags);
+        assert_eq!(split.decisions(), combined.decisions());
+        assert_eq!(
+            library_names(&split),
+            ["psplit_a", "psplit_b", "pthread", "numa"]
+        );
+    }
+
+    fn create_test_dir_with_files(files: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for file in files {
+            File::create(dir.path().join(file)).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_target_env_from_lookup() {
+        let lookup = |vars: &'static [(&'static str, &'static str)]| {
+            TargetEnv::from_lookup(move |var| {
+                vars.iter()
+                    .find(|(k, _)| *k == var)
+                    .map(|(_, v)| v.to_string())
+            })
+        };
+
+        assert_eq!(lookup(&[]), TargetEnv::Unix);
+        assert_eq!(
+            lookup(&[("TARGET", "x86_64-unknown-linux-gnu")]),
+            TargetEnv::Unix
+        );
+        assert_eq!(
+            lookup(&[("TARGET", "x86_64-pc-windows-msvc")]),
+            TargetEnv::Msvc
+        );
+        assert_eq!(
+            lookup(&[("TARGET", "x86_64-pc-windows-gnu")]),
+            TargetEnv::WindowsGnu
+        );
+        // The cfg variables win over the triple
+        assert_eq!(
+            lookup(&[
+                ("TARGET", "x86_64-pc-windows-gnu"),
+                ("CARGO_CFG_TARGET_OS", "windows"),
+                ("CARGO_CFG_TARGET_ENV", "msvc"),
+            ]),
+            TargetEnv::Msvc
+        );
+    }
+
+    #[test]
+    fn test_msvc_static_detection() {
+        let dir = create_test_dir_with_files(&["foo.lib", "libbar.a", "libbaz.dll.a"]);
+        let output = format!("-L{} -lfoo -lbar -lbaz", dir.path().display());
 
-        assert!(parser.is_static_available("foo", &dirs));
-        assert!(parser.is_static_available("bar", &dirs));
-        assert!(!parser.is_static_available("baz", &dirs));
+        let msvc = PkgConfigParser::new().target_env(TargetEnv::Msvc);
+        let flags = msvc.parse(&output);
+        assert_eq!(link_kind(&flags, "foo"), Some(LinkKind::Static));
+        assert_eq!(link_kind(&flags, "bar"), Some(LinkKind::Default));
+        assert_eq!(link_kind(&flags, "baz"), Some(LinkKind::Default));
+
+        let mingw = PkgConfigParser::new().target_env(TargetEnv::WindowsGnu);
+        let flags = mingw.parse(&output);
+        assert_eq!(link_kind(&flags, "foo"), Some(LinkKind::Static));
+        assert_eq!(link_kind(&flags, "bar"), Some(LinkKind::Static));
+        // Import libraries link the DLL
+        assert_eq!(link_kind(&flags, "baz"), Some(LinkKind::Default));
+
+        let unix = PkgConfigParser::new().target_env(TargetEnv::Unix);
+        let flags = unix.parse(&output);
+        assert_eq!(link_kind(&flags, "foo"), Some(LinkKind::Default));
+        assert_eq!(link_kind(&flags, "bar"), Some(LinkKind::Static));
     }
 
     #[test]
-    fn test_system_root_exclusion() {
-        // Create a temp dir inside /tmp (not a system root)
-        let dir = create_test_dir_with_libs(&["mylib"]);
-        let parser = PkgConfigParser::new(); // default system_roots = ["/usr"]
-        let dirs = vec![dir.path().to_path_buf()];
-
-        // Should find it since /tmp is not under /usr
-        assert!(parser.is_static_available("mylib", &dirs));
+    fn test_system_root_drive_letters() {
+        let windows = TargetEnv::WindowsGnu;
+        let lib = Path::new(r"C:\msys64\mingw64\lib");
 
-        // Now test with the dir as a system root
-        let parser_with_root = PkgConfigParser::new().system_roots([dir.path()]);
-        assert!(!parser_with_root.is_static_available("mylib", &dirs));
+        assert!(is_under_root(lib, Path::new("c:/msys64"), windows));
+        assert!(is_under_root(lib, Path::new(r"C:\msys64\"), windows));
+        assert!(is_under_root(lib, Path::new("C:/"), windows));
+        assert!(is_under_root(
+            Path::new(r"\\?\C:\msys64\mingw64\lib"),
+            Path::new("C:/msys64/mingw64"),
+            TargetEnv::Msvc
+        ));
+        assert!(!is_under_root(lib, Path::new("C:/msys"), windows));
+        assert!(!is_under_root(lib, Path::new("D:/msys64"), windows));
+        // Unix targets keep component-wise, case-sensitive comparison
+        assert!(!is_under_root(
+            Path::new("/USR/lib"),
+            Path::new("/usr"),
+            TargetEnv::Unix
+        ));
     }
 
     #[test]
-    fn test_parse_with_static_detection() {
-        let dir = create_test_dir_with_libs(&["spdk_env", "rte_mempool"]);
-        let parser = PkgConfigParser::new();
-
-        let output = format!("-L{} -lspdk_env -lpthread -lnuma", dir.path().display());
-        let flags = parser.parse(&output);
+    fn test_msvc_cargo_directives() {
+        let flags = vec![
+            LinkerFlag::SearchPath("C:/spdk/lib".to_string()),
+            LinkerFlag::LinkerArg("-Wl,--start-group".to_string()),
+            lib("spdk_log", LinkKind::Static),
+            lib("spdk_event", LinkKind::WholeArchive),
+            LinkerFlag::LinkerArg("-Wl,--end-group".to_string()),
+            lib("ws2_32", LinkKind::Default),
+        ];
 
-        assert_eq!(flags.len(), 4);
-        // spdk_env has .a → Static
-        assert!(
-            matches!(&flags[1], LinkerFlag::Library { name, kind } if name == "spdk_env" && *kind == LinkKind::Static)
-        );
-        // pthread has no .a in test dir → Default
-        assert!(
-            matches!(&flags[2], LinkerFlag::Library { name, kind } if name == "pthread" && *kind == LinkKind::Default)
+        assert_eq!(
+            to_target_cargo_directives(&flags, true, TargetEnv::Msvc),
+            [
+                "cargo:rustc-link-search=native=C:/spdk/lib",
+                "cargo:rustc-link-lib=static:-bundle=spdk_log",
+                "cargo:rustc-link-lib=static:-bundle=spdk_event",
+                "cargo:rustc-link-arg=/WHOLEARCHIVE:spdk_event.lib",
+                "cargo:rustc-link-lib=ws2_32",
+            ]
         );
-        // numa has no .a in test dir → Default
-        assert!(
-            matches!(&flags[3], LinkerFlag::Library { name, kind } if name == "numa" && *kind == LinkKind::Default)
+        // Other targets are unchanged
+        assert_eq!(
+            to_target_cargo_directives(&flags, true, TargetEnv::WindowsGnu),
+            to_cargo_directives(&flags, true)
         );
     }
 
     #[test]
-    fn test_whole_archive_region_with_static_detection() {
-        let dir = create_test_dir_with_libs(&["spdk_log", "rte_mempool_ring", "rte_eal"]);
-        let parser = PkgConfigParser::new();
+    fn test_allow_system_libs_sets_env() {
+        let env_of = |allow| {
+            let cmd = PkgConfigParser::pkg_config_command(
+                "pkg-config",
+                &["--libs"],
+                ["foo"],
+                None,
+                allow,
+            );
+            cmd.get_envs()
+                .find(|(key, _)| *key == "PKG_CONFIG_ALLOW_SYSTEM_LIBS")
+                .map(|(_, value)| value.map(|v| v.to_owned()))
+        };
 
-        let output = format!(
-            "-L{} -lspdk_log -Wl,--whole-archive -lrte_mempool_ring -lrte_eal -Wl,--no-whole-archive -lpthread",
-            dir.path().display()
-        );
-        let flags = parser.parse(&output);
+        assert_eq!(env_of(true), Some(Some("1".into())));
+        assert_eq!(env_of(false), None);
 
-        assert_eq!(flags.len(), 5);
-        // spdk_log before --whole-archive, has .a → Static
-        assert!(
-            matches!(&flags[1], LinkerFlag::Library { name, kind } if name == "spdk_log" && *kind == LinkKind::Static)
-        );
-        // rte_mempool_ring inside --whole-archive, has .a → WholeArchive
-        assert!(
-            matches!(&flags[2], LinkerFlag::Library { name, kind } if name == "rte_mempool_ring" && *kind == LinkKind::WholeArchive)
-        );
-        // rte_eal inside --whole-archive, has .a → WholeArchive
-        assert!(
-            matches!(&flags[3], LinkerFlag::Library { name, kind } if name == "rte_eal" && *kind == LinkKind::WholeArchive)
-        );
-        // pthread after --no-whole-archive, no .a → Default
-        assert!(
-            matches!(&flags[4], LinkerFlag::Library { name, kind } if name == "pthread" && *kind == LinkKind::Default)
+        // The crate keeps the system directories pkg-config now reports
+        let flags = PkgConfigParser::new()
+            .allow_system_libs(true)
+            .parse("-L/usr/lib -lfoo");
+        assert_eq!(search_paths(&flags), ["/usr/lib"]);
+    }
+
+    #[test]
+    fn test_parse_search_dirs() {
+        let output = "install: /usr/lib/gcc/x86_64-linux-gnu/12/\n\
+                      programs: =/usr/libexec/gcc/x86_64-linux-gnu/12/\n\
+                      libraries: =/nix/store/abc-gcc/lib/:/nix/store/abc-gcc/lib/../lib64:/nix/store/abc-gcc/lib\n";
+        assert_eq!(
+            parse_search_dirs(output),
+            [
+                PathBuf::from("/nix/store/abc-gcc/lib"),
+                PathBuf::from("/nix/store/abc-gcc/lib64"),
+            ]
         );
+        assert!(parse_search_dirs("install: /usr\n").is_empty());
     }
 
     #[test]
-    fn test_upgrade_to_whole_archive_on_duplicate() {
-        let dir = create_test_dir_with_libs(&["rte_mempool_ring"]);
-        let parser = PkgConfigParser::new();
+    #[cfg(unix)]
+    fn test_detect_system_roots_from_compiler() {
+        use std::os::unix::fs::PermissionsExt;
 
-        let output = format!(
-            "-L{} -lrte_mempool_ring -Wl,--whole-archive -l:librte_mempool_ring.a -Wl,--no-whole-archive",
-            dir.path().display()
-        );
-        let flags = parser.parse(&output);
+        let libs = create_test_dir_with_libs(&["toolchainlib"]);
+        let bin = tempfile::tempdir().unwrap();
+        let cc = bin.path().join("fake-cc");
+        std::fs::write(
+            &cc,
+            format!(
+                "#!/bin/sh\n[ \"$1\" = -print-search-dirs ] || exit 1\n\
+                 echo 'install: /opt/fake/'\n\
+                 echo 'libraries: ={}:/opt/fake/lib'\n",
+                libs.path().display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&cc, std::fs::Permissions::from_mode(0o755)).unwrap();
 
-        // Should only have 2 entries (SearchPath + one library)
-        assert_eq!(flags.len(), 2);
-        // Should be upgraded to WholeArchive
-        assert!(
-            matches!(&flags[1], LinkerFlag::Library { name, kind } if name == "rte_mempool_ring" && *kind == LinkKind::WholeArchive)
+        let roots = compiler_search_dirs(cc.to_str().unwrap());
+        assert_eq!(
+            roots,
+            [
+                normalize_path(libs.path().to_str().unwrap()),
+                PathBuf::from("/opt/fake/lib")
+            ]
         );
+
+        let output = format!("-L{} -ltoolchainlib", libs.path().display());
+        let _env = ScopedEnv::set(&[("CC", cc.to_str().unwrap())]);
+        let flags = PkgConfigParser::new().detect_system_roots().parse(&output);
+        assert_eq!(link_kind(&flags, "toolchainlib"), Some(LinkKind::Default));
+        let flags = PkgConfigParser::new().parse(&output);
+        assert_eq!(link_kind(&flags, "toolchainlib"), Some(LinkKind::Static));
     }
 
     #[test]
-    fn test_dedup_libs() {
-        let dir = create_test_dir_with_libs(&["spdk_log"]);
-        let parser = PkgConfigParser::new();
+    fn test_system_roots_env_override() {
+        let libs = create_test_dir_with_libs(&["envrootlib"]);
+        let output = format!("-L{} -lenvrootlib", libs.path().display());
+        let _env = ScopedEnv::set(&[
+            (ENV_SYSTEM_ROOTS, libs.path().to_str().unwrap()),
+            // Not run: the variable takes precedence over the compiler
+            ("CC", "/nonexistent/cc"),
+        ]);
 
-        let output = format!(
-            "-L{} -lspdk_log -l:libspdk_log.a -lspdk_log",
-            dir.path().display()
-        );
+        let parser = PkgConfigParser::new().detect_system_roots();
+        assert_eq!(parser.detected_system_roots(), [libs.path()]);
         let flags = parser.parse(&output);
+        assert_eq!(link_kind(&flags, "envrootlib"), Some(LinkKind::Default));
+    }
 
-        // Should only have 2 entries (SearchPath + one library)
-        assert_eq!(flags.len(), 2);
+    /// Writes an executable script that prints `version` for `--version`.
+    #[cfg(unix)]
+    fn fake_tool(dir: &Path, name: &str, version: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\n[ \"$1\" = --version ] || exit 1\necho '{}'\n",
+                version
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_str().unwrap().to_string()
     }
 
     #[test]
-    fn test_parse_cflags_include_paths() {
-        let parser = PkgConfigParser::new();
-        let output = "-I/opt/spdk/include -I/usr/include/dpdk";
-        let flags = parser.parse_cflags(output);
+    #[cfg(unix)]
+    fn test_check_tool_versions() {
+        let dir = tempfile::tempdir().unwrap();
 
-        assert_eq!(flags.len(), 2);
+        for version in ["0.29", "0.29.2", "1.8.1", "2.1.0"] {
+            let tool = fake_tool(dir.path(), &format!("pc-{}", version), version);
+            let info = PkgConfigParser::check_tool_at(&tool, "0.29").unwrap();
+            assert_eq!(info.version, version);
+            assert_eq!(info.path, PathBuf::from(&tool));
+        }
+
+        let old = fake_tool(dir.path(), "pc-old", "0.23");
+        let err = PkgConfigParser::check_tool_at(&old, "0.29").unwrap_err();
         assert_eq!(
-            flags[0],
-            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include"))
+            err,
+            ProbeError::ToolTooOld {
+                version: "0.23".to_string(),
+                required: "0.29".to_string(),
+            }
         );
         assert_eq!(
-            flags[1],
-            CompilerFlag::IncludePath(PathBuf::from("/usr/include/dpdk"))
+            err.to_string(),
+            "pkg-config 0.23 too old, need >= 0.29 for --static ordering"
         );
+        // The minimum is configurable
+        assert!(PkgConfigParser::check_tool_at(&old, "0.20").is_ok());
+
+        let garbage = fake_tool(dir.path(), "pc-garbage", "not a version");
+        assert!(matches!(
+            PkgConfigParser::check_tool_at(&garbage, "0.29"),
+            Err(ProbeError::Failed(_))
+        ));
     }
 
     #[test]
-    fn test_parse_cflags_defines() {
-        let parser = PkgConfigParser::new();
-        let output = "-DFOO -DBAR=1 -DBAZ=hello";
-        let flags = parser.parse_cflags(output);
+    fn test_check_tool_missing() {
+        for program in ["/nonexistent/pkg-config", "pkgconf-test-no-such-tool"] {
+            let err = PkgConfigParser::check_tool_at(program, "0.29").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("{} not found in PATH (set PKG_CONFIG to override)", program)
+            );
+        }
+    }
 
-        assert_eq!(flags.len(), 3);
-        assert_eq!(
-            flags[0],
-            CompilerFlag::Define {
-                key: "FOO".to_string(),
-                value: None
-            }
-        );
-        assert_eq!(
-            flags[1],
-            CompilerFlag::Define {
-                key: "BAR".to_string(),
-                value: Some("1".to_string())
-            }
-        );
-        assert_eq!(
-            flags[2],
-            CompilerFlag::Define {
-                key: "BAZ".to_string(),
-                value: Some("hello".to_string())
-            }
-        );
+    #[test]
+    fn test_probe_reports_tool() {
+        let dir = create_test_dir_with_libs(&["tool_lib"]);
+        write_pc_file(dir.path(), "tooltest", "tool_lib");
+        let search = dir.path().to_str().unwrap();
+
+        let pkg = PkgConfigParser::new()
+            .probe(["tooltest"], Some(search))
+            .unwrap();
+        let tool = pkg.tool.expect("probe sets the tool");
+        assert!(tool.path.is_file());
+        assert!(parse_version(&tool.version).is_some());
+
+        let err = PkgConfigParser::new()
+            .min_tool_version("999")
+            .probe(["tooltest"], Some(search))
+            .unwrap_err();
+        assert!(matches!(err, ProbeError::ToolTooOld { .. }));
     }
 
     #[test]
-    fn test_parse_cflags_mixed_with_unknown() {
+    fn test_atleast_version() {
+        let dir = create_test_dir_with_libs(&["ver_lib"]);
+        std::fs::write(
+            dir.path().join("ver_pkg.pc"),
+            format!(
+                "Name: ver_pkg\nDescription: test\nVersion: 24.01\nLibs: -L{} -lver_lib\n",
+                dir.path().display()
+            ),
+        )
+        .unwrap();
+        let search = dir.path().to_str();
         let parser = PkgConfigParser::new();
-        let output = "-I/opt/spdk/include -std=c11 -DFOO -Wall -I/usr/include/dpdk";
-        let flags = parser.parse_cflags(output);
 
-        // Unknown flags (-std=c11, -Wall) are silently ignored
-        assert_eq!(flags.len(), 3);
+        assert_eq!(parser.modversion("ver_pkg", search).unwrap(), "24.01");
         assert_eq!(
-            flags[0],
-            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include"))
+            Version::parse(&parser.modversion("ver_pkg", search).unwrap()),
+            Some(Version::new(24, 1, 0))
         );
+
+        for required in ["23.09", "24.01"] {
+            let pkg = PkgConfigParser::new()
+                .atleast_version(required)
+                .probe(["ver_pkg"], search)
+                .unwrap();
+            assert_eq!(library_names(&pkg), ["ver_lib"]);
+        }
+
+        let err = PkgConfigParser::new()
+            .atleast_version("25.01")
+            .probe_split(["ver_pkg"], search)
+            .unwrap_err();
         assert_eq!(
-            flags[1],
-            CompilerFlag::Define {
-                key: "FOO".to_string(),
-                value: None
+            err,
+            ProbeError::PackageTooOld {
+                package: "ver_pkg".to_string(),
+                version: "24.01".to_string(),
+                required: "25.01".to_string(),
             }
         );
-        assert_eq!(
-            flags[2],
-            CompilerFlag::IncludePath(PathBuf::from("/usr/include/dpdk"))
-        );
+        assert_eq!(err.to_string(), "ver_pkg 24.01 is too old, need >= 25.01");
+
+        // A missing package is still reported as missing
+        let err = PkgConfigParser::new()
+            .atleast_version("1.0")
+            .probe(["ver_pkg", "ver_absent"], search)
+            .unwrap_err();
+        assert_eq!(err.missing_packages(), ["ver_absent"]);
     }
 
     #[test]
-    fn test_parse_cflags_dedup() {
-        let parser = PkgConfigParser::new();
-        let output = "-I/opt/spdk/include -I/opt/spdk/include -DFOO -DFOO";
-        let flags = parser.parse_cflags(output);
-
-        assert_eq!(flags.len(), 2);
+    fn test_version_parse() {
+        assert_eq!(Version::parse("25.09.1"), Some(Version::new(25, 9, 1)));
+        assert_eq!(Version::parse("22"), Some(Version::new(22, 0, 0)));
+        assert_eq!(Version::parse("24.01-pre.3"), Some(Version::new(24, 1, 0)));
+        assert_eq!(Version::parse(" 1.2.3.4 "), Some(Version::new(1, 2, 3)));
+        assert_eq!(Version::parse("v24.01"), None);
+        assert!(Version::new(24, 1, 0) < Version::new(24, 1, 1));
+        assert!(Version::new(22, 9, 0) < Version::new(24, 1, 0));
     }
 
     #[test]
-    fn test_to_clang_arg() {
+    fn test_pkg_config_program_from() {
+        fn lookup<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+            move |var| {
+                vars.iter()
+                    .find(|(name, _)| *name == var)
+                    .map(|(_, value)| value.to_string())
+            }
+        }
+
+        assert_eq!(pkg_config_program_from(lookup(&[])), "pkg-config");
         assert_eq!(
-            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")).to_clang_arg(),
-            "-I/opt/spdk/include"
+            pkg_config_program_from(lookup(&[("PKG_CONFIG", "pkgconf")])),
+            "pkgconf"
         );
+        let cross = [
+            ("TARGET", "aarch64-unknown-linux-gnu"),
+            ("HOST", "x86_64-unknown-linux-gnu"),
+            ("TARGET_PKG_CONFIG", "aarch64-linux-gnu-pkg-config"),
+            ("PKG_CONFIG", "pkgconf"),
+        ];
         assert_eq!(
-            CompilerFlag::Define {
-                key: "FOO".to_string(),
-                value: None
-            }
-            .to_clang_arg(),
-            "-DFOO"
+            pkg_config_program_from(lookup(&cross)),
+            "aarch64-linux-gnu-pkg-config"
         );
+        // TARGET_PKG_CONFIG only applies when cross-compiling
+        let native = [
+            ("TARGET", "x86_64-unknown-linux-gnu"),
+            ("HOST", "x86_64-unknown-linux-gnu"),
+            ("TARGET_PKG_CONFIG", "aarch64-linux-gnu-pkg-config"),
+        ];
+        assert_eq!(pkg_config_program_from(lookup(&native)), "pkg-config");
         assert_eq!(
-            CompilerFlag::Define {
-                key: "FOO".to_string(),
-                value: Some("1".to_string())
-            }
-            .to_clang_arg(),
-            "-DFOO=1"
+            pkg_config_program_from(lookup(&[("PKG_CONFIG", "")])),
+            "pkg-config"
         );
     }
 
     #[test]
-    fn test_to_clang_args() {
-        let flags = vec![
-            CompilerFlag::IncludePath(PathBuf::from("/opt/spdk/include")),
-            CompilerFlag::Define {
-                key: "FOO".to_string(),
-                value: None,
-            },
-        ];
-        let args = to_clang_args(&flags);
-        assert_eq!(args, vec!["-I/opt/spdk/include", "-DFOO"]);
+    #[cfg(unix)]
+    fn test_pkg_config_exe() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = create_test_dir_with_libs(&["exe_lib"]);
+        write_pc_file(dir.path(), "exetest", "exe_lib");
+        let wrapper = dir.path().join("target-pkg-config");
+        std::fs::write(&wrapper, "#!/bin/sh\nexec pkg-config \"$@\"\n").unwrap();
+        std::fs::set_permissions(&wrapper, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let pkg = PkgConfigParser::new()
+            .pkg_config_exe(wrapper.to_str().unwrap())
+            .probe(["exetest"], dir.path().to_str())
+            .unwrap();
+        assert_eq!(pkg.tool.as_ref().unwrap().path, wrapper);
+        assert_eq!(library_names(&pkg), ["exe_lib"]);
+
+        let err = PkgConfigParser::new()
+            .pkg_config_exe("/nonexistent/aarch64-linux-gnu-pkg-config")
+            .probe(["exetest"], dir.path().to_str())
+            .unwrap_err();
+        assert!(matches!(err, ProbeError::ToolNotFound { .. }));
     }
 
     #[test]
-    fn test_to_cargo_directive_search_path() {
-        let flag = LinkerFlag::SearchPath("/opt/spdk/lib".to_string());
+    fn test_sysroot_static_detection() {
+        let sysroot = tempfile::tempdir().unwrap();
+        let host_dir = sysroot.path().join("opt/spdk/lib");
+        std::fs::create_dir_all(&host_dir).unwrap();
+        File::create(host_dir.join("libspdk_env.a")).unwrap();
+
+        let output = "-L/opt/spdk/lib -lspdk_env";
+        let parser = PkgConfigParser::new()
+            .target_env(TargetEnv::Unix)
+            .sysroot(sysroot.path());
+        // The linker sees the target path; the archive is found on the host
         assert_eq!(
-            flag.to_cargo_directive(true),
-            "cargo:rustc-link-search=native=/opt/spdk/lib"
+            parser.parse(output),
+            [
+                LinkerFlag::SearchPath("/opt/spdk/lib".to_string()),
+                lib("spdk_env", LinkKind::Static),
+            ]
         );
         assert_eq!(
-            flag.to_cargo_directive(false),
-            "cargo:rustc-link-search=native=/opt/spdk/lib"
+            PkgConfigParser::new()
+                .target_env(TargetEnv::Unix)
+                .parse(output)[1],
+            lib("spdk_env", LinkKind::Default)
         );
-    }
 
-    #[test]
-    fn test_to_cargo_directive_default_lib() {
-        let flag = LinkerFlag::Library {
-            name: "pthread".to_string(),
-            kind: LinkKind::Default,
-        };
+        // Directories pkg-config already prefixed with the sysroot are kept
+        let prefixed = format!("-L{} -lspdk_env", host_dir.display());
         assert_eq!(
-            flag.to_cargo_directive(true),
-            "cargo:rustc-link-lib=pthread"
+            parser.parse(&prefixed)[1],
+            lib("spdk_env", LinkKind::Static)
         );
+
+        // -Bstatic regions look in the sysroot too
         assert_eq!(
-            flag.to_cargo_directive(false),
-            "cargo:rustc-link-lib=pthread"
+            parser.parse("-L/opt/spdk/lib -Wl,-Bstatic -lspdk_env -Wl,-Bdynamic")[1],
+            lib("spdk_env", LinkKind::Static)
         );
     }
 
     #[test]
-    fn test_to_cargo_directive_static_lib() {
-        let flag = LinkerFlag::Library {
-            name: "spdk_log".to_string(),
-            kind: LinkKind::Static,
-        };
-        assert_eq!(
-            flag.to_cargo_directive(true),
-            "cargo:rustc-link-lib=static:-bundle=spdk_log"
-        );
+    fn test_emit_to_writer() {
+        let dir = create_test_dir_with_libs(&["emit_a", "emit_b"]);
+        let flags = PkgConfigParser::new()
+            .target_env(TargetEnv::Unix)
+            .parse(&format!(
+                "-L{} -lemit_a -Wl,--whole-archive -lemit_b -Wl,--no-whole-archive -lnuma",
+                dir.path().display()
+            ));
+
+        let mut out = Vec::new();
+        emit_cargo_metadata_to(&flags, true, &mut out).unwrap();
+        let directives: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
         assert_eq!(
-            flag.to_cargo_directive(false),
-            "cargo:rustc-link-lib=static=spdk_log"
+            directives,
+            [
+                format!(
+                    "cargo:rustc-link-search=native={}",
+                    normalize_path(dir.path().to_str().unwrap()).display()
+                )
+                .as_str(),
+                "cargo:rustc-link-lib=static:-bundle=emit_a",
+                "cargo:rustc-link-lib=static:+whole-archive,-bundle=emit_b",
+                "cargo:rustc-link-lib=numa",
+            ]
         );
+
+        let mut pkg = pkg_with_libs(flags);
+        pkg.pc_files = vec![PathBuf::from("/opt/emit/emit.pc")];
+        let mut out = Vec::new();
+        emit_links_metadata_to(&pkg, &mut out).unwrap();
+        emit_cargo_rerun_to(&pkg, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("cargo:include=\n"));
+        assert!(out.contains("cargo:rerun-if-changed=/opt/emit/emit.pc\n"));
     }
 
     #[test]
-    fn test_to_cargo_directive_whole_archive() {
-        let flag = LinkerFlag::Library {
-            name: "rte_eal".to_string(),
-            kind: LinkKind::WholeArchive,
-        };
+    #[cfg(unix)]
+    fn test_probe_from_output_matches_probe() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let libs_dir = create_test_dir_with_libs(&["spdk_log", "rte_eal", "rte_mempool_ring"]);
+        let libs_output = format!(
+            "-L{} -lspdk_log -Wl,--whole-archive -lrte_mempool_ring -Wl,--no-whole-archive -lrte_eal -lnuma",
+            libs_dir.path().display()
+        );
+        let cflags_output = "-I/opt/spdk/include -DSPDK_CONFIG";
+
+        // A pkg-config that prints the recorded output
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("pkg-config");
+        std::fs::write(
+            &tool,
+            format!(
+                "#!/bin/sh\ncase \"$*\" in\n--version) echo 2.1.0 ;;\n*--cflags*) echo '{}' ;;\n*--libs*) echo '{}' ;;\n*) exit 1 ;;\nesac\n",
+                cflags_output, libs_output
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let parser = PkgConfigParser::new()
+            .pkg_config_exe(tool.to_str().unwrap())
+            .force_whole_archive(["rte_eal"]);
+        let probed = parser.probe(["spdk_env_dpdk"], None).unwrap();
+        let offline = parser.probe_from_output(&libs_output, cflags_output);
+
+        assert_eq!(offline.libs, probed.libs);
+        assert_eq!(offline.cflags, probed.cflags);
+        assert_eq!(offline.decisions(), probed.decisions());
+        assert!(offline.pc_files.is_empty());
+        assert!(offline.tool.is_none());
         assert_eq!(
-            flag.to_cargo_directive(true),
-            "cargo:rustc-link-lib=static:+whole-archive,-bundle=rte_eal"
+            offline.libs[1..],
+            [
+                lib("spdk_log", LinkKind::Static),
+                lib("rte_mempool_ring", LinkKind::WholeArchive),
+                lib("rte_eal", LinkKind::WholeArchive),
+                lib("numa", LinkKind::Default),
+            ]
         );
+
+        // System roots apply to the existence checks as well
+        let rooted = PkgConfigParser::new()
+            .system_roots([libs_dir.path()])
+            .probe_from_output(&libs_output, cflags_output);
         assert_eq!(
-            flag.to_cargo_directive(false),
-            "cargo:rustc-link-lib=static:+whole-archive=rte_eal"
+            rooted.libs[1..],
+            [
+                lib("spdk_log", LinkKind::Default),
+                lib("rte_mempool_ring", LinkKind::Default),
+                lib("rte_eal", LinkKind::Default),
+                lib("numa", LinkKind::Default),
+            ]
         );
     }
 
     #[test]
-    fn test_to_cargo_directive_linker_arg() {
-        let flag = LinkerFlag::LinkerArg("-Wl,--export-dynamic".to_string());
+    fn test_probe_from_files() {
+        let libs_dir = create_test_dir_with_libs(&["spdk_env"]);
+        let dir = tempfile::tempdir().unwrap();
+        let libs_path = dir.path().join("libs.txt");
+        let cflags_path = dir.path().join("cflags.txt");
+        std::fs::write(
+            &libs_path,
+            format!("-L{} -lspdk_env -lpthread\n", libs_dir.path().display()),
+        )
+        .unwrap();
+        std::fs::write(&cflags_path, "-I/opt/spdk/include\n").unwrap();
+
+        let pkg = PkgConfig::from_files(&libs_path, &cflags_path).unwrap();
+        assert_eq!(library_names(&pkg), ["spdk_env", "pthread"]);
+        assert_eq!(pkg.libs[1], lib("spdk_env", LinkKind::Static));
         assert_eq!(
-            flag.to_cargo_directive(true),
-            "cargo:rustc-link-arg=-Wl,--export-dynamic"
+            pkg.cflags,
+            [CompilerFlag::IncludePath(PathBuf::from(
+                "/opt/spdk/include"
+            ))]
         );
+
+        let missing = dir.path().join("missing.txt");
+        let err = PkgConfig::from_files(&libs_path, &missing).unwrap_err();
+        assert!(matches!(&err, ProbeError::ReadOutput { path, .. } if *path == missing));
+        assert!(err.to_string().contains("missing.txt"));
     }
 }
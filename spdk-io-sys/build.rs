@@ -5,86 +5,379 @@
 //!
 //! Environment variables:
 //! - `PKG_CONFIG_PATH`: Must include SPDK's pkg-config directory (e.g., /opt/spdk/lib/pkgconfig)
+//! - `SPDK_DYNAMIC=1`: Link against SPDK's shared libraries (with an rpath) for faster
+//!   development builds
+//! - `SPDK_LIB_DIR` / `SPDK_INCLUDE_DIR`: Skip pkg-config and use the libraries and
+//!   headers in these directories (`:`-separated lists), e.g. an uninstalled in-source
+//!   build without `.pc` files. The link order then comes from `build_support.rs`.
+//! - `SPDK_SYSTEM_LIBS`: Extra comma-separated system libraries for `SPDK_LIB_DIR` builds
+//! - `SPDK_SOURCE_DIR`: SPDK source tree matching the install, for module headers
+//!   that are not installed (`bdev_null.h`, see `shim.c`)
+//!
+//! With the `vendored` feature SPDK is built from source instead (see `vendor.rs`)
+//! and `PKG_CONFIG_PATH` is not used.
+//!
+//! The component features (`bdev`, `nvme`, ...) select which SPDK libraries are
+//! linked and which headers are bound. `full` (the default) enables all of them.
+//!
+//! SPDK's `static inline` helpers have no symbol to bind, so `shim.c` wraps a curated
+//! set of them as `spdk_rs_*` functions. It is compiled with the probed cflags and
+//! linked ahead of SPDK.
+//!
+//! The SPDK version is read from `spdk/version.h` and turned into
+//! `spdk_ge_<major>_<minor>` cfgs (see `build_support.rs`), which are also passed
+//! to dependents as `DEP_SPDK_VERSION` / `DEP_SPDK_VERSION_CFGS`.
 
+use std::collections::HashSet;
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pkgconf::{CompilerFlag, PkgConfig, PkgConfigParser};
+
+mod build_support;
+mod vendor;
+
+/// Optional SPDK components, each behind the cargo feature of the same name.
+/// Dependencies between them are declared in Cargo.toml.
+const COMPONENTS: &[&str] = &[
+    "bdev", "blob", "nvme", "nvmf", "accel", "sock", "rpc", "event",
+];
+
+/// Item name prefixes (`spdk_<prefix>*`, `SPDK_<PREFIX>*`) bound for each component
+/// when `full` is not enabled.
+const BINDING_PREFIXES: &[(&str, &[&str])] = &[
+    ("bdev", &["bdev"]),
+    ("blob", &["blob", "bs"]),
+    ("nvme", &["nvme"]),
+    ("nvmf", &["nvmf"]),
+    ("accel", &["accel"]),
+    ("sock", &["sock"]),
+    ("rpc", &["rpc", "jsonrpc"]),
+    (
+        "event",
+        &[
+            "app",
+            "event",
+            "reactor",
+            "subsystem",
+            "scheduler",
+            "governor",
+            "framework",
+        ],
+    ),
+];
+
+/// Headers that are always bound. They are allowlisted by file because
+/// their items (`spdk_env_*`, `spdk_dma_*`, `spdk_poller_*`, ...) share no prefix.
+const CORE_HEADERS: &str = r".*/spdk/(env|thread|cpuset|log|string|json|version)\.h";
+
+/// Whether every cargo feature in `features` is enabled.
+fn enabled(features: &[&str]) -> bool {
+    features
+        .iter()
+        .all(|feature| env::var_os(format!("CARGO_FEATURE_{}", feature.to_uppercase())).is_some())
+}
 
-use pkgconf::PkgConfigParser;
+/// `-DSPDK_IO_<COMPONENT>` for each enabled component, which select the
+/// parts of `wrapper.h` and `shim.h` that are compiled.
+fn component_defines() -> Vec<String> {
+    COMPONENTS
+        .iter()
+        .filter(|component| enabled(&[component]))
+        .map(|component| format!("-DSPDK_IO_{}", component.to_uppercase()))
+        .collect()
+}
+
+/// Names whose features are all enabled (no features means always).
+fn select<'a>(items: &[(&'a str, &[&str])]) -> Vec<&'a str> {
+    items
+        .iter()
+        .filter(|(_, features)| enabled(features))
+        .map(|(name, _)| *name)
+        .collect()
+}
 
 fn main() {
     println!("cargo:rerun-if-changed=wrapper.h");
+    println!("cargo:rerun-if-changed=shim.h");
+    println!("cargo:rerun-if-changed=shim.c");
     println!("cargo:rerun-if-env-changed=PKG_CONFIG_PATH");
+    println!("cargo:rerun-if-env-changed=SPDK_DYNAMIC");
+    println!("cargo:rerun-if-env-changed=SPDK_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=SPDK_INCLUDE_DIR");
+    println!("cargo:rerun-if-env-changed=SPDK_SYSTEM_LIBS");
+    println!("cargo:rerun-if-env-changed=SPDK_SOURCE_DIR");
+
+    let dynamic = env::var("SPDK_DYNAMIC").is_ok_and(|v| v == "1");
+    let vendored = env::var_os("CARGO_FEATURE_VENDORED").is_some();
+    if vendored && dynamic {
+        panic!("SPDK_DYNAMIC=1 is not supported with the `vendored` feature (static build only)");
+    }
+    let lib_dirs: Option<Vec<PathBuf>> =
+        env::var_os("SPDK_LIB_DIR").map(|dirs| env::split_paths(&dirs).collect());
+    if vendored && lib_dirs.is_some() {
+        panic!("SPDK_LIB_DIR is not supported with the `vendored` feature");
+    }
 
-    // Core SPDK libraries we need
-    let spdk_libs = [
-        "spdk_env_dpdk",
-        "spdk_thread",
-        "spdk_bdev",
-        "spdk_blob",
-        "spdk_blob_bdev",
-        "spdk_nvme",
-        "spdk_nvmf", // NVMe-oF target
-        "spdk_log",
-        "spdk_util",
-        "spdk_json",
-        "spdk_rpc",
-        "spdk_jsonrpc",
-        "spdk_event",
-        "spdk_event_bdev", // Register bdev subsystem with event framework
-        "spdk_event_nvmf", // Register nvmf subsystem with event framework
-        "spdk_bdev_malloc",
-        "spdk_bdev_null",
-        "spdk_accel",      // Accel framework + software module
-        "spdk_sock",       // Socket abstraction
-        "spdk_sock_posix", // POSIX socket implementation
-        "libdpdk",
-        "spdk_syslibs", // System dependencies (isal, ssl, crypto, uuid, fuse3, aio, etc.)
-    ];
+    // SPDK libraries we need, with the features that select them
+    let spdk_libs = select(&[
+        ("spdk_env_dpdk", &[]),
+        ("spdk_thread", &[]),
+        ("spdk_bdev", &["bdev"]),
+        ("spdk_blob", &["blob"]),
+        ("spdk_blob_bdev", &["blob"]),
+        ("spdk_nvme", &["nvme"]),
+        ("spdk_nvmf", &["nvmf"]), // NVMe-oF target
+        ("spdk_log", &[]),
+        ("spdk_util", &[]),
+        ("spdk_json", &[]),
+        ("spdk_rpc", &["rpc"]),
+        ("spdk_jsonrpc", &["rpc"]),
+        ("spdk_event", &["event"]),
+        ("spdk_event_bdev", &["event", "bdev"]), // Register bdev subsystem with event framework
+        ("spdk_event_nvmf", &["event", "nvmf"]), // Register nvmf subsystem with event framework
+        ("spdk_bdev_malloc", &["bdev"]),
+        ("spdk_bdev_null", &["bdev"]),
+        ("spdk_accel", &["accel"]), // Accel framework + software module
+        ("spdk_sock", &["sock"]),   // Socket abstraction
+        ("spdk_sock_posix", &["sock"]), // POSIX socket implementation
+        ("libdpdk", &[]),
+        ("spdk_syslibs", &[]), // System dependencies (isal, ssl, crypto, uuid, fuse3, aio, etc.)
+    ]);
 
     // PKG_CONFIG_PATH for SPDK installation
-    let pkg_config_path =
-        env::var("PKG_CONFIG_PATH").unwrap_or_else(|_| "/opt/spdk/lib/pkgconfig".to_string());
+    let pkg_config_path = if vendored {
+        vendor::build()
+    } else {
+        env::var("PKG_CONFIG_PATH").unwrap_or_else(|_| "/opt/spdk/lib/pkgconfig".to_string())
+    };
 
     // SPDK event subsystem libraries use SPDK_SUBSYSTEM_REGISTER() which creates
     // constructor functions. These need --whole-archive or the linker will discard them.
     // Bdev modules also use SPDK_BDEV_MODULE_REGISTER() with constructors.
     // Accel modules use SPDK_ACCEL_MODULE_REGISTER() with constructors.
     // NVMe transports use SPDK_NVME_TRANSPORT_REGISTER() with constructors.
-    let parser = PkgConfigParser::new().force_whole_archive([
-        "spdk_event_bdev",
-        "spdk_event_nvmf",
-        "spdk_event_accel",
-        "spdk_event_vmd",
-        "spdk_event_sock",
-        "spdk_event_iobuf",
-        "spdk_event_keyring",
-        "spdk_bdev_null",
-        "spdk_bdev_malloc",
-        "spdk_accel",      // Contains software accel module (accel_sw)
-        "spdk_sock_posix", // POSIX socket implementation
-        "spdk_nvmf",       // NVMf target with transport registrations
-        "spdk_nvme",       // NVMe initiator with transport registrations (TCP, RDMA, etc.)
+    // Only libraries of enabled components are listed.
+    let whole_archive = select(&[
+        ("spdk_event_bdev", &["event", "bdev"]),
+        ("spdk_event_nvmf", &["event", "nvmf"]),
+        ("spdk_event_accel", &["event"]),
+        ("spdk_event_vmd", &["event"]),
+        ("spdk_event_sock", &["event"]),
+        ("spdk_event_iobuf", &["event"]),
+        ("spdk_event_keyring", &["event"]),
+        ("spdk_bdev_null", &["bdev"]),
+        ("spdk_bdev_malloc", &["bdev"]),
+        ("spdk_accel", &["accel"]), // Contains software accel module (accel_sw)
+        ("spdk_sock_posix", &["sock"]), // POSIX socket implementation
+        ("spdk_nvmf", &["nvmf"]),   // NVMf target with transport registrations
+        ("spdk_nvme", &["nvme"]),   // NVMe initiator with transport registrations (TCP, RDMA, etc.)
     ]);
+    // Shared libraries keep their constructors, so whole-archive is not needed
+    // (and, being an override, would beat prefer_dynamic)
+    let forced: &[&str] = if dynamic { &[] } else { &whole_archive };
+    let parser = PkgConfigParser::new()
+        .force_whole_archive(forced)
+        .prefer_dynamic(dynamic)
+        .rpath(dynamic);
 
     // Single probe call: parses both --libs and --cflags
-    let pkg = parser
-        .probe(spdk_libs, Some(&pkg_config_path))
-        .expect("pkg-config failed");
+    let probed = match &lib_dirs {
+        Some(lib_dirs) => probe_lib_dirs(&parser, lib_dirs, &spdk_libs, &whole_archive),
+        None => parser.probe(&spdk_libs, Some(&pkg_config_path)),
+    };
+    let pkg = match probed {
+        Ok(pkg) => pkg,
+        Err(err) if lib_dirs.is_some() => panic!(
+            "{}\nSPDK_LIB_DIR must list SPDK's build/lib and the DPDK library directory",
+            err
+        ),
+        Err(err) => panic!("{}", err), // Lists missing packages and configure hints
+    };
+
+    for warning in pkg.warnings() {
+        println!("cargo:warning={}", warning);
+    }
+
+    // Before SPDK's directives, so the static linker sees the shim's
+    // references to SPDK first
+    let source_dir = if vendored {
+        Some(vendor::source_dir())
+    } else {
+        env::var_os("SPDK_SOURCE_DIR").map(PathBuf::from)
+    };
+    build_shim(&pkg.cflags, source_dir.as_deref());
+
+    // Collect all directives first so exact duplicates are printed once
+    let mut directives = Vec::new();
+    // Cargo linker directives (no_bundle=true for -sys crate with `links` key)
+    pkgconf::emit_cargo_metadata_to(&pkg.libs, true, &mut directives).unwrap();
+    // Rebuild when SPDK is reinstalled in place (.pc files, archives, headers)
+    pkgconf::emit_cargo_rerun_to(&pkg, &mut directives).unwrap();
+    // Expose DEP_SPDK_INCLUDE / DEP_SPDK_LIB_DIR / DEP_SPDK_DEFINES to dependents
+    pkgconf::emit_links_metadata_to(&pkg, &mut directives).unwrap();
+
+    let mut seen = HashSet::new();
+    for directive in String::from_utf8(directives).unwrap().lines() {
+        if seen.insert(directive.to_string()) {
+            println!("{}", directive);
+        }
+    }
 
-    // Emit cargo linker directives (no_bundle=true for -sys crate with `links` key)
-    pkgconf::emit_cargo_metadata(&pkg.libs, true);
+    emit_version_cfgs(spdk_version(&pkg.cflags).as_deref());
 
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("bindings.rs");
+    generate_bindings(&pkg.cflags, &out_path);
+}
+
+/// Finds SPDK in `SPDK_LIB_DIR` / `SPDK_INCLUDE_DIR` instead of with pkg-config.
+///
+/// The packages (minus DPDK and system libraries, which are listed in
+/// `build_support.rs`) and the whole-archive libraries are the roots of the
+/// SPDK dependency table. Static libraries are grouped, in case the table
+/// misses a dependency cycle.
+fn probe_lib_dirs(
+    parser: &PkgConfigParser,
+    lib_dirs: &[PathBuf],
+    packages: &[&str],
+    whole_archive: &[&str],
+) -> Result<PkgConfig, pkgconf::ProbeError> {
+    let include_dirs: Vec<PathBuf> = match env::var_os("SPDK_INCLUDE_DIR") {
+        Some(dirs) => env::split_paths(&dirs).collect(),
+        None => panic!("SPDK_LIB_DIR is set, but SPDK_INCLUDE_DIR is not"),
+    };
+
+    let roots: Vec<&str> = packages
+        .iter()
+        .chain(whole_archive)
+        .copied()
+        .filter(|name| name.starts_with("spdk_") && *name != "spdk_syslibs")
+        .collect();
+    let libraries = build_support::direct_link_libraries(&roots, lib_dirs);
+
+    let extra = env::var("SPDK_SYSTEM_LIBS").unwrap_or_default();
+    let system_libs: Vec<&str> = build_support::SYSTEM_LIBRARIES
+        .iter()
+        .copied()
+        .chain(
+            extra
+                .split(',')
+                .map(str::trim)
+                .filter(|lib| !lib.is_empty()),
+        )
+        .collect();
+
+    parser.clone().group_static_libs(true).probe_lib_dirs(
+        lib_dirs,
+        &include_dirs,
+        &libraries,
+        &system_libs,
+    )
+}
+
+/// Compiles `shim.c` into a static library, with SPDK's include paths and
+/// defines so the inline helpers see the same headers as the bindings.
+///
+/// With the SPDK source tree, the uninstalled null bdev module header is
+/// made available as well.
+fn build_shim(cflags: &[CompilerFlag], source_dir: Option<&Path>) {
+    let mut build = cc::Build::new();
+    build.file("shim.c");
+    if let Some(dir) = source_dir
+        .map(|dir| dir.join("module/bdev/null"))
+        .filter(|dir| dir.is_dir())
+    {
+        build.include(dir);
+    }
+    for flag in cflags {
+        build.flag(flag.to_clang_arg());
+    }
+    for define in component_defines() {
+        build.flag(define);
+    }
+    build.compile("spdk_io_shim");
+}
+
+/// Reads the SPDK version from `spdk/version.h` in the include path.
+///
+/// The `.pc` files are no help here: their `Version` is each library's
+/// soname version, not the SPDK release.
+fn spdk_version(cflags: &[CompilerFlag]) -> Option<String> {
+    cflags
+        .iter()
+        .filter_map(|flag| match flag {
+            CompilerFlag::IncludePath(dir) | CompilerFlag::SystemIncludePath(dir) => {
+                Some(dir.join("spdk/version.h"))
+            }
+            _ => None,
+        })
+        .find_map(|header| fs::read_to_string(header).ok())
+        .and_then(|header| build_support::version_from_header(&header))
+}
+
+/// Emits the version cfg ladder for `version`, and declares every cfg of the
+/// ladder so code gated on releases newer than the installed one still passes
+/// the `unexpected_cfgs` lint.
+fn emit_version_cfgs(version: Option<&str>) {
+    let known = build_support::known_cfgs();
+    for cfg in &known {
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+    }
+    println!("cargo:known_version_cfgs={}", known.join(","));
+
+    let Some(version) = version else {
+        println!("cargo:warning=SPDK version not found (no spdk/version.h), no spdk_ge_* cfgs set");
+        return;
+    };
+    let cfgs = build_support::version_cfgs(version);
+    for cfg in &cfgs {
+        println!("cargo:rustc-cfg={}", cfg);
+    }
+    println!("cargo:rustc-env=SPDK_VERSION={}", version);
+    // DEP_SPDK_VERSION / DEP_SPDK_VERSION_CFGS for dependents' build scripts
+    println!("cargo:version={}", version);
+    println!("cargo:version_cfgs={}", cfgs.join(","));
+}
+
+/// Generates bindings for `wrapper.h` with bindgen and writes them to `out_path`.
+fn generate_bindings(cflags: &[pkgconf::CompilerFlag], out_path: &std::path::Path) {
     // Build clang args for bindgen from parsed cflags
-    let clang_args = pkgconf::to_clang_args(&pkg.cflags);
+    let clang_args = pkgconf::to_clang_args(cflags);
 
-    // Generate bindings
-    let bindings = bindgen::Builder::default()
+    let mut builder = bindgen::Builder::default()
         .header("wrapper.h")
-        .clang_args(&clang_args)
+        .clang_args(&clang_args);
+
+    // wrapper.h only includes the headers of enabled components
+    builder = builder.clang_args(component_defines());
+
+    if enabled(&["full"]) {
         // Allowlist SPDK types and functions
-        .allowlist_function("spdk_.*")
-        .allowlist_type("spdk_.*")
-        .allowlist_var("SPDK_.*")
+        builder = builder
+            .allowlist_function("spdk_.*")
+            .allowlist_type("spdk_.*")
+            .allowlist_var("SPDK_.*");
+    } else {
+        builder = builder
+            .allowlist_file(CORE_HEADERS)
+            .allowlist_function("spdk_rs_.*");
+        let prefixes: Vec<&str> = BINDING_PREFIXES
+            .iter()
+            .filter(|(component, _)| enabled(&[component]))
+            .flat_map(|(_, prefixes)| prefixes.iter().copied())
+            .collect();
+        if !prefixes.is_empty() {
+            let names = prefixes.join("|");
+            builder = builder
+                .allowlist_function(format!("spdk_({})(_.*)?", names))
+                .allowlist_type(format!("spdk_({})(_.*)?", names))
+                .allowlist_var(format!("SPDK_({})(_.*)?", names.to_uppercase()));
+        }
+    }
+
+    // Generate bindings
+    let bindings = builder
         // Also allow some DPDK types we need
         .allowlist_type("rte_.*")
         .allowlist_function("rte_.*")
@@ -127,9 +420,7 @@ fn main() {
         .generate()
         .expect("Failed to generate SPDK bindings");
 
-    // Write bindings to OUT_DIR
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     bindings
-        .write_to_file(out_path.join("bindings.rs"))
+        .write_to_file(out_path)
         .expect("Failed to write bindings");
 }
@@ -0,0 +1,415 @@
+//! Tables and helpers shared by the build script and the crate's unit tests.
+//!
+//! # Version cfgs
+//!
+//! The build script turns the installed SPDK version into a ladder of
+//! `spdk_ge_<major>_<minor>` cfgs, one per entry of [`VERSION_LADDER`] at or
+//! below it, so wrappers can be gated on the release that introduced an API:
+//!
+//! ```ignore
+//! #[cfg(spdk_ge_25_01)]
+//! pub fn keyring_auth(...) { ... }
+//! ```
+//!
+//! # Linking without pkg-config
+//!
+//! With `SPDK_LIB_DIR`, the libraries are found by scanning directories, so
+//! the link order pkg-config derives from `Requires` comes from
+//! [`SPDK_DEPENDENCIES`] and [`DPDK_LIBRARIES`] instead (see
+//! [`direct_link_libraries`]).
+
+use std::path::PathBuf;
+
+/// SPDK releases that get a cfg, oldest first.
+///
+/// Only append to this list; removing an entry breaks code gated on it.
+pub const VERSION_LADDER: &[(u32, u32)] = &[
+    (24, 1),
+    (24, 5),
+    (24, 9),
+    (25, 1),
+    (25, 5),
+    (25, 9),
+    (26, 1),
+];
+
+/// The cfg name for a release, e.g. `spdk_ge_24_09`.
+pub fn cfg_name(major: u32, minor: u32) -> String {
+    format!("spdk_ge_{}_{:02}", major, minor)
+}
+
+/// Every cfg the ladder can produce, for `rustc-check-cfg`.
+pub fn known_cfgs() -> Vec<String> {
+    VERSION_LADDER
+        .iter()
+        .map(|&(major, minor)| cfg_name(major, minor))
+        .collect()
+}
+
+/// Parses the release out of `MAJOR.MINOR[.PATCH][SUFFIX]`, e.g. `26.01`,
+/// `24.09.1` or `25.05-pre`.
+pub fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor: String = parts
+        .next()?
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    Some((major, minor.parse().ok()?))
+}
+
+/// The cfgs enabled for `version`: every ladder entry at or below it.
+///
+/// Pre-releases (`25.05-pre`, SPDK's master branch) count as the release
+/// they lead up to. Unparseable versions enable nothing.
+pub fn version_cfgs(version: &str) -> Vec<String> {
+    let Some(release) = parse_version(version) else {
+        return Vec::new();
+    };
+    VERSION_LADDER
+        .iter()
+        .filter(|&&entry| entry <= release)
+        .map(|&(major, minor)| cfg_name(major, minor))
+        .collect()
+}
+
+/// Reads the version from the text of `spdk/version.h`, formatted like
+/// SPDK's `SPDK_VERSION_STRING` without the `SPDK v` prefix.
+pub fn version_from_header(header: &str) -> Option<String> {
+    let define = |name: &str| {
+        header.lines().find_map(|line| {
+            let mut words = line.split_whitespace();
+            (words.next() == Some("#define") && words.next() == Some(name))
+                .then(|| words.collect::<Vec<_>>().join(" "))
+        })
+    };
+
+    let major: u32 = define("SPDK_VERSION_MAJOR")?.parse().ok()?;
+    let minor: u32 = define("SPDK_VERSION_MINOR")?.parse().ok()?;
+    let patch: u32 = define("SPDK_VERSION_PATCH")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let suffix = define("SPDK_VERSION_SUFFIX").unwrap_or_default();
+
+    let mut version = format!("{}.{:02}", major, minor);
+    if patch != 0 {
+        version.push_str(&format!(".{}", patch));
+    }
+    version.push_str(suffix.trim_matches('"'));
+    Some(version)
+}
+
+/// SPDK libraries and the SPDK libraries they call into.
+///
+/// Mirrors the `Requires` of SPDK's `.pc` files for the libraries spdk-io-sys
+/// links. Every dependency must have an entry of its own.
+pub const SPDK_DEPENDENCIES: &[(&str, &[&str])] = &[
+    ("spdk_event_bdev", &["spdk_event", "spdk_init", "spdk_bdev"]),
+    ("spdk_event_nvmf", &["spdk_event", "spdk_init", "spdk_nvmf"]),
+    (
+        "spdk_event_accel",
+        &["spdk_event", "spdk_init", "spdk_accel"],
+    ),
+    ("spdk_event_vmd", &["spdk_event", "spdk_init", "spdk_vmd"]),
+    ("spdk_event_sock", &["spdk_event", "spdk_init", "spdk_sock"]),
+    (
+        "spdk_event_iobuf",
+        &["spdk_event", "spdk_init", "spdk_thread"],
+    ),
+    (
+        "spdk_event_keyring",
+        &["spdk_event", "spdk_init", "spdk_keyring"],
+    ),
+    (
+        "spdk_event",
+        &[
+            "spdk_init",
+            "spdk_rpc",
+            "spdk_trace",
+            "spdk_thread",
+            "spdk_env_dpdk",
+        ],
+    ),
+    (
+        "spdk_init",
+        &["spdk_rpc", "spdk_jsonrpc", "spdk_thread", "spdk_json"],
+    ),
+    (
+        "spdk_nvmf",
+        &[
+            "spdk_bdev",
+            "spdk_nvme",
+            "spdk_sock",
+            "spdk_keyring",
+            "spdk_thread",
+            "spdk_trace",
+        ],
+    ),
+    ("spdk_bdev_malloc", &["spdk_bdev", "spdk_accel"]),
+    ("spdk_bdev_null", &["spdk_bdev"]),
+    ("spdk_blob_bdev", &["spdk_blob", "spdk_bdev"]),
+    ("spdk_blob", &["spdk_dma", "spdk_thread", "spdk_trace"]),
+    (
+        "spdk_bdev",
+        &[
+            "spdk_accel",
+            "spdk_notify",
+            "spdk_dma",
+            "spdk_thread",
+            "spdk_trace",
+        ],
+    ),
+    ("spdk_accel", &["spdk_dma", "spdk_thread", "spdk_trace"]),
+    (
+        "spdk_nvme",
+        &[
+            "spdk_sock",
+            "spdk_keyring",
+            "spdk_dma",
+            "spdk_trace",
+            "spdk_env_dpdk",
+        ],
+    ),
+    ("spdk_sock_posix", &["spdk_sock"]),
+    ("spdk_sock", &["spdk_trace", "spdk_util"]),
+    ("spdk_rpc", &["spdk_jsonrpc"]),
+    ("spdk_jsonrpc", &["spdk_json"]),
+    ("spdk_keyring", &["spdk_json", "spdk_util"]),
+    ("spdk_vmd", &["spdk_env_dpdk"]),
+    ("spdk_notify", &["spdk_json"]),
+    ("spdk_trace", &["spdk_env_dpdk", "spdk_util"]),
+    ("spdk_thread", &["spdk_trace", "spdk_env_dpdk", "spdk_util"]),
+    ("spdk_dma", &["spdk_log"]),
+    ("spdk_env_dpdk", &["spdk_util"]),
+    ("spdk_json", &["spdk_util"]),
+    ("spdk_util", &["spdk_log"]),
+    ("spdk_log", &[]),
+];
+
+/// DPDK libraries linked after SPDK's, in link order.
+///
+/// Libraries marked `false` only exist in some DPDK versions or
+/// configurations (`rte_log` was split out of EAL in 23.11, `rte_power`
+/// pulls in `rte_ethdev`) and are skipped when absent.
+pub const DPDK_LIBRARIES: &[(&str, bool)] = &[
+    ("rte_bus_pci", true),
+    ("rte_pci", true),
+    ("rte_mempool_ring", true),
+    ("rte_power", false),
+    ("rte_timer", false),
+    ("rte_ethdev", false),
+    ("rte_meter", false),
+    ("rte_net", false),
+    ("rte_mbuf", true),
+    ("rte_mempool", true),
+    ("rte_ring", true),
+    ("rte_rcu", true),
+    ("rte_eal", true),
+    ("rte_telemetry", true),
+    ("rte_kvargs", true),
+    ("rte_log", false),
+];
+
+/// ISA-L archives of an in-tree build (`isa-l/.libs`), linked when found.
+/// Otherwise SPDK was built without them or they are system libraries.
+pub const ISAL_LIBRARIES: &[&str] = &["isal_crypto", "isal"];
+
+/// System libraries linked after everything else, like `spdk_syslibs.pc`.
+pub const SYSTEM_LIBRARIES: &[&str] = &["uuid", "numa", "ssl", "crypto", "rt", "dl", "m"];
+
+/// Orders `roots` and the SPDK libraries they need for linking, each
+/// library before its dependencies.
+///
+/// Names missing from [`SPDK_DEPENDENCIES`] are kept, without dependencies.
+pub fn spdk_link_order<'a>(roots: &[&'a str]) -> Vec<&'a str> {
+    fn visit<'a>(name: &'a str, visited: &mut Vec<&'a str>, postorder: &mut Vec<&'a str>) {
+        if visited.contains(&name) {
+            return;
+        }
+        visited.push(name);
+        let deps = SPDK_DEPENDENCIES
+            .iter()
+            .find(|(lib, _)| *lib == name)
+            .map_or(&[][..], |(_, deps)| deps);
+        for dep in deps {
+            visit(dep, visited, postorder);
+        }
+        postorder.push(name);
+    }
+
+    let mut visited = Vec::new();
+    let mut postorder = Vec::new();
+    // Visiting the roots last-first keeps them in their given order
+    for root in roots.iter().rev() {
+        visit(root, &mut visited, &mut postorder);
+    }
+    postorder.reverse();
+    postorder
+}
+
+/// Whether `lib<name>.a` or `lib<name>.so` exists in one of `dirs`.
+pub fn has_library(name: &str, dirs: &[PathBuf]) -> bool {
+    dirs.iter().any(|dir| {
+        ["a", "so"]
+            .iter()
+            .any(|ext| dir.join(format!("lib{}.{}", name, ext)).exists())
+    })
+}
+
+/// Every library to link for `roots` from `lib_dirs`, in link order: SPDK
+/// (see [`spdk_link_order`]), then DPDK and ISA-L.
+///
+/// Optional DPDK libraries and ISA-L are only included if found; missing
+/// required ones are left in so the link step can report them.
+pub fn direct_link_libraries<'a>(roots: &[&'a str], lib_dirs: &[PathBuf]) -> Vec<&'a str> {
+    let mut libraries = spdk_link_order(roots);
+    libraries.extend(
+        DPDK_LIBRARIES
+            .iter()
+            .filter(|(name, required)| *required || has_library(name, lib_dirs))
+            .map(|(name, _)| *name),
+    );
+    libraries.extend(
+        ISAL_LIBRARIES
+            .iter()
+            .filter(|name| has_library(name, lib_dirs)),
+    );
+    libraries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("26.01"), Some((26, 1)));
+        assert_eq!(parse_version("24.09.1"), Some((24, 9)));
+        assert_eq!(parse_version("25.05-pre"), Some((25, 5)));
+        assert_eq!(parse_version("26"), None);
+        assert_eq!(parse_version("v26.01"), None);
+    }
+
+    #[test]
+    fn test_version_cfgs_ladder() {
+        assert_eq!(
+            version_cfgs("24.09"),
+            ["spdk_ge_24_01", "spdk_ge_24_05", "spdk_ge_24_09"]
+        );
+        // Between releases: only the older rungs
+        assert_eq!(version_cfgs("24.06"), ["spdk_ge_24_01", "spdk_ge_24_05"]);
+        assert_eq!(version_cfgs("25.05-pre").last().unwrap(), "spdk_ge_25_05");
+        assert_eq!(version_cfgs("26.01"), known_cfgs());
+        assert!(version_cfgs("23.09").is_empty());
+        assert!(version_cfgs("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_known_cfgs_are_sorted_and_unique() {
+        assert!(VERSION_LADDER.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(known_cfgs().first().unwrap(), "spdk_ge_24_01");
+    }
+
+    #[test]
+    fn test_spdk_dependencies_are_closed() {
+        for (lib, deps) in SPDK_DEPENDENCIES {
+            for dep in *deps {
+                assert!(
+                    SPDK_DEPENDENCIES.iter().any(|(name, _)| name == dep),
+                    "{} depends on {}, which has no entry",
+                    lib,
+                    dep
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_spdk_link_order_respects_dependencies() {
+        let roots: Vec<&str> = SPDK_DEPENDENCIES.iter().map(|(lib, _)| *lib).collect();
+        let order = spdk_link_order(&roots);
+        assert_eq!(order.len(), SPDK_DEPENDENCIES.len());
+
+        let position = |name: &str| order.iter().position(|lib| *lib == name).unwrap();
+        for (lib, deps) in SPDK_DEPENDENCIES {
+            for dep in *deps {
+                assert!(
+                    position(lib) < position(dep),
+                    "{} must come before its dependency {}",
+                    lib,
+                    dep
+                );
+            }
+        }
+        assert_eq!(order.last(), Some(&"spdk_log"));
+    }
+
+    #[test]
+    fn test_spdk_link_order_closure() {
+        let order = spdk_link_order(&["spdk_bdev_malloc", "spdk_thread"]);
+        assert_eq!(order[0], "spdk_bdev_malloc");
+        assert!(order.contains(&"spdk_bdev"));
+        assert!(order.contains(&"spdk_accel"));
+        assert!(!order.contains(&"spdk_nvme"));
+
+        // Unknown names are kept as leaves
+        assert_eq!(spdk_link_order(&["foo"]), ["foo"]);
+    }
+
+    #[test]
+    fn test_direct_link_libraries_scans_dirs() {
+        let spdk = tempfile::tempdir().unwrap();
+        let dpdk = tempfile::tempdir().unwrap();
+        let create = |dir: &tempfile::TempDir, file: &str| {
+            std::fs::File::create(dir.path().join(file)).unwrap();
+        };
+        create(&spdk, "libspdk_log.a");
+        create(&spdk, "libisal.a");
+        for (name, required) in DPDK_LIBRARIES {
+            if *required {
+                create(&dpdk, &format!("lib{}.a", name));
+            }
+        }
+        create(&dpdk, "librte_log.so");
+
+        let dirs = [spdk.path().to_path_buf(), dpdk.path().to_path_buf()];
+        let libraries = direct_link_libraries(&["spdk_log"], &dirs);
+
+        let mut expected = vec!["spdk_log"];
+        expected.extend(
+            DPDK_LIBRARIES
+                .iter()
+                .filter(|(name, required)| *required || *name == "rte_log")
+                .map(|(name, _)| *name),
+        );
+        expected.push("isal");
+        assert_eq!(libraries, expected);
+
+        // Required DPDK libraries stay even when absent
+        let libraries = direct_link_libraries(&[], &[spdk.path().to_path_buf()]);
+        assert!(libraries.contains(&"rte_eal"));
+        assert!(!libraries.contains(&"rte_power"));
+    }
+
+    #[test]
+    fn test_version_from_header() {
+        let header = r#"
+#ifndef SPDK_VERSION_H
+#define SPDK_VERSION_H
+
+#define SPDK_VERSION_MAJOR	26
+#define SPDK_VERSION_MINOR	1
+#define SPDK_VERSION_PATCH	0
+#define SPDK_VERSION_SUFFIX	""
+"#;
+        assert_eq!(version_from_header(header).as_deref(), Some("26.01"));
+
+        let header = header
+            .replace("PATCH\t0", "PATCH\t2")
+            .replace("SUFFIX\t\"\"", "SUFFIX\t\"-pre\"");
+        assert_eq!(version_from_header(&header).as_deref(), Some("26.01.2-pre"));
+
+        assert_eq!(version_from_header("#define SPDK_VERSION_MAJOR 26"), None);
+    }
+}
@@ -0,0 +1,168 @@
+/* Non-inline wrappers for SPDK helpers bindgen cannot bind (see shim.h) */
+
+#include <errno.h>
+#include <string.h>
+
+#include <spdk/version.h>
+
+#include "shim.h"
+
+/*
+ * module/bdev/null/bdev_null.h is not installed with SPDK. build.rs adds its
+ * directory in the SPDK source tree to the include path when the tree is
+ * known (vendored builds, SPDK_SOURCE_DIR). Otherwise the declarations are
+ * mirrored from SPDK v26.01, the oldest release they are known to match.
+ */
+#ifdef SPDK_IO_BDEV
+#if __has_include("bdev_null.h")
+#include "bdev_null.h"
+#define SPDK_RS_HAVE_BDEV_NULL
+#elif SPDK_VERSION_MAJOR > 26 || (SPDK_VERSION_MAJOR == 26 && SPDK_VERSION_MINOR >= 1)
+struct null_bdev_opts {
+	const char *name;
+	const struct spdk_uuid *uuid;
+	uint64_t num_blocks;
+	uint32_t block_size;
+	uint32_t physical_block_size;
+	uint32_t md_size;
+	bool md_interleave;
+	enum spdk_dif_type dif_type;
+	bool dif_is_head_of_md;
+	enum spdk_dif_pi_format dif_pi_format;
+};
+_Static_assert(sizeof(struct null_bdev_opts) == 56,
+	       "struct null_bdev_opts does not match SPDK v26.01");
+
+int bdev_null_create(struct spdk_bdev **bdev, const struct null_bdev_opts *opts);
+void bdev_null_delete(const char *bdev_name, void (*cb_fn)(void *cb_arg, int bdeverrno),
+		      void *cb_arg);
+#define SPDK_RS_HAVE_BDEV_NULL
+#endif
+#endif
+
+#ifdef SPDK_IO_NVME
+uint8_t
+spdk_rs_nvme_cpl_sct(const struct spdk_nvme_cpl *cpl)
+{
+	return cpl->status.sct;
+}
+
+uint8_t
+spdk_rs_nvme_cpl_sc(const struct spdk_nvme_cpl *cpl)
+{
+	return cpl->status.sc;
+}
+
+bool
+spdk_rs_nvme_cpl_dnr(const struct spdk_nvme_cpl *cpl)
+{
+	return cpl->status.dnr;
+}
+
+bool
+spdk_rs_nvme_cpl_is_error(const struct spdk_nvme_cpl *cpl)
+{
+	return spdk_nvme_cpl_is_error(cpl);
+}
+
+bool
+spdk_rs_nvme_cpl_is_success(const struct spdk_nvme_cpl *cpl)
+{
+	return spdk_nvme_cpl_is_success(cpl);
+}
+
+bool
+spdk_rs_nvme_cpl_is_pi_error(const struct spdk_nvme_cpl *cpl)
+{
+	return spdk_nvme_cpl_is_pi_error(cpl);
+}
+
+bool
+spdk_rs_nvme_cpl_is_abort_success(const struct spdk_nvme_cpl *cpl)
+{
+	return spdk_nvme_cpl_is_abort_success(cpl);
+}
+#endif
+
+#ifdef SPDK_IO_BDEV
+struct spdk_bdev *
+spdk_rs_bdev_io_bdev(const struct spdk_bdev_io *bdev_io)
+{
+	return bdev_io->bdev;
+}
+
+uint8_t
+spdk_rs_bdev_io_type(const struct spdk_bdev_io *bdev_io)
+{
+	return bdev_io->type;
+}
+
+uint64_t
+spdk_rs_bdev_io_offset_blocks(const struct spdk_bdev_io *bdev_io)
+{
+	return bdev_io->u.bdev.offset_blocks;
+}
+
+uint64_t
+spdk_rs_bdev_io_num_blocks(const struct spdk_bdev_io *bdev_io)
+{
+	return bdev_io->u.bdev.num_blocks;
+}
+
+const struct spdk_bdev_alias *
+spdk_rs_bdev_alias_first(const struct spdk_bdev *bdev)
+{
+	return TAILQ_FIRST(spdk_bdev_get_aliases(bdev));
+}
+
+const struct spdk_bdev_alias *
+spdk_rs_bdev_alias_next(const struct spdk_bdev_alias *alias)
+{
+	return TAILQ_NEXT(alias, tailq);
+}
+
+const char *
+spdk_rs_bdev_alias_name(const struct spdk_bdev_alias *alias)
+{
+	return alias->alias.name;
+}
+
+void
+spdk_rs_bdev_ext_io_opts_init(struct spdk_bdev_ext_io_opts *opts, uint32_t nvme_cdw12,
+			      uint32_t nvme_cdw13)
+{
+	memset(opts, 0, sizeof(*opts));
+	opts->size = sizeof(*opts);
+	opts->nvme_cdw12.raw = nvme_cdw12;
+	opts->nvme_cdw13.raw = nvme_cdw13;
+}
+
+int
+spdk_rs_bdev_null_create(struct spdk_bdev **bdev, const char *name, uint64_t num_blocks,
+			 uint32_t block_size)
+{
+#ifdef SPDK_RS_HAVE_BDEV_NULL
+	/* Zero is SPDK_DIF_DISABLE and SPDK_DIF_PI_FORMAT_16 */
+	struct null_bdev_opts opts = {
+		.name = name,
+		.num_blocks = num_blocks,
+		.block_size = block_size,
+	};
+
+	return bdev_null_create(bdev, &opts);
+#else
+	return -ENOTSUP;
+#endif
+}
+
+void
+spdk_rs_bdev_null_delete(const char *name, void (*cb_fn)(void *cb_arg, int bdeverrno),
+			 void *cb_arg)
+{
+#ifdef SPDK_RS_HAVE_BDEV_NULL
+	bdev_null_delete(name, cb_fn, cb_arg);
+#else
+	cb_fn(cb_arg, -ENOTSUP);
+#endif
+}
+#endif
@@ -0,0 +1,70 @@
+/* Non-inline wrappers for SPDK helpers bindgen cannot bind */
+/*
+ * Many SPDK helpers are static inline functions or macros, which have no
+ * symbol to link against. shim.c wraps them in real functions with the
+ * spdk_rs_ prefix. It is compiled by build.rs with SPDK's cflags and the
+ * same SPDK_IO_<COMPONENT> defines as wrapper.h.
+ */
+
+#ifndef SPDK_IO_SHIM_H
+#define SPDK_IO_SHIM_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+/* NVMe completion status */
+#ifdef SPDK_IO_NVME
+#include <spdk/nvme.h>
+
+/* Status Code Type of a completion */
+uint8_t spdk_rs_nvme_cpl_sct(const struct spdk_nvme_cpl *cpl);
+/* Status Code of a completion */
+uint8_t spdk_rs_nvme_cpl_sc(const struct spdk_nvme_cpl *cpl);
+/* Do Not Retry bit of a completion */
+bool spdk_rs_nvme_cpl_dnr(const struct spdk_nvme_cpl *cpl);
+/* spdk_nvme_cpl_is_error() */
+bool spdk_rs_nvme_cpl_is_error(const struct spdk_nvme_cpl *cpl);
+/* spdk_nvme_cpl_is_success() */
+bool spdk_rs_nvme_cpl_is_success(const struct spdk_nvme_cpl *cpl);
+/* spdk_nvme_cpl_is_pi_error() */
+bool spdk_rs_nvme_cpl_is_pi_error(const struct spdk_nvme_cpl *cpl);
+/* spdk_nvme_cpl_is_abort_success() */
+bool spdk_rs_nvme_cpl_is_abort_success(const struct spdk_nvme_cpl *cpl);
+#endif
+
+/* Bdev I/O accessors */
+#ifdef SPDK_IO_BDEV
+#include <spdk/bdev_module.h>
+
+/* The bdev the I/O was submitted to */
+struct spdk_bdev *spdk_rs_bdev_io_bdev(const struct spdk_bdev_io *bdev_io);
+/* The I/O type (enum spdk_bdev_io_type) */
+uint8_t spdk_rs_bdev_io_type(const struct spdk_bdev_io *bdev_io);
+/* First block of a block I/O */
+uint64_t spdk_rs_bdev_io_offset_blocks(const struct spdk_bdev_io *bdev_io);
+/* Number of blocks of a block I/O */
+uint64_t spdk_rs_bdev_io_num_blocks(const struct spdk_bdev_io *bdev_io);
+/* First alias of a bdev (TAILQ_FIRST of spdk_bdev_get_aliases()), or NULL */
+const struct spdk_bdev_alias *spdk_rs_bdev_alias_first(const struct spdk_bdev *bdev);
+/* Alias after `alias`, or NULL */
+const struct spdk_bdev_alias *spdk_rs_bdev_alias_next(const struct spdk_bdev_alias *alias);
+/* Name of an alias */
+const char *spdk_rs_bdev_alias_name(const struct spdk_bdev_alias *alias);
+/*
+ * Initialize ext I/O opts (packed, so opaque to bindgen) with its size set
+ * and the given NVMe CDW12 flags and CDW13 hints, everything else zeroed
+ */
+void spdk_rs_bdev_ext_io_opts_init(struct spdk_bdev_ext_io_opts *opts, uint32_t nvme_cdw12,
+				   uint32_t nvme_cdw13);
+/*
+ * bdev_null_create() with default options besides the name and geometry.
+ * Returns -ENOTSUP if the null bdev API is unavailable (see shim.c)
+ */
+int spdk_rs_bdev_null_create(struct spdk_bdev **bdev, const char *name, uint64_t num_blocks,
+			     uint32_t block_size);
+/* bdev_null_delete(); cb_fn gets -ENOTSUP if the null bdev API is unavailable */
+void spdk_rs_bdev_null_delete(const char *name, void (*cb_fn)(void *cb_arg, int bdeverrno),
+			      void *cb_arg);
+#endif
+
+#endif /* SPDK_IO_SHIM_H */
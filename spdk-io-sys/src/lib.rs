@@ -11,6 +11,25 @@
 //! export PKG_CONFIG_PATH=/opt/spdk/lib/pkgconfig:$PKG_CONFIG_PATH
 //! cargo build
 //! ```
+//!
+//! # Features
+//!
+//! Each SPDK component has a feature that links its libraries and generates
+//! its bindings: `bdev`, `blob`, `nvme`, `nvmf`, `accel`, `sock`, `rpc` and
+//! `event`. The default `full` feature enables all of them. For example, an
+//! NVMe-only build:
+//!
+//! ```toml
+//! spdk-io-sys = { version = "0.1", default-features = false, features = ["nvme"] }
+//! ```
+//!
+//! # SPDK version
+//!
+//! The installed SPDK version is available as `env!("SPDK_VERSION")` (e.g.
+//! `26.01`), and each release it includes from a fixed ladder (24.01, 24.05,
+//! 24.09, 25.01, 25.05, 25.09, 26.01) sets a cfg such as `spdk_ge_24_09`, so
+//! bindings of newer APIs can be gated on it. Dependents' build scripts get
+//! the same information as `DEP_SPDK_VERSION` and `DEP_SPDK_VERSION_CFGS`.
 
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
@@ -24,14 +43,73 @@
 // Include the generated bindings
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
+/// Version ladder shared with the build script, included for its unit tests.
+#[cfg(test)]
+#[path = "../build_support.rs"]
+mod build_support;
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const BINDINGS: &str = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
     #[test]
+    #[cfg(feature = "bdev")]
     fn test_bindings_exist() {
         // Basic sanity check that bindings were generated
         // Just verify some types exist
         let _: spdk_bdev_io_type = spdk_bdev_io_type_SPDK_BDEV_IO_TYPE_READ;
     }
+
+    #[test]
+    #[cfg(feature = "nvme")]
+    fn test_shim_nvme_cpl_status() {
+        // Calls into shim.c, which evaluates SPDK's inline/macro helpers
+        let mut cpl = spdk_nvme_cpl::default();
+        unsafe {
+            assert!(spdk_rs_nvme_cpl_is_success(&cpl));
+            assert!(!spdk_rs_nvme_cpl_is_error(&cpl));
+
+            // SCT 0 (generic), SC 0x02 (Invalid Field in Command), DNR set
+            cpl.__bindgen_anon_1.status_raw = (0x02 << 1) | (1 << 15);
+            assert!(spdk_rs_nvme_cpl_is_error(&cpl));
+            assert_eq!(spdk_rs_nvme_cpl_sct(&cpl), 0);
+            assert_eq!(spdk_rs_nvme_cpl_sc(&cpl), 0x02);
+            assert!(spdk_rs_nvme_cpl_dnr(&cpl));
+        }
+    }
+
+    #[test]
+    fn test_component_bindings_match_features() {
+        let components = [
+            ("bdev", cfg!(feature = "bdev"), "pub fn spdk_bdev_open_ext("),
+            ("blob", cfg!(feature = "blob"), "pub fn spdk_bs_init("),
+            ("nvme", cfg!(feature = "nvme"), "pub fn spdk_nvme_probe("),
+            (
+                "nvmf",
+                cfg!(feature = "nvmf"),
+                "pub fn spdk_nvmf_tgt_create(",
+            ),
+            (
+                "accel",
+                cfg!(feature = "accel"),
+                "pub fn spdk_accel_submit_copy(",
+            ),
+            ("event", cfg!(feature = "event"), "pub fn spdk_app_start("),
+        ];
+        for (component, enabled, binding) in components {
+            assert_eq!(
+                BINDINGS.contains(binding),
+                enabled,
+                "`{}` bindings do not match the `{}` feature",
+                binding.trim_start_matches("pub fn "),
+                component
+            );
+        }
+
+        // Core bindings are always present
+        assert!(BINDINGS.contains("pub fn spdk_thread_poll("));
+        assert!(BINDINGS.contains("pub fn spdk_cpuset_alloc("));
+    }
 }
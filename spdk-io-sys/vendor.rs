@@ -0,0 +1,220 @@
+//! Builds SPDK from source for the `vendored` feature.
+//!
+//! SPDK is checked out at [`SPDK_COMMIT`] (or taken from `SPDK_SOURCE_DIR`),
+//! configured with [`CONFIGURE_FLAGS`], built, and installed into
+//! `OUT_DIR/spdk`. A stamp file records which commit and flags were
+//! installed, so later builds go straight to the pkg-config probe.
+//!
+//! GitHub's release archives do not contain the DPDK and ISA-L submodules,
+//! so the pinned tag is cloned with its submodules instead of downloaded as
+//! a tarball.
+//!
+//! Environment variables:
+//! - `SPDK_SOURCE_DIR`: Build this SPDK checkout (with submodules) instead of
+//!   fetching one. SPDK does not support out-of-tree builds, so the build
+//!   happens inside it.
+//! - `SPDK_BUILD_JOBS`: Parallel `make` jobs (defaults to cargo's `NUM_JOBS`)
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// SPDK release that is fetched when `SPDK_SOURCE_DIR` is not set.
+const SPDK_TAG: &str = "v26.01";
+
+/// Commit [`SPDK_TAG`] must resolve to (same pin as the top-level CMakeLists.txt).
+const SPDK_COMMIT: &str = "2ef883ef96e79c3cc16da02f667a7a58c2453f2f";
+
+const SPDK_REPO: &str = "https://github.com/spdk/spdk.git";
+
+/// Submodules needed for a self-contained build.
+const SUBMODULES: &[&str] = &["dpdk", "isa-l", "isa-l-crypto", "libvfio-user"];
+
+/// Static libraries only, without the optional drivers and the binaries
+/// that spdk-io does not use.
+const CONFIGURE_FLAGS: &[&str] = &[
+    "--without-shared",
+    "--without-rdma",
+    "--without-fio",
+    "--without-rbd",
+    "--without-ocf",
+    "--without-crypto",
+    "--disable-tests",
+    "--disable-unit-tests",
+    "--disable-examples",
+    "--disable-apps",
+];
+
+/// Written into the install prefix after a successful `make install`.
+const STAMP_FILE: &str = ".spdk-io-vendored";
+
+/// Lines of a failed step's log that are shown in the panic message.
+const LOG_TAIL_LINES: usize = 60;
+
+/// Builds and installs SPDK unless the install in `OUT_DIR` is current.
+///
+/// Returns the `PKG_CONFIG_PATH` for the installed libraries.
+pub fn build() -> String {
+    println!("cargo:rerun-if-env-changed=SPDK_SOURCE_DIR");
+    println!("cargo:rerun-if-env-changed=SPDK_BUILD_JOBS");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let prefix = out_dir.join("spdk");
+    let source = if env::var_os("SPDK_SOURCE_DIR").is_some() {
+        source_dir()
+    } else {
+        fetch(&source_dir())
+    };
+    let pkg_config_path = pkg_config_path(&prefix, &source);
+
+    // A source tree that is not a git checkout has no version to compare,
+    // so it is always rebuilt (make itself is incremental).
+    let stamp = prefix.join(STAMP_FILE);
+    let expected = source_version(&source).map(|version| stamp_contents(&version));
+    if expected.is_some() && fs::read_to_string(&stamp).ok() == expected {
+        return pkg_config_path;
+    }
+
+    let logs = out_dir.join("spdk-logs");
+    fs::create_dir_all(&logs).expect("Failed to create SPDK log directory");
+    let jobs = env::var("SPDK_BUILD_JOBS")
+        .or_else(|_| env::var("NUM_JOBS"))
+        .unwrap_or_else(|_| "1".to_string());
+
+    println!(
+        "cargo:warning=Building vendored SPDK in {} (this takes several minutes)",
+        source.display()
+    );
+
+    let mut configure = Command::new("./configure");
+    configure
+        .current_dir(&source)
+        .arg(format!("--prefix={}", prefix.display()))
+        .args(CONFIGURE_FLAGS);
+    run_logged(configure, &logs.join("configure.log"));
+
+    let mut make = Command::new("make");
+    make.current_dir(&source).arg(format!("-j{}", jobs));
+    run_logged(make, &logs.join("make.log"));
+
+    let mut install = Command::new("make");
+    install.current_dir(&source).arg("install");
+    run_logged(install, &logs.join("install.log"));
+
+    if let Some(contents) = expected {
+        fs::write(&stamp, contents).expect("Failed to write SPDK install stamp");
+    }
+    pkg_config_path
+}
+
+/// The SPDK source tree: `SPDK_SOURCE_DIR`, or the checkout in `OUT_DIR`.
+pub fn source_dir() -> PathBuf {
+    match env::var_os("SPDK_SOURCE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var("OUT_DIR").unwrap()).join("spdk-src"),
+    }
+}
+
+/// Clones [`SPDK_TAG`] with its submodules into `dir`, reusing an existing
+/// checkout of [`SPDK_COMMIT`].
+fn fetch(dir: &Path) -> PathBuf {
+    if source_version(dir).as_deref() == Some(SPDK_COMMIT) {
+        return dir.to_path_buf();
+    }
+    if dir.exists() {
+        fs::remove_dir_all(dir).expect("Failed to remove stale SPDK checkout");
+    }
+
+    let mut clone = Command::new("git");
+    clone
+        .args(["clone", "--depth", "1", "--branch", SPDK_TAG, SPDK_REPO])
+        .arg(dir);
+    run(clone);
+
+    let mut submodules = Command::new("git");
+    submodules
+        .current_dir(dir)
+        .args(["submodule", "update", "--init", "--depth", "1", "--"])
+        .args(SUBMODULES);
+    run(submodules);
+
+    let head = source_version(dir);
+    if head.as_deref() != Some(SPDK_COMMIT) {
+        panic!(
+            "SPDK {} resolved to {}, expected {}",
+            SPDK_TAG,
+            head.as_deref().unwrap_or("nothing"),
+            SPDK_COMMIT
+        );
+    }
+    dir.to_path_buf()
+}
+
+/// Returns the commit `source` is checked out at, if it is a git checkout.
+fn source_version(source: &Path) -> Option<String> {
+    if !source.join(".git").exists() {
+        return None;
+    }
+    let output = Command::new("git")
+        .current_dir(source)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn stamp_contents(version: &str) -> String {
+    format!("{}\n{}\n", version, CONFIGURE_FLAGS.join(" "))
+}
+
+/// SPDK's own `.pc` files, then the bundled DPDK's (`libdpdk.pc`).
+fn pkg_config_path(prefix: &Path, source: &Path) -> String {
+    format!(
+        "{}:{}",
+        prefix.join("lib/pkgconfig").display(),
+        source.join("dpdk/build/lib/pkgconfig").display()
+    )
+}
+
+/// Runs `cmd` with inherited output, panicking if it fails.
+fn run(mut cmd: Command) {
+    let status = cmd
+        .status()
+        .unwrap_or_else(|err| panic!("Failed to run {:?}: {}", cmd.get_program(), err));
+    if !status.success() {
+        panic!("{:?} failed with {}", cmd, status);
+    }
+}
+
+/// Runs `cmd` with its output written to `log`. On failure the end of the
+/// log is included in the panic message, since cargo hides build script
+/// output otherwise.
+fn run_logged(mut cmd: Command, log: &Path) {
+    let file = fs::File::create(log)
+        .unwrap_or_else(|err| panic!("Failed to create {}: {}", log.display(), err));
+    cmd.stdout(file.try_clone().expect("Failed to clone log file handle"))
+        .stderr(file);
+
+    let status = cmd
+        .status()
+        .unwrap_or_else(|err| panic!("Failed to run {:?}: {}", cmd.get_program(), err));
+    if status.success() {
+        return;
+    }
+
+    let output = fs::read_to_string(log).unwrap_or_default();
+    let lines: Vec<&str> = output.lines().collect();
+    let tail = lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].join("\n");
+    panic!(
+        "{:?} failed with {}\n--- last {} lines of {} ---\n{}",
+        cmd.get_program(),
+        status,
+        LOG_TAIL_LINES,
+        log.display(),
+        tail
+    );
+}
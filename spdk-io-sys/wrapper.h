@@ -1,32 +1,53 @@
 /* SPDK headers for bindgen */
+/* SPDK_IO_<COMPONENT> is defined by build.rs for each enabled cargo feature */
 
-/* Environment & initialization */
+/* Environment */
 #include <spdk/env.h>
-#include <spdk/init.h>
 
 /* Threading */
 #include <spdk/thread.h>
+#include <spdk/cpuset.h>
 
 /* Block device layer */
+#ifdef SPDK_IO_BDEV
 #include <spdk/bdev.h>
 #include <spdk/bdev_module.h>
+#endif
+
+/* Acceleration framework */
+#ifdef SPDK_IO_ACCEL
+#include <spdk/accel.h>
+#endif
 
 /* Blobstore */
+#ifdef SPDK_IO_BLOB
 #include <spdk/blob.h>
 #include <spdk/blob_bdev.h>
+#endif
 
 /* NVMe driver */
+#ifdef SPDK_IO_NVME
 #include <spdk/nvme.h>
 #include <spdk/nvme_spec.h>
+#endif
 
 /* NVMe-oF target */
+#ifdef SPDK_IO_NVMF
 #include <spdk/nvmf.h>
 #include <spdk/nvmf_transport.h>
+#endif
 
 /* Utilities */
+#include <spdk/version.h>
 #include <spdk/log.h>
 #include <spdk/string.h>
 #include <spdk/json.h>
 
-/* Event framework (optional, for app framework) */
+/* Event framework and subsystem initialization (optional, for app framework) */
+#ifdef SPDK_IO_EVENT
+#include <spdk/init.h>
 #include <spdk/event.h>
+#endif
+
+/* spdk_rs_* wrappers for inline helpers (compiled from shim.c) */
+#include "shim.h"
@@ -0,0 +1,32 @@
+//! Build script for spdk-io
+//!
+//! Cfgs set by a build script only apply to its own crate, so the SPDK version
+//! cfgs computed by spdk-io-sys (`spdk_ge_24_01`, ...) are re-emitted here from
+//! the `DEP_SPDK_*` metadata it passes to dependents.
+
+use std::env;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=DEP_SPDK_KNOWN_VERSION_CFGS");
+    println!("cargo:rerun-if-env-changed=DEP_SPDK_VERSION_CFGS");
+    println!("cargo:rerun-if-env-changed=DEP_SPDK_VERSION");
+
+    let list = |var: &str| {
+        env::var(var)
+            .unwrap_or_default()
+            .split(',')
+            .filter(|cfg| !cfg.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    for cfg in list("DEP_SPDK_KNOWN_VERSION_CFGS") {
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+    }
+    for cfg in list("DEP_SPDK_VERSION_CFGS") {
+        println!("cargo:rustc-cfg={}", cfg);
+    }
+    if let Ok(version) = env::var("DEP_SPDK_VERSION") {
+        println!("cargo:rustc-env=SPDK_VERSION={}", version);
+    }
+}
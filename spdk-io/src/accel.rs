@@ -0,0 +1,422 @@
+//! Acceleration framework (`spdk_accel`)
+//!
+//! SPDK's accel framework offloads data operations such as copies to
+//! hardware engines (Intel DSA or IOAT) when a module for them is loaded,
+//! and falls back to the software module otherwise. Callers don't choose the
+//! module; the framework assigns each operation to the best one available.
+//!
+//! Encryption and decryption are submitted on an [`AccelChannel`] with a
+//! [`CryptoKey`] registered through [`Crypto::create()`]; the software
+//! module implements them with ISA-L crypto.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use spdk_io::{DmaBuf, accel};
+//!
+//! # async fn example() -> spdk_io::Result<()> {
+//! let src = DmaBuf::alloc_zeroed(1 << 20, 4096)?;
+//! let dst = DmaBuf::alloc(1 << 20, 4096)?;
+//! let (dst, src) = accel::copy_offloaded(dst, src).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::ffi::{CString, c_char, c_void};
+use std::fmt::Write;
+use std::ptr::NonNull;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use spdk_io_sys::*;
+
+use crate::channel::IoChannel;
+use crate::complete::{CompletionSender, completion};
+use crate::dma::DmaBuf;
+use crate::error::{Error, Result};
+
+/// Get an accel I/O channel for the current SPDK thread.
+///
+/// SPDK reference-counts the channel per thread, so repeated calls are cheap.
+///
+/// # Errors
+///
+/// Returns [`Error::ChannelAlloc`] if no channel could be obtained (e.g. not
+/// on an SPDK thread, or the accel framework is not initialized).
+pub fn get_io_channel() -> Result<IoChannel> {
+    let ptr = unsafe { spdk_accel_get_io_channel() };
+    NonNull::new(ptr)
+        .map(IoChannel::from_ptr)
+        .ok_or(Error::ChannelAlloc)
+}
+
+/// Copy `src` into `dst` using the accel framework.
+///
+/// Obtains an accel channel on the current SPDK thread, submits the copy
+/// and waits for it to complete. The copy runs on DSA or IOAT hardware when
+/// available, otherwise in software.
+///
+/// The buffers are moved into the operation and handed back as
+/// `(dst, src)` on success, so dropping the future early cannot free them
+/// while the hardware still uses them. On error they are dropped.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `dst` and `src` have different lengths ([`Error::InvalidArgument`])
+/// - No accel channel is available on this thread ([`Error::ChannelAlloc`])
+/// - Submission or the copy itself fails ([`Error::Os`])
+pub async fn copy_offloaded(dst: DmaBuf, src: DmaBuf) -> Result<(DmaBuf, DmaBuf)> {
+    if dst.len() != src.len() {
+        return Err(Error::InvalidArgument(format!(
+            "copy length mismatch: dst is {} bytes, src is {} bytes",
+            dst.len(),
+            src.len()
+        )));
+    }
+
+    let (tx, rx) = completion();
+    let op = AccelOp::new(Rc::new(get_io_channel()?), dst, src, None, tx);
+
+    let rc = unsafe {
+        spdk_accel_submit_copy(
+            (*op).channel.as_ptr(),
+            (*op).dst.as_mut_ptr() as *mut c_void,
+            (*op).src.as_ptr() as *mut c_void,
+            (*op).src.len() as u64,
+            Some(accel_op_done),
+            op as *mut c_void,
+        )
+    };
+    if rc != 0 {
+        // SAFETY: the callback will not run, reclaim the operation
+        drop(unsafe { Box::from_raw(op) });
+        return Err(Error::from_rc(rc));
+    }
+
+    rx.await
+}
+
+/// An accel operation in flight.
+///
+/// SPDK uses the buffers, iovecs and key until it calls
+/// [`accel_op_done()`], so the operation is boxed and owned by that callback
+/// rather than by the future, and outlives a future that is dropped early.
+struct AccelOp {
+    dst: DmaBuf,
+    src: DmaBuf,
+    dst_iov: iovec,
+    src_iov: iovec,
+    /// Keeps the channel alive until completion
+    channel: Rc<IoChannel>,
+    _key: Option<CryptoKey>,
+    tx: CompletionSender<(DmaBuf, DmaBuf)>,
+}
+
+impl AccelOp {
+    /// Box the operation, with iovecs covering the whole buffers, and hand
+    /// it over as the `cb_arg` for [`accel_op_done()`].
+    ///
+    /// If submission fails, the caller reclaims it with `Box::from_raw`.
+    fn new(
+        channel: Rc<IoChannel>,
+        mut dst: DmaBuf,
+        src: DmaBuf,
+        key: Option<CryptoKey>,
+        tx: CompletionSender<(DmaBuf, DmaBuf)>,
+    ) -> *mut AccelOp {
+        // DMA memory does not move with the DmaBuf, so the iovecs stay valid
+        let dst_iov = iovec {
+            iov_base: dst.as_mut_ptr() as *mut c_void,
+            iov_len: dst.len(),
+        };
+        let src_iov = iovec {
+            iov_base: src.as_ptr() as *mut c_void,
+            iov_len: src.len(),
+        };
+        Box::into_raw(Box::new(AccelOp {
+            dst,
+            src,
+            dst_iov,
+            src_iov,
+            channel,
+            _key: key,
+            tx,
+        }))
+    }
+}
+
+/// Completion callback for accel submissions with an [`AccelOp`] `cb_arg`.
+unsafe extern "C" fn accel_op_done(cb_arg: *mut c_void, status: i32) {
+    let op = unsafe { Box::from_raw(cb_arg as *mut AccelOp) };
+    let AccelOp { dst, src, tx, .. } = *op;
+
+    if status == 0 {
+        tx.success((dst, src));
+    } else {
+        tx.error(Error::from_rc(status));
+    }
+}
+
+/// Cipher of a [`CryptoKey`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cipher {
+    /// AES in CBC mode (one key)
+    AesCbc,
+    /// AES in XTS mode (data key plus tweak key), the usual choice for
+    /// block storage
+    AesXts,
+}
+
+impl Cipher {
+    fn as_str(self) -> &'static str {
+        match self {
+            Cipher::AesCbc => "AES_CBC",
+            Cipher::AesXts => "AES_XTS",
+        }
+    }
+}
+
+/// Unique suffix for key names.
+static NEXT_KEY: AtomicU32 = AtomicU32::new(0);
+
+/// Entry point of the accel crypto API.
+///
+/// Keys come from [`Crypto::create()`]; operations using them are
+/// submitted with [`AccelChannel::encrypt()`] and
+/// [`AccelChannel::decrypt()`].
+pub struct Crypto;
+
+impl Crypto {
+    /// Register `key` for `cipher` via `spdk_accel_crypto_key_create`.
+    ///
+    /// [`Cipher::AesXts`] needs `tweak_key`, of the same length as `key`
+    /// and different from it. The key is registered under a generated
+    /// name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the accel framework rejects the key (wrong
+    /// length, missing or identical tweak key, or no module supports the
+    /// cipher) as [`Error::Os`].
+    pub fn create(cipher: Cipher, key: &[u8], tweak_key: Option<&[u8]>) -> Result<CryptoKey> {
+        let name = format!("spdk_io_key_{}", NEXT_KEY.fetch_add(1, Ordering::Relaxed));
+        let name = CString::new(name)?;
+        let cipher = CString::new(cipher.as_str())?;
+        let hex_key = HexKey::new(key);
+        let hex_key2 = tweak_key.map(HexKey::new);
+
+        let mut param: spdk_accel_crypto_key_create_param = unsafe { std::mem::zeroed() };
+        param.cipher = cipher.as_ptr() as *mut c_char;
+        param.hex_key = hex_key.as_ptr();
+        param.hex_key2 = hex_key2
+            .as_ref()
+            .map_or(std::ptr::null_mut(), HexKey::as_ptr);
+        param.key_name = name.as_ptr() as *mut c_char;
+
+        let rc = unsafe { spdk_accel_crypto_key_create(&param) };
+        if rc != 0 {
+            return Err(Error::from_rc(rc));
+        }
+
+        let ptr = unsafe { spdk_accel_crypto_key_get(name.as_ptr()) };
+        NonNull::new(ptr)
+            .map(|ptr| CryptoKey {
+                inner: Rc::new(KeyInner(ptr)),
+            })
+            .ok_or(Error::Os(libc::ENOENT))
+    }
+}
+
+/// Encryption key registered with the accel framework.
+///
+/// Created with [`Crypto::create()`]. Clones share the key, which is
+/// destroyed when the last clone is dropped; operations in flight hold a
+/// clone, so dropping a key (or an operation's future) early is safe.
+#[derive(Clone)]
+pub struct CryptoKey {
+    inner: Rc<KeyInner>, // !Send + !Sync
+}
+
+struct KeyInner(NonNull<spdk_accel_crypto_key>);
+
+impl CryptoKey {
+    /// Get the raw pointer.
+    pub fn as_ptr(&self) -> *mut spdk_accel_crypto_key {
+        self.inner.0.as_ptr()
+    }
+}
+
+impl Drop for KeyInner {
+    fn drop(&mut self) {
+        unsafe { spdk_accel_crypto_key_destroy(self.0.as_ptr()) };
+    }
+}
+
+/// Hex-encoded key for `spdk_accel_crypto_key_create_param`, wiped on drop.
+struct HexKey(Vec<u8>);
+
+impl HexKey {
+    fn new(key: &[u8]) -> Self {
+        let mut hex = String::with_capacity(key.len() * 2 + 1);
+        for byte in key {
+            let _ = write!(hex, "{:02x}", byte);
+        }
+        let mut bytes = hex.into_bytes();
+        bytes.push(0);
+        Self(bytes)
+    }
+
+    fn as_ptr(&self) -> *mut c_char {
+        self.0.as_ptr() as *mut c_char
+    }
+}
+
+impl Drop for HexKey {
+    fn drop(&mut self) {
+        self.0.fill(0);
+    }
+}
+
+/// Accel I/O channel of the current SPDK thread, for submitting operations.
+///
+/// Get one per thread with [`AccelChannel::get()`] and reuse it. Operations
+/// in flight keep the channel alive, so it may be dropped before they
+/// complete.
+///
+/// # Example
+///
+/// ```no_run
+/// use spdk_io::DmaBuf;
+/// use spdk_io::accel::{AccelChannel, Cipher, Crypto};
+///
+/// # async fn example() -> spdk_io::Result<()> {
+/// let channel = AccelChannel::get()?;
+/// let key = Crypto::create(Cipher::AesXts, &[0x11; 16], Some(&[0x22; 16]))?;
+///
+/// let plain = DmaBuf::alloc_zeroed(4096, 4096)?;
+/// let cipher = DmaBuf::alloc(4096, 4096)?;
+/// let (cipher, plain) = channel.encrypt(&key, cipher, plain, 0, 512).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AccelChannel {
+    channel: Rc<IoChannel>, // !Send + !Sync
+}
+
+impl AccelChannel {
+    /// Get the accel channel of the current SPDK thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ChannelAlloc`] like [`get_io_channel()`].
+    pub fn get() -> Result<Self> {
+        Ok(Self {
+            channel: Rc::new(get_io_channel()?),
+        })
+    }
+
+    /// Encrypt `src` into `dst` with `key`.
+    ///
+    /// The data is processed in units of `block_size` bytes; `iv` is the
+    /// tweak of the first unit (typically its LBA) and increments by one per
+    /// unit.
+    ///
+    /// The buffers are moved into the operation and handed back as
+    /// `(dst, src)` on success, so dropping the future early cannot free
+    /// them while the operation runs. On error they are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - `dst` and `src` differ in length, or the length is not a multiple
+    ///   of a non-zero `block_size` ([`Error::InvalidArgument`])
+    /// - Submission or the operation itself fails ([`Error::Os`])
+    pub async fn encrypt(
+        &self,
+        key: &CryptoKey,
+        dst: DmaBuf,
+        src: DmaBuf,
+        iv: u64,
+        block_size: u32,
+    ) -> Result<(DmaBuf, DmaBuf)> {
+        self.crypt(true, key, dst, src, iv, block_size).await
+    }
+
+    /// Decrypt `src` into `dst` with `key`; the inverse of
+    /// [`encrypt()`](Self::encrypt).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`encrypt()`](Self::encrypt).
+    pub async fn decrypt(
+        &self,
+        key: &CryptoKey,
+        dst: DmaBuf,
+        src: DmaBuf,
+        iv: u64,
+        block_size: u32,
+    ) -> Result<(DmaBuf, DmaBuf)> {
+        self.crypt(false, key, dst, src, iv, block_size).await
+    }
+
+    async fn crypt(
+        &self,
+        encrypt: bool,
+        key: &CryptoKey,
+        dst: DmaBuf,
+        src: DmaBuf,
+        iv: u64,
+        block_size: u32,
+    ) -> Result<(DmaBuf, DmaBuf)> {
+        if dst.len() != src.len() {
+            return Err(Error::InvalidArgument(format!(
+                "crypto length mismatch: dst is {} bytes, src is {} bytes",
+                dst.len(),
+                src.len()
+            )));
+        }
+        if block_size == 0 || src.len() % block_size as usize != 0 {
+            return Err(Error::InvalidArgument(format!(
+                "length {} is not a multiple of block size {}",
+                src.len(),
+                block_size
+            )));
+        }
+
+        let (tx, rx) = completion();
+        let op = AccelOp::new(self.channel.clone(), dst, src, Some(key.clone()), tx);
+
+        let submit = if encrypt {
+            spdk_accel_submit_encrypt
+        } else {
+            spdk_accel_submit_decrypt
+        };
+        let rc = unsafe {
+            submit(
+                (*op).channel.as_ptr(),
+                key.as_ptr(),
+                &mut (*op).dst_iov,
+                1,
+                &mut (*op).src_iov,
+                1,
+                iv,
+                block_size,
+                Some(accel_op_done),
+                op as *mut c_void,
+            )
+        };
+        if rc != 0 {
+            // SAFETY: the callback will not run, reclaim the operation
+            drop(unsafe { Box::from_raw(op) });
+            return Err(Error::from_rc(rc));
+        }
+
+        rx.await
+    }
+
+    /// Get the underlying I/O channel.
+    pub fn io_channel(&self) -> &IoChannel {
+        &self.channel
+    }
+}
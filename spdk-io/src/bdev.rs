@@ -43,14 +43,17 @@
 
 use std::ffi::{CStr, CString, c_void};
 use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
 use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
 
 use spdk_io_sys::*;
 
 use crate::channel::IoChannel;
-use crate::complete::{CompletionSender, completion};
+use crate::complete::{Completion, CompletionSender, completion};
 use crate::dma::DmaBuf;
-use crate::error::{Error, Result};
+use crate::error::{Error, Result, c_str_checked};
 
 /// Block device handle.
 ///
@@ -114,6 +117,50 @@ impl Bdev {
         self.block_size() as u64 * self.num_blocks()
     }
 
+    /// Get the minimum buffer alignment required for I/O, in bytes.
+    pub fn buf_align(&self) -> usize {
+        unsafe { spdk_bdev_get_buf_align(self.ptr.as_ptr()) }
+    }
+
+    /// Get the NUMA id of the socket the bdev is attached to.
+    ///
+    /// Returns [`NUMA_ID_ANY`](crate::dma::NUMA_ID_ANY) if unknown.
+    pub fn numa_id(&self) -> i32 {
+        unsafe { spdk_bdev_get_numa_id(self.ptr.as_ptr()) }
+    }
+
+    /// Get the bdev's UUID, a stable identity across restarts.
+    ///
+    /// Returns `None` if the module assigned none (all-zero UUID). The type
+    /// is `uuid::Uuid` with the `uuid` feature, raw bytes otherwise.
+    pub fn uuid(&self) -> Option<BdevUuid> {
+        let raw = unsafe { (*spdk_bdev_get_uuid(self.ptr.as_ptr())).u.raw };
+        if raw == [0; 16] {
+            return None;
+        }
+        #[cfg(feature = "uuid")]
+        let uuid = uuid::Uuid::from_bytes(raw);
+        #[cfg(not(feature = "uuid"))]
+        let uuid = raw;
+        Some(uuid)
+    }
+
+    /// Get the bdev's aliases (alternative names it can be looked up by).
+    ///
+    /// Names that are not valid UTF-8 are skipped.
+    pub fn aliases(&self) -> Vec<String> {
+        let mut aliases = Vec::new();
+        let mut alias = unsafe { spdk_rs_bdev_alias_first(self.ptr.as_ptr()) };
+        while !alias.is_null() {
+            let name = unsafe { CStr::from_ptr(spdk_rs_bdev_alias_name(alias)) };
+            if let Ok(name) = name.to_str() {
+                aliases.push(name.to_string());
+            }
+            alias = unsafe { spdk_rs_bdev_alias_next(alias) };
+        }
+        aliases
+    }
+
     /// Open this bdev for I/O operations.
     ///
     /// # Arguments
@@ -145,11 +192,31 @@ impl Bdev {
         NonNull::new(desc)
             .map(|ptr| BdevDesc {
                 ptr,
+                module: None,
                 _marker: PhantomData,
             })
             .ok_or(Error::InvalidArgument("null descriptor".into()))
     }
 
+    /// Open the bdev and return a descriptor that can be shared across threads.
+    ///
+    /// Equivalent to `self.open(write)?.into_shared()`. See [`SharedBdevDesc`].
+    pub fn open_shared(&self, write: bool) -> Result<SharedBdevDesc> {
+        Ok(self.open(write)?.into_shared())
+    }
+
+    /// Check whether the bdev supports zero-copy I/O.
+    ///
+    /// See [`BdevDesc::zcopy_start()`].
+    pub fn supports_zcopy(&self) -> bool {
+        unsafe {
+            spdk_bdev_io_type_supported(
+                self.ptr.as_ptr(),
+                spdk_bdev_io_type_SPDK_BDEV_IO_TYPE_ZCOPY,
+            )
+        }
+    }
+
     /// Get the raw pointer.
     ///
     /// # Safety
@@ -181,6 +248,84 @@ impl Clone for Bdev {
     }
 }
 
+/// UUID returned by [`Bdev::uuid()`]: `uuid::Uuid` with the `uuid` feature,
+/// otherwise the 16 raw bytes in RFC 4122 order.
+#[cfg(feature = "uuid")]
+pub type BdevUuid = uuid::Uuid;
+/// UUID returned by [`Bdev::uuid()`]: `uuid::Uuid` with the `uuid` feature,
+/// otherwise the 16 raw bytes in RFC 4122 order.
+#[cfg(not(feature = "uuid"))]
+pub type BdevUuid = [u8; 16];
+
+/// Per-I/O flags for [`BdevDesc::write_with_flags()`].
+///
+/// The flags travel in the NVMe CDW12 field of `spdk_bdev_ext_io_opts`, so
+/// only NVMe bdevs act on them. Other bdev modules ignore them and perform
+/// a normal write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WriteFlags(u32);
+
+impl WriteFlags {
+    /// No flags, same as a plain write.
+    pub const NONE: Self = Self(0);
+
+    /// Force Unit Access: complete only once the data is on non-volatile
+    /// media, bypassing the device's volatile write cache.
+    pub const FUA: Self = Self(1 << 30);
+
+    /// Whether all flags in `other` are set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The flags as NVMe CDW12 bits.
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+}
+
+impl std::ops::BitOr for WriteFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for WriteFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Access pattern hint for [`BdevDesc::read_with_opts()`].
+///
+/// Hints are advisory: they travel in the NVMe CDW13 dataset management
+/// field of `spdk_bdev_ext_io_opts`, where a device may use them to
+/// prefetch. Other bdev modules ignore them, and a hinted read returns the
+/// same data as a plain one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ReadHint {
+    /// No hint.
+    #[default]
+    Normal,
+    /// The read is part of a sequential run (NVMe "sequential request").
+    Sequential,
+    /// The read is not part of a sequential run. NVMe has no hint for
+    /// this, so no bits are set; it states the caller's intent.
+    Random,
+}
+
+impl ReadHint {
+    /// The hint as NVMe CDW13 dataset management bits.
+    pub fn bits(self) -> u32 {
+        match self {
+            ReadHint::Normal | ReadHint::Random => 0,
+            ReadHint::Sequential => 1 << 6,
+        }
+    }
+}
+
 /// Open descriptor to a bdev (like a file descriptor).
 ///
 /// Use [`get_io_channel()`](BdevDesc::get_io_channel) to obtain a thread-local
@@ -196,10 +341,36 @@ impl Clone for Bdev {
 /// Automatically closes the descriptor when dropped.
 pub struct BdevDesc {
     ptr: NonNull<spdk_bdev_desc>,
+    /// Module that [`claim()`](Self::claim) claims for, `None` for `spdk_io`
+    module: Option<BdevModule>,
     _marker: PhantomData<*mut ()>, // !Send + !Sync
 }
 
 impl BdevDesc {
+    /// Open the bdev `name` on behalf of `module`.
+    ///
+    /// A virtual bdev stacked on a base bdev opens the base this way, so
+    /// [`claim()`](Self::claim) records the claim under its own module and
+    /// other claimants see who owns the base.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DeviceNotFound`] if there is no bdev `name`, or an
+    /// error as for [`Bdev::open()`].
+    pub fn open_for_module(name: &str, write: bool, module: &BdevModule) -> Result<BdevDesc> {
+        let bdev =
+            Bdev::get_by_name(name).ok_or_else(|| Error::DeviceNotFound(name.to_string()))?;
+        let mut desc = bdev.open(write)?;
+        desc.module = Some(*module);
+        Ok(desc)
+    }
+
+    /// Get the module this descriptor claims for, if it was opened with
+    /// [`open_for_module()`](Self::open_for_module).
+    pub fn module(&self) -> Option<BdevModule> {
+        self.module
+    }
+
     /// Get an I/O channel for this descriptor on the current thread.
     ///
     /// I/O channels are per-thread and must be used on the thread that
@@ -220,11 +391,100 @@ impl BdevDesc {
         }
     }
 
+    /// Get the name of the underlying bdev, failing if it is not valid UTF-8.
+    pub fn name_checked(&self) -> Result<&str> {
+        unsafe {
+            let bdev = spdk_bdev_desc_get_bdev(self.ptr.as_ptr());
+            c_str_checked(spdk_bdev_get_name(bdev))
+        }
+    }
+
+    /// Get the UUID of the underlying bdev, see [`Bdev::uuid()`].
+    pub fn uuid(&self) -> Option<BdevUuid> {
+        self.bdev().uuid()
+    }
+
+    /// Get the aliases of the underlying bdev, see [`Bdev::aliases()`].
+    pub fn aliases(&self) -> Vec<String> {
+        self.bdev().aliases()
+    }
+
     /// Get the raw pointer.
     pub fn as_ptr(&self) -> *mut spdk_bdev_desc {
         self.ptr.as_ptr()
     }
 
+    /// Claim the bdev so no other module can claim it until the guard is
+    /// dropped.
+    ///
+    /// The claim is taken for the descriptor's [`module()`](Self::module),
+    /// or for a default `spdk_io` module.
+    ///
+    /// SPDK records this as an exclusive-write (`EXCL_WRITE`) claim: while
+    /// it is held, every new write open of the bdev fails, whoever asks.
+    /// Descriptors that are already open keep their access. With `write`
+    /// this descriptor is also promoted to write access, so a descriptor
+    /// opened read-only can become the bdev's single writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Busy`] if the bdev is already claimed.
+    pub fn claim(&self, write: bool) -> Result<ClaimGuard<'_>> {
+        let bdev = self.bdev();
+        let desc = if write {
+            self.ptr.as_ptr()
+        } else {
+            std::ptr::null_mut()
+        };
+
+        let module = self.module.unwrap_or_else(BdevModule::default_module);
+        let rc = unsafe { spdk_bdev_module_claim_bdev(bdev.as_ptr(), desc, module.as_ptr()) };
+        match rc {
+            0 => Ok(ClaimGuard {
+                bdev,
+                _marker: PhantomData,
+            }),
+            rc if rc == -libc::EPERM => Err(Error::Busy(bdev.name().to_string())),
+            rc => Err(Error::from_rc(rc)),
+        }
+    }
+
+    /// Convert into a descriptor that can be shared across threads.
+    ///
+    /// See [`SharedBdevDesc`].
+    pub fn into_shared(self) -> SharedBdevDesc {
+        SharedBdevDesc {
+            inner: Arc::new(SharedDescInner {
+                desc: ManuallyDrop::new(self),
+                thread: crate::thread::current_thread_id(),
+            }),
+        }
+    }
+
+    /// Validate that an I/O of `len` bytes at `offset` covers whole blocks.
+    ///
+    /// SPDK rejects misaligned requests for most bdevs, but some modules
+    /// silently round, which turns an undersized buffer into a partial read
+    /// or an overrun. The check is cheap, so it also runs in release builds.
+    fn check_io_range(&self, len: usize, offset: u64) -> Result<()> {
+        let block_size = self.bdev().block_size() as u64;
+        let len = len as u64;
+
+        if len == 0 || len % block_size != 0 {
+            return Err(Error::Config(format!(
+                "buffer length {} is not a non-zero multiple of block size {}",
+                len, block_size
+            )));
+        }
+        if offset % block_size != 0 {
+            return Err(Error::Config(format!(
+                "offset {} is not aligned to block size {}",
+                offset, block_size
+            )));
+        }
+        Ok(())
+    }
+
     /// Read data from the bdev.
     ///
     /// Reads `buf.len()` bytes from the specified byte offset into the buffer.
@@ -238,6 +498,8 @@ impl BdevDesc {
     /// # Errors
     ///
     /// Returns an error if:
+    /// - `buf.len()` is zero or not a multiple of the block size, or `offset`
+    ///   is not block-aligned ([`Error::Config`])
     /// - The read submission fails (e.g., invalid offset/length)
     /// - The I/O operation fails
     ///
@@ -257,6 +519,8 @@ impl BdevDesc {
     /// # }
     /// ```
     pub async fn read(&self, channel: &IoChannel, buf: &mut DmaBuf, offset: u64) -> Result<()> {
+        self.check_io_range(buf.len(), offset)?;
+
         let (tx, rx) = completion::<()>();
 
         let rc = unsafe {
@@ -292,6 +556,8 @@ impl BdevDesc {
     ///
     /// Returns an error if:
     /// - The bdev was opened read-only
+    /// - `buf.len()` is zero or not a multiple of the block size, or `offset`
+    ///   is not block-aligned ([`Error::Config`])
     /// - The write submission fails (e.g., invalid offset/length)
     /// - The I/O operation fails
     ///
@@ -312,6 +578,8 @@ impl BdevDesc {
     /// # }
     /// ```
     pub async fn write(&self, channel: &IoChannel, buf: &DmaBuf, offset: u64) -> Result<()> {
+        self.check_io_range(buf.len(), offset)?;
+
         let (tx, rx) = completion::<()>();
 
         let rc = unsafe {
@@ -332,6 +600,255 @@ impl BdevDesc {
 
         rx.await
     }
+
+    /// Write `num_blocks` blocks from `buf` at `offset_blocks` with per-I/O
+    /// [`WriteFlags`].
+    ///
+    /// With [`WriteFlags::FUA`] the write completes only once the data is
+    /// durable, which is what journals need for commit records. Uses
+    /// `spdk_bdev_writev_blocks_ext`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - `num_blocks` is zero or `buf` is shorter than `num_blocks` blocks
+    ///   ([`Error::Config`])
+    /// - The write submission fails (e.g., read-only descriptor, out of range)
+    /// - The I/O operation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::{Bdev, DmaBuf, WriteFlags};
+    ///
+    /// # async fn example() -> spdk_io::Result<()> {
+    /// let bdev = Bdev::get_by_name("Nvme0n1").unwrap();
+    /// let desc = bdev.open(true)?;
+    /// let channel = desc.get_io_channel()?;
+    ///
+    /// let buf = DmaBuf::alloc_zeroed(4096, 4096)?;
+    /// desc.write_with_flags(&channel, &buf, 0, 1, WriteFlags::FUA).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_with_flags(
+        &self,
+        channel: &IoChannel,
+        buf: &DmaBuf,
+        offset_blocks: u64,
+        num_blocks: u64,
+        flags: WriteFlags,
+    ) -> Result<()> {
+        let len = self.blocks_len(buf.len(), num_blocks)?;
+        self.submit_ext(
+            spdk_bdev_writev_blocks_ext,
+            channel,
+            iovec {
+                iov_base: buf.as_ptr() as *mut c_void,
+                iov_len: len,
+            },
+            offset_blocks,
+            num_blocks,
+            (flags.bits(), 0),
+        )
+        .await
+    }
+
+    /// Read `num_blocks` blocks at `offset_blocks` into `buf` with an
+    /// access [`ReadHint`].
+    ///
+    /// The hint is advisory (see [`ReadHint`]); the data read is the same
+    /// as with [`read()`](Self::read). Uses `spdk_bdev_readv_blocks_ext`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - `num_blocks` is zero or `buf` is shorter than `num_blocks` blocks
+    ///   ([`Error::Config`])
+    /// - The read submission fails (e.g., out of range)
+    /// - The I/O operation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::{Bdev, DmaBuf, ReadHint};
+    ///
+    /// # async fn example() -> spdk_io::Result<()> {
+    /// let bdev = Bdev::get_by_name("Nvme0n1").unwrap();
+    /// let desc = bdev.open(false)?;
+    /// let channel = desc.get_io_channel()?;
+    ///
+    /// let mut buf = DmaBuf::alloc(64 * 4096, 4096)?;
+    /// for chunk in 0..16 {
+    ///     desc.read_with_opts(&channel, &mut buf, chunk * 64, 64, ReadHint::Sequential)
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_with_opts(
+        &self,
+        channel: &IoChannel,
+        buf: &mut DmaBuf,
+        offset_blocks: u64,
+        num_blocks: u64,
+        hint: ReadHint,
+    ) -> Result<()> {
+        let len = self.blocks_len(buf.len(), num_blocks)?;
+        self.submit_ext(
+            spdk_bdev_readv_blocks_ext,
+            channel,
+            iovec {
+                iov_base: buf.as_mut_ptr() as *mut c_void,
+                iov_len: len,
+            },
+            offset_blocks,
+            num_blocks,
+            (0, hint.bits()),
+        )
+        .await
+    }
+
+    /// Bytes in `num_blocks` blocks, checked against a `buf_len` byte buffer.
+    fn blocks_len(&self, buf_len: usize, num_blocks: u64) -> Result<usize> {
+        let block_size = self.bdev().block_size() as u64;
+        let len = num_blocks.saturating_mul(block_size);
+        if num_blocks == 0 || len > buf_len as u64 {
+            return Err(Error::Config(format!(
+                "{} blocks of {} bytes do not fit a {} byte buffer",
+                num_blocks, block_size, buf_len
+            )));
+        }
+        Ok(len as usize)
+    }
+
+    /// Submit a single-buffer `*v_blocks_ext` I/O with the given NVMe
+    /// `(cdw12, cdw13)` and wait for it.
+    async fn submit_ext(
+        &self,
+        submit: unsafe extern "C" fn(
+            *mut spdk_bdev_desc,
+            *mut spdk_io_channel,
+            *mut iovec,
+            i32,
+            u64,
+            u64,
+            spdk_bdev_io_completion_cb,
+            *mut c_void,
+            *mut spdk_bdev_ext_io_opts,
+        ) -> i32,
+        channel: &IoChannel,
+        mut iov: iovec,
+        offset_blocks: u64,
+        num_blocks: u64,
+        (cdw12, cdw13): (u32, u32),
+    ) -> Result<()> {
+        let mut opts = std::mem::MaybeUninit::<spdk_bdev_ext_io_opts>::uninit();
+        unsafe { spdk_rs_bdev_ext_io_opts_init(opts.as_mut_ptr(), cdw12, cdw13) };
+
+        let (tx, rx) = completion::<()>();
+        let ctx = tx.into_raw();
+
+        // SPDK copies what it needs from the iovec and opts during submission
+        let rc = unsafe {
+            submit(
+                self.ptr.as_ptr(),
+                channel.as_ptr(),
+                &mut iov,
+                1,
+                offset_blocks,
+                num_blocks,
+                Some(bdev_io_completion_cb),
+                ctx,
+                opts.as_mut_ptr(),
+            )
+        };
+
+        if rc != 0 {
+            // SAFETY: the callback will not run, reclaim the sender
+            drop(unsafe { CompletionSender::<()>::from_raw(ctx) });
+            return Err(Error::from_rc(rc));
+        }
+
+        rx.await
+    }
+
+    /// Start a zero-copy I/O on `num_blocks` blocks at `offset_blocks`.
+    ///
+    /// The bdev lends its own buffers instead of copying into a [`DmaBuf`].
+    /// With `populate` the I/O is a read: the buffers hold the current data
+    /// and must not be modified, since a module may lend its backing memory
+    /// (malloc does) and changes would reach the bdev without any commit.
+    /// Otherwise the I/O is a write: the buffer contents are undefined and
+    /// are meant to be filled, then committed. Finish with
+    /// [`ZcopyHandle::end()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The bdev does not support zero-copy ([`Error::Os`] with `ENOTSUP`),
+    ///   see [`Bdev::supports_zcopy()`]
+    /// - The submission fails (e.g., out of range)
+    /// - The I/O operation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::Bdev;
+    ///
+    /// # async fn example() -> spdk_io::Result<()> {
+    /// let bdev = Bdev::get_by_name("Malloc0").unwrap();
+    /// let desc = bdev.open(true)?;
+    /// let channel = desc.get_io_channel()?;
+    ///
+    /// let mut zcopy = desc.zcopy_start(&channel, 0, 1, false).await?;
+    /// for buf in zcopy.buffers_mut() {
+    ///     buf.fill(0xab);
+    /// }
+    /// zcopy.end(true).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn zcopy_start<'a>(
+        &'a self,
+        channel: &'a IoChannel,
+        offset_blocks: u64,
+        num_blocks: u64,
+        populate: bool,
+    ) -> Result<ZcopyHandle<'a>> {
+        if !self.bdev().supports_zcopy() {
+            return Err(Error::Os(libc::ENOTSUP));
+        }
+
+        let (tx, rx) = completion::<NonNull<spdk_bdev_io>>();
+        let ctx = tx.into_raw();
+
+        let rc = unsafe {
+            spdk_bdev_zcopy_start(
+                self.ptr.as_ptr(),
+                channel.as_ptr(),
+                std::ptr::null_mut(),
+                0,
+                offset_blocks,
+                num_blocks,
+                populate,
+                Some(zcopy_start_cb),
+                ctx,
+            )
+        };
+
+        if rc != 0 {
+            // SAFETY: the callback will not run, reclaim the sender
+            drop(unsafe { CompletionSender::<NonNull<spdk_bdev_io>>::from_raw(ctx) });
+            return Err(Error::from_rc(rc));
+        }
+
+        let bdev_io = rx.await?;
+        Ok(ZcopyHandle {
+            bdev_io,
+            _marker: PhantomData,
+        })
+    }
 }
 
 impl Drop for BdevDesc {
@@ -341,6 +858,397 @@ impl Drop for BdevDesc {
     }
 }
 
+/// A zero-copy I/O started by [`BdevDesc::zcopy_start()`].
+///
+/// Exposes the bdev's buffers for the requested blocks. For a write, call
+/// [`end()`](Self::end) with `commit = true` to write the buffers back.
+/// `commit = false` releases the buffers without committing but does not
+/// roll anything back: where the buffers are the bdev's own memory, data
+/// written into them is already on the bdev. Buffers of a read
+/// (`populate = true`) must not be modified.
+///
+/// # Drop
+///
+/// Dropping without [`end()`](Self::end) releases the buffers without
+/// committing, without waiting for completion.
+pub struct ZcopyHandle<'a> {
+    bdev_io: NonNull<spdk_bdev_io>,
+    _marker: PhantomData<(&'a BdevDesc, *mut ())>, // !Send + !Sync
+}
+
+impl ZcopyHandle<'_> {
+    /// Get the buffer iovecs.
+    pub fn iovs(&self) -> &[iovec] {
+        let mut iovs: *mut iovec = std::ptr::null_mut();
+        let mut iovcnt: i32 = 0;
+        unsafe {
+            spdk_bdev_io_get_iovec(self.bdev_io.as_ptr(), &mut iovs, &mut iovcnt);
+            if iovs.is_null() || iovcnt <= 0 {
+                return &[];
+            }
+            std::slice::from_raw_parts(iovs, iovcnt as usize)
+        }
+    }
+
+    /// Iterate over the buffers.
+    pub fn buffers(&self) -> impl Iterator<Item = &[u8]> {
+        self.iovs().iter().map(|iov| unsafe {
+            std::slice::from_raw_parts(iov.iov_base as *const u8, iov.iov_len)
+        })
+    }
+
+    /// Iterate over the buffers mutably.
+    pub fn buffers_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        self.iovs().iter().map(|iov| unsafe {
+            std::slice::from_raw_parts_mut(iov.iov_base as *mut u8, iov.iov_len)
+        })
+    }
+
+    /// Finish the zero-copy I/O.
+    ///
+    /// With `commit` the buffer contents of a write are written to the bdev;
+    /// reads ignore it. Not committing is no undo, see [`ZcopyHandle`].
+    pub async fn end(self, commit: bool) -> Result<()> {
+        let this = ManuallyDrop::new(self);
+        let (tx, rx) = completion::<()>();
+        let ctx = tx.into_raw();
+
+        let rc = unsafe {
+            spdk_bdev_zcopy_end(
+                this.bdev_io.as_ptr(),
+                commit,
+                Some(bdev_io_completion_cb),
+                ctx,
+            )
+        };
+
+        if rc != 0 {
+            // SAFETY: the callback will not run, reclaim the sender
+            drop(unsafe { CompletionSender::<()>::from_raw(ctx) });
+            unsafe { spdk_bdev_free_io(this.bdev_io.as_ptr()) };
+            return Err(Error::from_rc(rc));
+        }
+
+        rx.await
+    }
+
+    /// Get the raw `spdk_bdev_io` pointer.
+    pub fn as_ptr(&self) -> *mut spdk_bdev_io {
+        self.bdev_io.as_ptr()
+    }
+}
+
+impl Drop for ZcopyHandle<'_> {
+    fn drop(&mut self) {
+        let rc = unsafe {
+            spdk_bdev_zcopy_end(
+                self.bdev_io.as_ptr(),
+                false,
+                Some(zcopy_end_noop),
+                std::ptr::null_mut(),
+            )
+        };
+        if rc != 0 {
+            unsafe { spdk_bdev_free_io(self.bdev_io.as_ptr()) };
+        }
+    }
+}
+
+/// A claim on a bdev taken by [`BdevDesc::claim()`].
+///
+/// # Drop
+///
+/// Releases the claim.
+pub struct ClaimGuard<'a> {
+    bdev: Bdev,
+    _marker: PhantomData<(&'a BdevDesc, *mut ())>, // !Send + !Sync
+}
+
+impl ClaimGuard<'_> {
+    /// Get the claimed bdev.
+    pub fn bdev(&self) -> Bdev {
+        self.bdev
+    }
+}
+
+impl Drop for ClaimGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { spdk_bdev_module_release_bdev(self.bdev.as_ptr()) };
+    }
+}
+
+/// A bdev module identity that claims are recorded under.
+///
+/// This is a claim token only, not a registered bdev module: the
+/// `spdk_bdev_module` is never added to SPDK's module list, so it has no
+/// init or fini hooks and cannot create bdevs. SPDK only stores the pointer
+/// as the claim owner and logs its name when another claim or write open
+/// is rejected.
+///
+/// Virtual bdevs open their base bdev with
+/// [`BdevDesc::open_for_module()`] so claims name the module that owns the
+/// base.
+///
+/// Each name allocates one `spdk_bdev_module` that is intentionally never
+/// freed, since claims may outlive any Rust owner; [`new()`](Self::new)
+/// returns the same module for the same name, so the leak is bounded by
+/// the number of distinct names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BdevModule {
+    ptr: NonNull<spdk_bdev_module>,
+}
+
+// SAFETY: the module is never mutated or freed after creation
+unsafe impl Send for BdevModule {}
+unsafe impl Sync for BdevModule {}
+
+/// Modules created by [`BdevModule::new()`], with their names
+static MODULES: Mutex<Vec<(String, usize)>> = Mutex::new(Vec::new());
+
+impl BdevModule {
+    /// Get the module named `name`, creating it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NulError`] if `name` contains a nul byte.
+    pub fn new(name: &str) -> Result<Self> {
+        let mut modules = MODULES.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&(_, ptr)) = modules.iter().find(|(existing, _)| existing == name) {
+            return Ok(Self {
+                ptr: NonNull::new(ptr as *mut spdk_bdev_module).expect("module is non-null"),
+            });
+        }
+
+        let module = spdk_bdev_module {
+            name: CString::new(name)?.into_raw(),
+            ..Default::default()
+        };
+        let ptr = NonNull::from(Box::leak(Box::new(module)));
+        modules.push((name.to_string(), ptr.as_ptr() as usize));
+        Ok(Self { ptr })
+    }
+
+    /// The module [`BdevDesc::claim()`] uses for descriptors not opened
+    /// for a module.
+    fn default_module() -> Self {
+        Self::new("spdk_io").expect("valid module name")
+    }
+
+    /// Get the module name.
+    pub fn name(&self) -> &str {
+        unsafe { CStr::from_ptr(self.ptr.as_ref().name) }
+            .to_str()
+            .expect("module names are created from &str")
+    }
+
+    /// Get the raw pointer.
+    pub fn as_ptr(&self) -> *mut spdk_bdev_module {
+        self.ptr.as_ptr()
+    }
+}
+
+/// Reference-counted bdev descriptor shared by multiple threads.
+///
+/// Created with [`Bdev::open_shared()`] or [`BdevDesc::into_shared()`].
+/// Cloning is cheap; the underlying `spdk_bdev_desc` is closed when the last
+/// clone is dropped. SPDK requires the close to happen on the thread that
+/// opened the descriptor, so if the last clone is dropped elsewhere the
+/// close is sent to the opening thread as a message (which must still be
+/// polled at that point). If the message cannot be sent, e.g. because that
+/// thread has exited or is exiting, the descriptor is leaked and an error
+/// is written to SPDK's log.
+///
+/// Dereferences to [`BdevDesc`], so each thread obtains its own channel with
+/// [`get_io_channel()`](BdevDesc::get_io_channel) and submits I/O with
+/// [`read()`](BdevDesc::read) and [`write()`](BdevDesc::write).
+///
+/// # Thread Safety
+///
+/// `Send + Sync` - the descriptor may be shared freely, but the
+/// [`IoChannel`]s obtained from it are `!Send` and must stay on the thread
+/// that created them.
+///
+/// # Example
+///
+/// ```no_run
+/// use spdk_io::{Bdev, DmaBuf, SpdkEvent};
+///
+/// # fn example() -> spdk_io::Result<()> {
+/// let desc = Bdev::get_by_name("Null0").unwrap().open_shared(false)?;
+///
+/// let desc_clone = desc.clone();
+/// SpdkEvent::call_on(1, move || {
+///     // Each thread gets its own channel from the shared descriptor
+///     let channel = desc_clone.get_io_channel().unwrap();
+///     let mut buf = DmaBuf::alloc(512, 512).unwrap();
+///     spdk_io::block_on(desc_clone.read(&channel, &mut buf, 0)).unwrap();
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SharedBdevDesc {
+    inner: Arc<SharedDescInner>,
+}
+
+struct SharedDescInner {
+    desc: ManuallyDrop<BdevDesc>,
+    /// Id of the thread that opened the descriptor and must close it
+    thread: Option<u64>,
+}
+
+// SAFETY: spdk_bdev_get_io_channel() and I/O submission on a per-thread
+// channel may be called from any SPDK thread. The close is routed back to
+// the opening thread in Drop.
+unsafe impl Send for SharedDescInner {}
+unsafe impl Sync for SharedDescInner {}
+
+impl Deref for SharedBdevDesc {
+    type Target = BdevDesc;
+
+    fn deref(&self) -> &BdevDesc {
+        &self.inner.desc
+    }
+}
+
+impl Drop for SharedDescInner {
+    fn drop(&mut self) {
+        // SAFETY: desc is not used again after being taken
+        let desc = unsafe { ManuallyDrop::take(&mut self.desc) };
+
+        let Some(thread) = self.thread else {
+            drop(desc);
+            return;
+        };
+        let current = NonNull::new(unsafe { spdk_get_thread() })
+            .map(|ptr| unsafe { spdk_thread_get_id(ptr.as_ptr()) });
+        if current == Some(thread) {
+            drop(desc);
+            return;
+        }
+
+        // The id is looked up in the live-thread registry, so an exited
+        // opening thread is detected instead of messaged after free
+        let ctx = desc.as_ptr() as *mut c_void;
+        std::mem::forget(desc);
+        if let Err(e) = crate::thread::send_to_thread(thread, Some(close_desc_msg), ctx) {
+            // Closing here would break SPDK's same-thread rule, so the
+            // descriptor is leaked instead
+            crate::env::log_error(
+                c"SharedBdevDesc::drop",
+                &format!(
+                    "cannot send close to the opening thread {} ({}), leaking descriptor",
+                    thread, e
+                ),
+            );
+        }
+    }
+}
+
+/// Message callback closing a descriptor on its opening thread.
+unsafe extern "C" fn close_desc_msg(ctx: *mut c_void) {
+    unsafe { spdk_bdev_close(ctx as *mut spdk_bdev_desc) };
+}
+
+/// Create a null bdev.
+///
+/// Null bdevs complete writes without storing anything and reads without
+/// filling the buffer, which makes them useful for benchmarking the I/O
+/// path without storage overhead. The bdev is deleted when the returned
+/// [`NullBdev`] is dropped.
+///
+/// Must be called on an SPDK thread after the bdev subsystem is initialized
+/// (e.g. inside [`SpdkApp`](crate::SpdkApp)).
+///
+/// # Errors
+///
+/// Returns an error if the name is taken, or `block_size` or `num_blocks`
+/// is invalid. Returns [`Error::Os`] with `ENOTSUP` if spdk-io-sys was built
+/// without the null bdev API (SPDK before v26.01 without `SPDK_SOURCE_DIR`).
+///
+/// # Example
+///
+/// ```no_run
+/// use spdk_io::bdev;
+///
+/// # fn example() -> spdk_io::Result<()> {
+/// let null = bdev::create_null("Null0", 262144, 512)?;
+/// let desc = null.bdev().open(true)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn create_null(name: &str, num_blocks: u64, block_size: u32) -> Result<NullBdev> {
+    let name_cstr = CString::new(name)?;
+
+    let mut bdev: *mut spdk_bdev = std::ptr::null_mut();
+    let rc =
+        unsafe { spdk_rs_bdev_null_create(&mut bdev, name_cstr.as_ptr(), num_blocks, block_size) };
+    if rc != 0 {
+        return Err(Error::from_rc(rc));
+    }
+
+    let ptr = NonNull::new(bdev).ok_or(Error::DeviceNotFound(name.to_string()))?;
+    Ok(NullBdev {
+        bdev: Bdev {
+            ptr,
+            _marker: PhantomData,
+        },
+        name: name_cstr,
+    })
+}
+
+/// A null bdev created with [`create_null()`].
+///
+/// # Thread Safety
+///
+/// `!Send + !Sync` - like [`Bdev`].
+///
+/// # Drop
+///
+/// Starts deleting the bdev without waiting for it to finish. Use
+/// [`delete()`](Self::delete) to wait. Descriptors on the bdev should be
+/// closed first.
+pub struct NullBdev {
+    bdev: Bdev,
+    name: CString,
+}
+
+impl NullBdev {
+    /// Get the bdev handle.
+    pub fn bdev(&self) -> Bdev {
+        self.bdev
+    }
+
+    /// Delete the bdev and wait for the deletion to complete.
+    pub async fn delete(self) -> Result<()> {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so the name is moved out once
+        let name = unsafe { std::ptr::read(&this.name) };
+        let completion = Completion::<()>::new();
+
+        unsafe {
+            spdk_rs_bdev_null_delete(name.as_ptr(), Some(Completion::CB_FN), completion.cb_arg())
+        };
+
+        completion.into_future().await
+    }
+}
+
+impl Drop for NullBdev {
+    fn drop(&mut self) {
+        unsafe {
+            spdk_rs_bdev_null_delete(
+                self.name.as_ptr(),
+                Some(null_delete_noop),
+                std::ptr::null_mut(),
+            )
+        };
+    }
+}
+
+/// Callback for the delete started in `Drop` (no-op).
+unsafe extern "C" fn null_delete_noop(_ctx: *mut c_void, _bdeverrno: i32) {}
+
 /// Bdev I/O completion callback.
 ///
 /// Called by SPDK when a read/write operation completes.
@@ -362,6 +1270,35 @@ unsafe extern "C" fn bdev_io_completion_cb(
     }
 }
 
+/// Completion callback for `spdk_bdev_zcopy_start`.
+///
+/// On success the bdev_io is handed to the [`ZcopyHandle`], which frees it
+/// once the zero-copy I/O is ended.
+unsafe extern "C" fn zcopy_start_cb(
+    bdev_io: *mut spdk_bdev_io,
+    success: bool,
+    cb_arg: *mut c_void,
+) {
+    // SAFETY: cb_arg was created by CompletionSender::into_raw()
+    let tx = unsafe { CompletionSender::<NonNull<spdk_bdev_io>>::from_raw(cb_arg) };
+    match NonNull::new(bdev_io) {
+        Some(bdev_io) if success => tx.success(bdev_io),
+        _ => {
+            unsafe { spdk_bdev_free_io(bdev_io) };
+            tx.error(Error::IoError);
+        }
+    }
+}
+
+/// Completion callback for `spdk_bdev_zcopy_end` from [`ZcopyHandle`]'s drop.
+unsafe extern "C" fn zcopy_end_noop(
+    bdev_io: *mut spdk_bdev_io,
+    _success: bool,
+    _cb_arg: *mut c_void,
+) {
+    unsafe { spdk_bdev_free_io(bdev_io) };
+}
+
 /// Bdev event callback (currently a no-op).
 ///
 /// This callback receives notifications about bdev events like removal.
@@ -11,6 +11,18 @@
 //! 4. In callback, reconstruct sender via [`CompletionSender::from_raw()`]
 //! 5. Send the result
 //! 6. Await the receiver
+//!
+//! For the common `void (*cb_fn)(void *cb_arg, int rc)` callback,
+//! [`Completion`] bundles these steps: pass [`Completion::CB_FN`] and
+//! [`cb_arg()`](Completion::cb_arg) to SPDK, then hand the submission's
+//! return code to [`submitted()`](Completion::submitted) and await the
+//! result.
+//!
+//! # Cancellation
+//!
+//! Dropping the receiver (or a future awaiting it) before the callback
+//! fires is safe: the sender stays boxed behind `cb_arg` until SPDK invokes
+//! the callback, which then frees it and discards the result.
 
 use std::ffi::c_void;
 use std::future::Future;
@@ -94,6 +106,87 @@ pub fn completion<T>() -> (CompletionSender<T>, CompletionReceiver<T>) {
     (CompletionSender { tx }, CompletionReceiver { rx })
 }
 
+/// Completion of an SPDK call reporting an errno-style `rc`.
+///
+/// Owns the sender behind [`cb_arg()`](Self::cb_arg) until the call is
+/// submitted. The generated callback ([`CB_FN`](Completion::CB_FN) for
+/// `Completion<()>`) reclaims it and resolves the future with `Ok` for
+/// `rc == 0`, or [`Error::from_rc()`] otherwise.
+///
+/// # Example
+///
+/// ```ignore
+/// let completion = Completion::<()>::new();
+/// let rc = unsafe {
+///     spdk_accel_submit_copy(ch, dst, src, len, Some(Completion::CB_FN), completion.cb_arg())
+/// };
+/// completion.submitted(rc)?.await?;
+/// ```
+pub struct Completion<T> {
+    cb_arg: *mut c_void,
+    rx: CompletionReceiver<T>,
+}
+
+impl<T> Completion<T> {
+    /// Allocate the channel and the boxed sender behind `cb_arg`.
+    pub fn new() -> Self {
+        let (tx, rx) = completion();
+        Self {
+            cb_arg: tx.into_raw(),
+            rx,
+        }
+    }
+
+    /// The `cb_arg` to pass to SPDK alongside the callback.
+    pub fn cb_arg(&self) -> *mut c_void {
+        self.cb_arg
+    }
+
+    /// Finish submission with the submitting call's return code.
+    ///
+    /// SPDK does not invoke the callback when submission fails, so a
+    /// non-zero `rc` frees the sender and returns [`Error::from_rc()`].
+    /// Otherwise returns the future to await; the callback now owns
+    /// `cb_arg`.
+    ///
+    /// A `Completion` dropped without calling this leaks its sender, since
+    /// SPDK may still hold `cb_arg`.
+    pub fn submitted(self, rc: i32) -> Result<CompletionReceiver<T>> {
+        if rc != 0 {
+            drop(unsafe { CompletionSender::<T>::from_raw(self.cb_arg) });
+            return Err(Error::from_rc(rc));
+        }
+        Ok(self.rx)
+    }
+
+    /// The future for a call that has no submission return code.
+    pub fn into_future(self) -> CompletionReceiver<T> {
+        self.rx
+    }
+}
+
+impl<T> Default for Completion<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Completion<()> {
+    /// Callback for `void (*)(void *cb_arg, int rc)`.
+    pub const CB_FN: unsafe extern "C" fn(*mut c_void, i32) = errno_callback;
+}
+
+/// Resolve a `Completion<()>` from an errno-style `rc`.
+unsafe extern "C" fn errno_callback(cb_arg: *mut c_void, rc: i32) {
+    let tx = unsafe { CompletionSender::<()>::from_raw(cb_arg) };
+
+    if rc == 0 {
+        tx.success(());
+    } else {
+        tx.error(Error::from_rc(rc));
+    }
+}
+
 /// Helper to create a completion for I/O operations that return `()` on success.
 ///
 /// This is a convenience for the common case of operations that only
@@ -188,6 +281,58 @@ mod tests {
         }
     }
 
+    /// Stands in for an SPDK call that completes inline.
+    unsafe fn fake_submit(
+        cb_fn: unsafe extern "C" fn(*mut c_void, i32),
+        cb_arg: *mut c_void,
+        submit_rc: i32,
+        rc: i32,
+    ) -> i32 {
+        if submit_rc == 0 {
+            unsafe { cb_fn(cb_arg, rc) };
+        }
+        submit_rc
+    }
+
+    #[test]
+    fn test_completion_callback() {
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let completion = Completion::<()>::new();
+        let rc = unsafe { fake_submit(Completion::CB_FN, completion.cb_arg(), 0, 0) };
+        let mut rx = completion.submitted(rc).unwrap();
+        assert!(matches!(
+            Pin::new(&mut rx).poll(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+
+        let completion = Completion::<()>::new();
+        let rc = unsafe { fake_submit(Completion::CB_FN, completion.cb_arg(), 0, -libc::EIO) };
+        let mut rx = completion.submitted(rc).unwrap();
+        assert!(matches!(
+            Pin::new(&mut rx).poll(&mut cx),
+            Poll::Ready(Err(Error::Os(libc::EIO)))
+        ));
+
+        // Submission failed: no callback, the sender is freed here
+        let completion = Completion::<()>::new();
+        let rc = unsafe { fake_submit(Completion::CB_FN, completion.cb_arg(), -libc::ENOMEM, 0) };
+        assert!(matches!(
+            completion.submitted(rc),
+            Err(Error::Os(libc::ENOMEM))
+        ));
+    }
+
+    #[test]
+    fn test_completion_dropped_before_callback() {
+        let completion = Completion::<()>::new();
+        let cb_arg = completion.cb_arg();
+        drop(completion.submitted(0).unwrap());
+
+        // The callback still owns cb_arg and discards the result
+        unsafe { Completion::CB_FN(cb_arg, 0) };
+    }
+
     #[test]
     fn test_into_raw_from_raw() {
         let (tx, rx) = completion::<i32>();
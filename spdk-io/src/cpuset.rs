@@ -0,0 +1,200 @@
+//! CPU core sets backed by `spdk_cpuset`.
+//!
+//! A [`CpuSet`] replaces hand-written hex core masks such as `"0x3"`: build
+//! it from core numbers and pass it to
+//! [`SpdkEnvBuilder::core_mask_set()`](crate::SpdkEnvBuilder::core_mask_set),
+//! or use it to read and pin an SPDK thread's cpumask
+//! ([`SpdkThread::cpumask()`](crate::SpdkThread::cpumask)).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use spdk_io::{CpuSet, SpdkEnv};
+//!
+//! let cores = CpuSet::from_cores([0, 1]);
+//! assert_eq!(cores.to_hex_string(), "0x3");
+//!
+//! let _env = SpdkEnv::builder().core_mask_set(cores).build()?;
+//! # Ok::<(), spdk_io::Error>(())
+//! ```
+
+use std::ffi::{CStr, CString};
+use std::fmt;
+
+use spdk_io_sys::*;
+
+use crate::error::{Error, Result};
+
+/// Number of cores a [`CpuSet`] can hold (`SPDK_CPUSET_SIZE`).
+pub const MAX_CORES: u32 = SPDK_CPUSET_SIZE;
+
+/// Set of CPU cores (`spdk_cpuset`).
+///
+/// Core numbers range from 0 to [`MAX_CORES`] - 1.
+#[derive(Clone)]
+pub struct CpuSet {
+    inner: spdk_cpuset,
+}
+
+impl CpuSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        let mut inner = std::mem::MaybeUninit::<spdk_cpuset>::uninit();
+        unsafe {
+            spdk_cpuset_zero(inner.as_mut_ptr());
+            Self {
+                inner: inner.assume_init(),
+            }
+        }
+    }
+
+    /// Create a set containing `cores`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a core is not below [`MAX_CORES`].
+    pub fn from_cores<I: IntoIterator<Item = u32>>(cores: I) -> Self {
+        let mut set = Self::new();
+        for core in cores {
+            set.set(core);
+        }
+        set
+    }
+
+    /// Parse a core mask (`"0x3"`) or core list (`"[0-1,4]"`) as accepted
+    /// by SPDK's `-m` option.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if SPDK cannot parse `mask`, or
+    /// [`Error::NulError`] if it contains a NUL byte.
+    pub fn parse(mask: &str) -> Result<Self> {
+        let cstr = CString::new(mask)?;
+        let mut set = Self::new();
+        let rc = unsafe { spdk_cpuset_parse(&mut set.inner, cstr.as_ptr()) };
+        if rc != 0 {
+            return Err(Error::InvalidArgument(format!(
+                "invalid core mask: {}",
+                mask
+            )));
+        }
+        Ok(set)
+    }
+
+    /// Add `core` to the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `core` is not below [`MAX_CORES`].
+    pub fn set(&mut self, core: u32) {
+        assert!(core < MAX_CORES, "core {} out of range", core);
+        unsafe { spdk_cpuset_set_cpu(&mut self.inner, core, true) };
+    }
+
+    /// Remove `core` from the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `core` is not below [`MAX_CORES`].
+    pub fn clear(&mut self, core: u32) {
+        assert!(core < MAX_CORES, "core {} out of range", core);
+        unsafe { spdk_cpuset_set_cpu(&mut self.inner, core, false) };
+    }
+
+    /// Check if `core` is in the set. Cores beyond [`MAX_CORES`] never are.
+    pub fn contains(&self, core: u32) -> bool {
+        core < MAX_CORES && unsafe { spdk_cpuset_get_cpu(&self.inner, core) }
+    }
+
+    /// Number of cores in the set.
+    pub fn count(&self) -> u32 {
+        unsafe { spdk_cpuset_count(&self.inner) }
+    }
+
+    /// Check if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+
+    /// Format the set as a `0x`-prefixed hex core mask (`"0x3"` for cores 0
+    /// and 1, `"0x0"` when empty).
+    pub fn to_hex_string(&self) -> String {
+        // spdk_cpuset_fmt() formats into the set's own buffer
+        let mut copy = self.inner;
+        let hex = unsafe { CStr::from_ptr(spdk_cpuset_fmt(&mut copy)) };
+        format!("0x{}", hex.to_string_lossy())
+    }
+
+    /// Create a set from a raw `spdk_cpuset` pointer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid `spdk_cpuset`.
+    pub unsafe fn from_raw(ptr: *const spdk_cpuset) -> Self {
+        Self {
+            inner: unsafe { *ptr },
+        }
+    }
+
+    /// Get the raw pointer for SPDK calls.
+    pub fn as_ptr(&self) -> *const spdk_cpuset {
+        &self.inner
+    }
+}
+
+impl Default for CpuSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for CpuSet {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { spdk_cpuset_equal(&self.inner, &other.inner) }
+    }
+}
+
+impl Eq for CpuSet {}
+
+impl fmt::Debug for CpuSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CpuSet")
+            .field(&self.to_hex_string())
+            .finish()
+    }
+}
+
+impl fmt::Display for CpuSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_cores_hex() {
+        assert_eq!(CpuSet::from_cores([0, 1]).to_hex_string(), "0x3");
+        assert_eq!(CpuSet::from_cores([4]).to_hex_string(), "0x10");
+        assert_eq!(CpuSet::from_cores([0, 8]).to_hex_string(), "0x101");
+        assert_eq!(CpuSet::new().to_hex_string(), "0x0");
+    }
+
+    #[test]
+    fn test_set_clear_count() {
+        let mut set = CpuSet::from_cores([0, 1, 2]);
+        assert_eq!(set.count(), 3);
+
+        set.clear(1);
+        set.set(5);
+        assert_eq!(set.count(), 3);
+        assert!(set.contains(0) && !set.contains(1) && set.contains(5));
+        assert!(!set.contains(MAX_CORES));
+        assert_eq!(set, CpuSet::from_cores([0, 2, 5]));
+
+        assert_eq!(CpuSet::parse("0x25").unwrap(), set);
+        assert!(CpuSet::parse("not a mask").is_err());
+    }
+}
@@ -20,10 +20,21 @@
 
 use std::ptr::NonNull;
 
-use spdk_io_sys::{spdk_dma_free, spdk_dma_malloc, spdk_dma_zmalloc};
+use spdk_io_sys::{
+    SPDK_VTOPHYS_ERROR, spdk_dma_free, spdk_dma_malloc, spdk_dma_malloc_socket, spdk_dma_zmalloc,
+    spdk_vtophys,
+};
 
+#[cfg(feature = "bdev")]
+use crate::bdev::BdevDesc;
 use crate::error::{Error, Result};
 
+/// NUMA id meaning "any socket" (same as SPDK's `SPDK_ENV_NUMA_ID_ANY`).
+pub const NUMA_ID_ANY: i32 = -1;
+
+/// Alignment used by [`DmaBuf::page_aligned`].
+pub const PAGE_SIZE: usize = 4096;
+
 /// A DMA-capable memory buffer for SPDK I/O operations.
 ///
 /// Buffers are allocated via `spdk_dma_malloc()` which returns pinned,
@@ -110,6 +121,92 @@ impl DmaBuf {
             .ok_or(Error::DmaAlloc(size))
     }
 
+    /// Allocate a DMA-capable buffer on a specific NUMA socket.
+    ///
+    /// Same as [`alloc`](Self::alloc) but the memory is taken from the given
+    /// socket, avoiding cross-NUMA DMA when the device is on that socket.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Size in bytes to allocate
+    /// * `align` - Alignment requirement (must be power of 2, or 0 for default)
+    /// * `socket_id` - NUMA socket to allocate on ([`NUMA_ID_ANY`] for any)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DmaAlloc`] if allocation fails.
+    pub fn alloc_on_socket(size: usize, align: usize, socket_id: i32) -> Result<Self> {
+        if size == 0 {
+            return Err(Error::InvalidArgument("size must be > 0".to_string()));
+        }
+
+        let ptr = unsafe { spdk_dma_malloc_socket(size, align, std::ptr::null_mut(), socket_id) };
+
+        NonNull::new(ptr as *mut u8)
+            .map(|ptr| Self { ptr, len: size })
+            .ok_or(Error::DmaAlloc(size))
+    }
+
+    /// Allocate a buffer for `num_blocks` blocks of `block_size` bytes,
+    /// aligned to `block_size`.
+    ///
+    /// Saves working out size and alignment by hand for bdev and NVMe I/O.
+    /// The contents are not zeroed, like [`alloc`](Self::alloc).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `block_size` is not a power of
+    /// two, `num_blocks` is zero or the size overflows, and
+    /// [`Error::DmaAlloc`] if allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::DmaBuf;
+    ///
+    /// // Two 4 KiB blocks, 4 KiB aligned
+    /// let buf = DmaBuf::for_blocks(4096, 2)?;
+    /// assert_eq!(buf.len(), 8192);
+    /// # Ok::<(), spdk_io::Error>(())
+    /// ```
+    pub fn for_blocks(block_size: usize, num_blocks: usize) -> Result<Self> {
+        if !block_size.is_power_of_two() {
+            return Err(Error::InvalidArgument(format!(
+                "block size {} is not a power of two",
+                block_size
+            )));
+        }
+        let size = block_size.checked_mul(num_blocks).ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "{} blocks of {} bytes overflow usize",
+                num_blocks, block_size
+            ))
+        })?;
+        Self::alloc(size, block_size)
+    }
+
+    /// Allocate `size` bytes aligned to [`PAGE_SIZE`] (4096).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DmaAlloc`] if allocation fails.
+    pub fn page_aligned(size: usize) -> Result<Self> {
+        Self::alloc(size, PAGE_SIZE)
+    }
+
+    /// Get the physical (IO) address of the start of the buffer.
+    ///
+    /// Returns `None` if the address cannot be translated.
+    pub fn phys_addr(&self) -> Option<u64> {
+        let mut size = self.len as u64;
+        let addr = unsafe { spdk_vtophys(self.ptr.as_ptr() as *const std::ffi::c_void, &mut size) };
+        if addr == SPDK_VTOPHYS_ERROR as u64 {
+            None
+        } else {
+            Some(addr)
+        }
+    }
+
     /// Get the buffer length in bytes.
     #[inline]
     pub fn len(&self) -> usize {
@@ -178,6 +275,104 @@ impl AsMut<[u8]> for DmaBuf {
     }
 }
 
+/// A fixed-size pool of pre-allocated DMA buffers.
+///
+/// All buffers are allocated up front with the same size and alignment,
+/// optionally on a specific NUMA socket, so the I/O path never has to call
+/// into the DMA allocator.
+///
+/// # Example
+///
+/// ```no_run
+/// use spdk_io::dma::BufferPool;
+///
+/// let mut pool = BufferPool::new_on_socket(4096, 16, 4096, 0)?;
+/// let buf = pool.get().expect("pool exhausted");
+/// // ... use buf for I/O ...
+/// pool.put(buf);
+/// # Ok::<(), spdk_io::Error>(())
+/// ```
+pub struct BufferPool {
+    free: Vec<DmaBuf>,
+    buf_size: usize,
+    socket_id: i32,
+}
+
+impl BufferPool {
+    /// Create a pool of `count` buffers on any NUMA socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DmaAlloc`] if any buffer allocation fails.
+    pub fn new(buf_size: usize, count: usize, align: usize) -> Result<Self> {
+        Self::new_on_socket(buf_size, count, align, NUMA_ID_ANY)
+    }
+
+    /// Create a pool of `count` buffers allocated on `socket_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DmaAlloc`] if any buffer allocation fails.
+    pub fn new_on_socket(
+        buf_size: usize,
+        count: usize,
+        align: usize,
+        socket_id: i32,
+    ) -> Result<Self> {
+        let free = (0..count)
+            .map(|_| DmaBuf::alloc_on_socket(buf_size, align, socket_id))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            free,
+            buf_size,
+            socket_id,
+        })
+    }
+
+    /// Create a pool sized for a bdev, on the bdev's NUMA socket.
+    ///
+    /// Each buffer is one block long and aligned to the bdev's required
+    /// buffer alignment.
+    #[cfg(feature = "bdev")]
+    pub fn for_bdev(desc: &BdevDesc, count: usize) -> Result<Self> {
+        let bdev = desc.bdev();
+        Self::new_on_socket(
+            bdev.block_size() as usize,
+            count,
+            bdev.buf_align(),
+            bdev.numa_id(),
+        )
+    }
+
+    /// Take a buffer from the pool.
+    ///
+    /// Returns `None` if the pool is exhausted.
+    pub fn get(&mut self) -> Option<DmaBuf> {
+        self.free.pop()
+    }
+
+    /// Return a buffer to the pool.
+    pub fn put(&mut self, buf: DmaBuf) {
+        self.free.push(buf);
+    }
+
+    /// Number of buffers currently available.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Size in bytes of each buffer.
+    pub fn buf_size(&self) -> usize {
+        self.buf_size
+    }
+
+    /// NUMA socket the buffers were allocated on.
+    pub fn socket_id(&self) -> i32 {
+        self.socket_id
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
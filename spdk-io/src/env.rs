@@ -20,15 +20,22 @@
 //! } // SpdkEnv dropped here, SPDK cleaned up
 //! ```
 
-use std::ffi::CString;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::ffi::{CStr, CString, c_char, c_int, c_void};
+use std::io::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, AtomicU32, Ordering};
 
 use spdk_io_sys::*;
 
+use crate::cpuset::CpuSet;
 use crate::error::{Error, Result};
 
 /// SPDK log level for controlling verbosity.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Levels are ordered by SPDK's numeric value, so a higher level is more
+/// verbose: `Disabled < Error < Warn < Notice < Info < Debug`. A message at
+/// `level` passes a filter configured at `max` when `level <= max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(i32)]
 pub enum LogLevel {
     /// Disable all logging
@@ -45,8 +52,385 @@ pub enum LogLevel {
     Debug = spdk_log_level_SPDK_LOG_DEBUG,
 }
 
-/// Global flag to track if SPDK environment is initialized
-static ENV_INITIALIZED: AtomicBool = AtomicBool::new(false);
+/// Log `msg` at error level through SPDK's logger, like `SPDK_ERRLOG`.
+///
+/// For errors that cannot be returned, e.g. in `Drop`. The message goes to
+/// SPDK's log (and is filtered by its print level) along with the caller's
+/// location; `func` names the failing operation.
+#[track_caller]
+#[cfg_attr(not(feature = "bdev"), allow(dead_code))]
+pub(crate) fn log_error(func: &CStr, msg: &str) {
+    let location = std::panic::Location::caller();
+    let file = CString::new(location.file()).unwrap_or_default();
+    let msg = CString::new(msg.replace('\0', "")).unwrap_or_default();
+    unsafe {
+        spdk_log(
+            spdk_log_level_SPDK_LOG_ERROR,
+            file.as_ptr(),
+            location.line() as c_int,
+            func.as_ptr(),
+            c"%s\n".as_ptr(),
+            msg.as_ptr(),
+        )
+    };
+}
+
+/// Pin the calling OS thread to the given CPU cores.
+///
+/// Independent of [`SpdkEnvBuilder::core_mask`] and usable before or after
+/// SPDK initialization, e.g. to keep Tokio workers off the reactor cores.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if `cores` is empty or a core is out
+/// of range, or [`Error::Os`] if `sched_setaffinity` fails (e.g. a core is
+/// offline or outside the process's cpuset).
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(cores: &[u32]) -> Result<()> {
+    if cores.is_empty() {
+        return Err(Error::InvalidArgument("no cores to pin to".into()));
+    }
+
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &core in cores {
+        if core as usize >= libc::CPU_SETSIZE as usize {
+            return Err(Error::InvalidArgument(format!(
+                "core {} out of range",
+                core
+            )));
+        }
+        unsafe { libc::CPU_SET(core as usize, &mut set) };
+    }
+    set_current_thread_affinity(&set)
+}
+
+/// Allow the calling OS thread to run on any configured CPU core again.
+///
+/// Undoes [`pin_current_thread`] or the pinning applied by SPDK to its own
+/// threads.
+#[cfg(target_os = "linux")]
+pub fn unpin_current_thread() -> Result<()> {
+    let ncpus = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_CONF) };
+    let ncpus = (ncpus.max(1) as usize).min(libc::CPU_SETSIZE as usize);
+
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for core in 0..ncpus {
+        unsafe { libc::CPU_SET(core, &mut set) };
+    }
+    set_current_thread_affinity(&set)
+}
+
+#[cfg(target_os = "linux")]
+fn set_current_thread_affinity(set: &libc::cpu_set_t) -> Result<()> {
+    // pid 0 means the calling thread
+    let rc = unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set) };
+    if rc != 0 {
+        return Err(Error::from_errno(
+            std::io::Error::last_os_error().raw_os_error().unwrap_or(0),
+        ));
+    }
+    Ok(())
+}
+
+/// Get the version string of the SPDK headers spdk-io-sys was built against.
+///
+/// Mirrors SPDK's `SPDK_VERSION_STRING` macro (e.g. `"SPDK v24.09"`), which
+/// bindgen cannot expand directly. Useful for logs and bug reports.
+///
+/// This is a compile-time constant: SPDK has no runtime version query, so
+/// with shared libraries the SPDK actually loaded may be a different build.
+/// A running app reports its own version through the `spdk_get_version`
+/// RPC.
+pub fn spdk_build_version() -> String {
+    let mut version = format!("SPDK v{}.{:02}", SPDK_VERSION_MAJOR, SPDK_VERSION_MINOR);
+    if SPDK_VERSION_PATCH != 0 {
+        version.push_str(&format!(".{}", SPDK_VERSION_PATCH));
+    }
+    if let Ok(suffix) = CStr::from_bytes_with_nul(SPDK_VERSION_SUFFIX) {
+        version.push_str(&suffix.to_string_lossy());
+    }
+    version
+}
+
+/// Get the number of NUMA nodes (sockets) DPDK detected.
+///
+/// Always at least 1: a single-socket system, or one without NUMA support,
+/// counts as one node. Only meaningful after the environment is
+/// initialized.
+pub fn numa_node_count() -> u32 {
+    unsafe { rte_socket_count() }.max(1)
+}
+
+/// Get the NUMA node (socket) of lcore `core`.
+///
+/// Node ids start at 0, so on a single-socket system every core maps to 0.
+/// Returns [`NUMA_ID_ANY`](crate::dma::NUMA_ID_ANY) if SPDK doesn't know
+/// the core. Pass the result as the socket of NUMA-local allocations, e.g.
+/// [`Mempool::new`](crate::Mempool::new).
+pub fn core_to_socket(core: u32) -> i32 {
+    unsafe { spdk_env_get_numa_id(core) }
+}
+
+// DPDK log, memory and lcore APIs (rte_log.h, rte_memory.h and rte_lcore.h
+// are not part of the generated bindings)
+unsafe extern "C" {
+    fn rte_socket_count() -> std::ffi::c_uint;
+    fn rte_openlog_stream(f: *mut libc::FILE) -> c_int;
+    fn rte_log_cur_msg_loglevel() -> c_int;
+    fn rte_eal_get_physmem_size() -> u64;
+    fn rte_mem_event_callback_register(
+        name: *const c_char,
+        clb: unsafe extern "C" fn(c_int, *const c_void, usize, *mut c_void),
+        arg: *mut c_void,
+    ) -> c_int;
+    fn rte_mem_event_callback_unregister(name: *const c_char, arg: *mut c_void) -> c_int;
+}
+
+/// DPDK's `RTE_MEM_EVENT_ALLOC`; `RTE_MEM_EVENT_FREE` is the other value.
+const RTE_MEM_EVENT_ALLOC: c_int = 0;
+
+/// Memory added to or removed from DPDK's heap.
+///
+/// Reported to callbacks registered with [`register_mem_event_cb()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemEvent {
+    /// Hugepage memory was mapped into the heap.
+    Added {
+        /// Virtual address of the new memory
+        addr: usize,
+        /// Length in bytes
+        len: usize,
+    },
+    /// Memory is about to be unmapped from the heap.
+    Removed {
+        /// Virtual address of the memory
+        addr: usize,
+        /// Length in bytes
+        len: usize,
+    },
+}
+
+type MemEventFn = Mutex<Box<dyn FnMut(MemEvent) + Send>>;
+
+/// Unique suffix for DPDK callback names.
+static NEXT_MEM_EVENT_CB: AtomicU32 = AtomicU32::new(0);
+
+/// Registration of a memory event callback.
+///
+/// Unregisters the callback when dropped; once the drop returns, DPDK no
+/// longer calls it.
+pub struct MemEventCallback {
+    name: CString,
+    ctx: *mut MemEventFn,
+}
+
+// SAFETY: the closure is Send and only reached through its Mutex, and DPDK
+// allows unregistering from any thread
+unsafe impl Send for MemEventCallback {}
+
+impl Drop for MemEventCallback {
+    fn drop(&mut self) {
+        unsafe {
+            rte_mem_event_callback_unregister(self.name.as_ptr(), self.ctx as *mut c_void);
+            drop(Box::from_raw(self.ctx));
+        }
+    }
+}
+
+/// Call `f` whenever DPDK grows or shrinks its memory.
+///
+/// In DPDK's dynamic memory mode, hugepages are mapped when allocations
+/// need more memory and may be unmapped when freed. Code that registers
+/// memory with devices (e.g. an NVMe controller's memory map) can follow
+/// these events.
+///
+/// `f` runs on whichever thread triggered the change, with DPDK's memory
+/// hotplug lock held: it must not allocate or free DMA memory itself.
+///
+/// # Errors
+///
+/// Returns [`Error::Os`] with `ENOTSUP` if DPDK refuses the callback,
+/// e.g. the environment is not initialized or runs in legacy memory mode
+/// (where memory never changes).
+///
+/// # Example
+///
+/// ```no_run
+/// use spdk_io::env::{MemEvent, register_mem_event_cb};
+///
+/// # fn main() -> spdk_io::Result<()> {
+/// let _cb = register_mem_event_cb(|event| match event {
+///     MemEvent::Added { addr, len } => println!("+{} bytes at {:#x}", len, addr),
+///     MemEvent::Removed { addr, len } => println!("-{} bytes at {:#x}", len, addr),
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn register_mem_event_cb<F>(f: F) -> Result<MemEventCallback>
+where
+    F: FnMut(MemEvent) + Send + 'static,
+{
+    let id = NEXT_MEM_EVENT_CB.fetch_add(1, Ordering::Relaxed);
+    let name = CString::new(format!("spdk-io-mem-event-{}", id))?;
+    let ctx: *mut MemEventFn = Box::into_raw(Box::new(Mutex::new(Box::new(f))));
+
+    let rc =
+        unsafe { rte_mem_event_callback_register(name.as_ptr(), mem_event_cb, ctx as *mut c_void) };
+    if rc != 0 {
+        // SAFETY: not registered, so DPDK holds no reference
+        drop(unsafe { Box::from_raw(ctx) });
+        return Err(Error::Os(libc::ENOTSUP));
+    }
+    Ok(MemEventCallback { name, ctx })
+}
+
+unsafe extern "C" fn mem_event_cb(event: c_int, addr: *const c_void, len: usize, arg: *mut c_void) {
+    let f = unsafe { &*(arg as *const MemEventFn) };
+    let addr = addr as usize;
+    let event = if event == RTE_MEM_EVENT_ALLOC {
+        MemEvent::Added { addr, len }
+    } else {
+        MemEvent::Removed { addr, len }
+    };
+    (f.lock().unwrap_or_else(|e| e.into_inner()))(event);
+}
+
+/// glibc `cookie_io_functions_t` (not exposed by the `libc` crate).
+#[repr(C)]
+struct CookieIoFunctions {
+    read: Option<unsafe extern "C" fn()>,
+    write: Option<unsafe extern "C" fn(*mut c_void, *const c_char, usize) -> isize>,
+    seek: Option<unsafe extern "C" fn()>,
+    close: Option<unsafe extern "C" fn()>,
+}
+
+unsafe extern "C" {
+    fn fopencookie(
+        cookie: *mut c_void,
+        mode: *const c_char,
+        funcs: CookieIoFunctions,
+    ) -> *mut libc::FILE;
+}
+
+/// DPDK's `RTE_LOG_NOTICE`; lower values are more severe.
+const RTE_LOG_NOTICE: c_int = 6;
+
+/// DPDK messages at notice level or above from the last `build()`.
+static INIT_WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Captures DPDK log output while `spdk_env_init()` runs.
+///
+/// Redirects the DPDK log stream to a `fopencookie` stream that records
+/// messages at [`RTE_LOG_NOTICE`] or above and still echoes everything to
+/// stderr. [`finish`](Self::finish) restores DPDK's default stream.
+struct InitLogCapture {
+    stream: *mut libc::FILE,
+    captured: Box<Vec<u8>>,
+}
+
+impl InitLogCapture {
+    fn start() -> Option<Self> {
+        let mut captured = Box::new(Vec::new());
+        let funcs = CookieIoFunctions {
+            read: None,
+            write: Some(capture_write),
+            seek: None,
+            close: None,
+        };
+        let stream = unsafe {
+            fopencookie(
+                &mut *captured as *mut Vec<u8> as *mut c_void,
+                c"w".as_ptr(),
+                funcs,
+            )
+        };
+        if stream.is_null() {
+            return None;
+        }
+        if unsafe { rte_openlog_stream(stream) } != 0 {
+            unsafe { libc::fclose(stream) };
+            return None;
+        }
+        Some(Self { stream, captured })
+    }
+
+    /// Restore the default DPDK log stream and return the captured lines.
+    fn finish(self) -> Vec<String> {
+        unsafe {
+            rte_openlog_stream(std::ptr::null_mut());
+            // Flushes any remaining output into `captured`
+            libc::fclose(self.stream);
+        }
+
+        String::from_utf8_lossy(&self.captured)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// `fopencookie` write callback for [`InitLogCapture`].
+///
+/// DPDK flushes after every message, so the level of the message being
+/// logged is still current here.
+unsafe extern "C" fn capture_write(cookie: *mut c_void, buf: *const c_char, size: usize) -> isize {
+    let bytes = unsafe { std::slice::from_raw_parts(buf as *const u8, size) };
+    let _ = std::io::stderr().write_all(bytes);
+
+    if unsafe { rte_log_cur_msg_loglevel() } <= RTE_LOG_NOTICE {
+        // SAFETY: cookie is the Vec owned by the InitLogCapture
+        let captured = unsafe { &mut *(cookie as *mut Vec<u8>) };
+        captured.extend_from_slice(bytes);
+    }
+    size as isize
+}
+
+/// No environment, `build()` may be called.
+const ENV_UNINIT: u8 = 0;
+/// A `build()` call is running `spdk_env_init()`.
+const ENV_INITIALIZING: u8 = 1;
+/// The environment is initialized and owned by an [`SpdkEnv`].
+const ENV_READY: u8 = 2;
+/// The panic hook of [`install_cleanup_on_panic()`] finalized the
+/// environment; the [`SpdkEnv`] must not finalize it again.
+const ENV_FINALIZED_ON_PANIC: u8 = 3;
+
+/// Global state of the SPDK environment
+static ENV_STATE: AtomicU8 = AtomicU8::new(ENV_UNINIT);
+
+/// Exclusive right to initialize the environment.
+///
+/// Only one caller can move the state out of `ENV_UNINIT`, so concurrent
+/// `build()` calls cannot both reach `spdk_env_init()`. Dropping the claim
+/// without [`commit`](Self::commit) (error or panic) resets the state.
+struct InitClaim(());
+
+impl InitClaim {
+    fn acquire() -> Result<Self> {
+        ENV_STATE
+            .compare_exchange(
+                ENV_UNINIT,
+                ENV_INITIALIZING,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .map(|_| InitClaim(()))
+            .map_err(|_| Error::AlreadyInitialized)
+    }
+
+    fn commit(self) {
+        ENV_STATE.store(ENV_READY, Ordering::Release);
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for InitClaim {
+    fn drop(&mut self) {
+        ENV_STATE.store(ENV_UNINIT, Ordering::Release);
+    }
+}
 
 /// SPDK environment guard.
 ///
@@ -78,20 +462,213 @@ impl SpdkEnv {
         Self::builder().build()
     }
 
+    /// DPDK messages at notice level or above (warnings, errors) printed
+    /// during the last [`SpdkEnvBuilder::build()`], one entry per line.
+    ///
+    /// For example `EAL: No free 2048 kB hugepages reported on node 1`.
+    /// Also filled in when `build()` fails. The messages are still printed
+    /// to stderr as usual. Initialization through [`SpdkApp`](crate::SpdkApp)
+    /// is not captured.
+    pub fn init_warnings() -> Vec<String> {
+        INIT_WARNINGS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Memory DPDK actually reserved during initialization, in MiB.
+    ///
+    /// This can differ from [`mem_size_mb`](SpdkEnvBuilder::mem_size_mb):
+    /// DPDK rounds up to whole hugepages and, without a size, reserves
+    /// whatever hugepages are free. Returns `None` before initialization or
+    /// if DPDK reports no memory segments (possible with
+    /// [`no_huge`](SpdkEnvBuilder::no_huge)).
+    pub fn reserved_mem_mb() -> Option<i32> {
+        if !Self::is_initialized() {
+            return None;
+        }
+        let bytes = unsafe { rte_eal_get_physmem_size() };
+        if bytes == 0 {
+            return None;
+        }
+        Some(i32::try_from(bytes / (1024 * 1024)).unwrap_or(i32::MAX))
+    }
+
     /// Check if the SPDK environment is currently initialized.
+    ///
+    /// Returns `false` while another thread is still inside `build()`.
     pub fn is_initialized() -> bool {
-        ENV_INITIALIZED.load(Ordering::SeqCst)
+        ENV_STATE.load(Ordering::Acquire) == ENV_READY
+    }
+
+    /// Get the first lcore of the core mask (the main core).
+    pub fn first_core() -> u32 {
+        unsafe { spdk_env_get_first_core() }
+    }
+
+    /// Get the last lcore of the core mask.
+    pub fn last_core() -> u32 {
+        unsafe { spdk_env_get_last_core() }
+    }
+
+    /// Get the number of lcores in the core mask.
+    pub fn core_count() -> u32 {
+        unsafe { spdk_env_get_core_count() }
+    }
+
+    /// Get the lcore the calling thread runs on, or `u32::MAX` for threads
+    /// DPDK didn't create (other than the main thread).
+    pub fn current_core() -> u32 {
+        unsafe { spdk_env_get_current_core() }
+    }
+
+    /// Get the NUMA socket of lcore `core`.
+    ///
+    /// Use the result as the socket of NUMA-local allocations such as
+    /// [`DmaBuf::alloc_on_socket`](crate::DmaBuf::alloc_on_socket) instead
+    /// of [`NUMA_ID_ANY`](crate::dma::NUMA_ID_ANY). Returns `NUMA_ID_ANY` if
+    /// SPDK doesn't know the core.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotInitialized`] unless an [`SpdkEnv`] is
+    /// initialized. Inside [`SpdkApp`](crate::SpdkApp), which initializes
+    /// the environment itself, use [`core_to_socket()`] instead.
+    pub fn socket_id(core: u32) -> Result<i32> {
+        if !Self::is_initialized() {
+            return Err(Error::NotInitialized);
+        }
+        Ok(core_to_socket(core))
+    }
+
+    /// Get the NUMA socket of the calling thread's lcore.
+    ///
+    /// Returns [`NUMA_ID_ANY`](crate::dma::NUMA_ID_ANY) on threads DPDK
+    /// didn't create, which have no lcore.
+    ///
+    /// # Errors
+    ///
+    /// As for [`socket_id()`](Self::socket_id).
+    pub fn current_socket() -> Result<i32> {
+        match Self::current_core() {
+            u32::MAX if Self::is_initialized() => Ok(crate::dma::NUMA_ID_ANY),
+            core => Self::socket_id(core),
+        }
+    }
+
+    /// Iterate over the lcores of the core mask in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::SpdkEnv;
+    ///
+    /// let _env = SpdkEnv::builder().core_mask("0x3").build()?;
+    /// for core in SpdkEnv::cores() {
+    ///     println!("lcore {}", core);
+    /// }
+    /// # Ok::<(), spdk_io::Error>(())
+    /// ```
+    pub fn cores() -> CoreIterator {
+        CoreIterator { next: None }
+    }
+}
+
+/// Iterator over the lcores of the core mask.
+///
+/// Created by [`SpdkEnv::cores()`].
+pub struct CoreIterator {
+    /// Core to yield next; `None` before the first call
+    next: Option<u32>,
+}
+
+impl Iterator for CoreIterator {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let core = match self.next {
+            None => SpdkEnv::first_core(),
+            Some(core) => core,
+        };
+        if core == u32::MAX {
+            self.next = Some(u32::MAX);
+            return None;
+        }
+        self.next = Some(unsafe { spdk_env_get_next_core(core) });
+        Some(core)
     }
 }
 
 impl Drop for SpdkEnv {
     fn drop(&mut self) {
-        // Clean up SPDK environment
+        // Clean up SPDK environment, unless the panic hook already did
         // WARNING: After this, SPDK cannot be re-initialized in this process
+        if ENV_STATE.swap(ENV_UNINIT, Ordering::AcqRel) == ENV_READY {
+            unsafe {
+                spdk_env_fini();
+            }
+        }
+    }
+}
+
+/// Finalize the SPDK environment when any thread panics.
+///
+/// Installs a process-wide panic hook that first runs the previously
+/// installed hook (so the panic message is still printed) and then calls
+/// `spdk_env_fini()` if an [`SpdkEnv`] is initialized, so DPDK removes its
+/// hugepage files instead of leaving them to wedge later processes. The
+/// environment is finalized at most once; the [`SpdkEnv`] is still dropped
+/// normally but no longer finalizes it, and
+/// [`SpdkEnv::is_initialized()`] returns `false` afterwards. Calling this
+/// again has no effect.
+///
+/// Only environments created with [`SpdkEnvBuilder::build()`] are covered;
+/// [`SpdkApp`](crate::SpdkApp) tears down its own environment.
+///
+/// # Caveats
+///
+/// Cleanup is best effort and meant for processes that exit on panic.
+/// The hook runs on the panicking thread while other threads (and the
+/// panicking thread's own callers) may still be inside SPDK: they must not
+/// touch SPDK memory afterwards, and a panic raised while SPDK holds a lock
+/// (e.g. inside an SPDK callback) can deadlock in `spdk_env_fini()`.
+///
+/// # Example
+///
+/// ```no_run
+/// use spdk_io::{SpdkEnv, env};
+///
+/// let _env = SpdkEnv::builder().name("app").build()?;
+/// env::install_cleanup_on_panic();
+/// # Ok::<(), spdk_io::Error>(())
+/// ```
+pub fn install_cleanup_on_panic() {
+    static INSTALLED: std::sync::Once = std::sync::Once::new();
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            fini_after_panic();
+        }));
+    });
+}
+
+/// Panic hook body of [`install_cleanup_on_panic()`].
+fn fini_after_panic() {
+    // Claim the environment so neither SpdkEnv::drop nor a second panic
+    // finalizes it again
+    if ENV_STATE
+        .compare_exchange(
+            ENV_READY,
+            ENV_FINALIZED_ON_PANIC,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        )
+        .is_ok()
+    {
         unsafe {
             spdk_env_fini();
         }
-        ENV_INITIALIZED.store(false, Ordering::SeqCst);
     }
 }
 
@@ -105,7 +682,16 @@ pub struct SpdkEnvBuilder {
     no_huge: bool,
     hugepage_single_segments: bool,
     main_core: Option<i32>,
+    iova_mode: Option<String>,
+    pci_allowed: Vec<String>,
+    pci_blocked: Vec<String>,
     log_level: Option<LogLevel>,
+    no_telemetry: bool,
+    eal_log_level: Option<i32>,
+    eal_log_levels: Vec<(String, i32)>,
+    /// Arguments from [`eal_args`](Self::eal_args) and unrecognized ones
+    /// from [`from_args`](Self::from_args)
+    eal_args: Vec<String>,
 }
 
 impl SpdkEnvBuilder {
@@ -120,10 +706,76 @@ impl SpdkEnvBuilder {
             no_huge: false,
             hugepage_single_segments: false,
             main_core: None,
+            iova_mode: None,
+            pci_allowed: Vec::new(),
+            pci_blocked: Vec::new(),
             log_level: None,
+            no_telemetry: false,
+            eal_log_level: None,
+            eal_log_levels: Vec::new(),
+            eal_args: Vec::new(),
         }
     }
 
+    /// Create a builder from DPDK-style command line arguments.
+    ///
+    /// `argv` holds the arguments only, without a program name, e.g. what a
+    /// CLI tool received after `--`. Recognized flags set builder fields:
+    ///
+    /// | Flag | Builder method |
+    /// |------|----------------|
+    /// | `-c <mask>` | [`core_mask`](Self::core_mask) |
+    /// | `-m <mb>` | [`mem_size_mb`](Self::mem_size_mb) |
+    /// | `-i <id>` | [`shm_id`](Self::shm_id) |
+    /// | `--no-pci` | [`no_pci`](Self::no_pci) |
+    /// | `--no-huge` | [`no_huge`](Self::no_huge) |
+    ///
+    /// Short flags also accept the value attached (`-c0x3`). Everything
+    /// else is passed to DPDK unchanged through `env_context`, in order, so
+    /// a flag and its value stay together. Builder methods called afterwards
+    /// override the parsed values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a recognized flag lacks its value, a
+    /// number does not parse, or a passed-through argument contains
+    /// whitespace (`env_context` is split on whitespace).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::SpdkEnvBuilder;
+    ///
+    /// let args: Vec<String> = std::env::args().skip(1).collect();
+    /// let env = SpdkEnvBuilder::from_args(&args)?.name("my_tool").build()?;
+    /// # Ok::<(), spdk_io::Error>(())
+    /// ```
+    pub fn from_args(argv: &[String]) -> Result<Self> {
+        let mut builder = Self::new();
+        let mut args = argv.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--no-pci" {
+                builder.no_pci = true;
+            } else if arg == "--no-huge" {
+                builder.no_huge = true;
+            } else if let Some(mask) = short_flag(arg, "-c", &mut args)? {
+                builder.core_mask = Some(mask);
+            } else if let Some(mb) = short_flag(arg, "-m", &mut args)? {
+                builder.mem_size_mb = Some(parse_number(&mb, "-m")?);
+            } else if let Some(id) = short_flag(arg, "-i", &mut args)? {
+                builder.shm_id = Some(parse_number(&id, "-i")?);
+            } else if arg.chars().any(char::is_whitespace) {
+                return Err(Error::Config(format!(
+                    "EAL argument {:?} contains whitespace",
+                    arg
+                )));
+            } else {
+                builder.eal_args.push(arg.clone());
+            }
+        }
+        Ok(builder)
+    }
+
     /// Set the application name (used in hugepage file names).
     pub fn name(mut self, name: &str) -> Self {
         self.name = Some(name.to_string());
@@ -136,6 +788,15 @@ impl SpdkEnvBuilder {
         self
     }
 
+    /// Set the CPU cores from a [`CpuSet`] instead of a hex string.
+    ///
+    /// Equivalent to [`core_mask`](Self::core_mask) with
+    /// [`CpuSet::to_hex_string()`]; the later call wins.
+    pub fn core_mask_set(mut self, cores: CpuSet) -> Self {
+        self.core_mask = Some(cores.to_hex_string());
+        self
+    }
+
     /// Set the amount of hugepage memory to reserve in MB.
     pub fn mem_size_mb(mut self, mb: i32) -> Self {
         self.mem_size_mb = Some(mb);
@@ -179,6 +840,49 @@ impl SpdkEnvBuilder {
         self
     }
 
+    /// Force DPDK's IOVA mode: `"va"` (virtual addresses, needed in most
+    /// containers and with VFIO) or `"pa"` (physical addresses, e.g. with UIO;
+    /// needs hugepages and root).
+    ///
+    /// By default DPDK picks the mode from the bound drivers. Other values
+    /// make [`build()`](Self::build) fail with [`Error::EnvInit`].
+    pub fn iova_mode(mut self, mode: &str) -> Self {
+        self.iova_mode = Some(mode.to_string());
+        self
+    }
+
+    /// Only probe the PCI devices at these addresses (e.g. `"0000:5e:00.0"`).
+    ///
+    /// Addresses are `domain:bus:device.function` as accepted by
+    /// `spdk_pci_addr_parse()`; the domain may be omitted. DPDK takes either
+    /// an allow list or a block list, not both, so combining this with
+    /// [`pci_blocked`](Self::pci_blocked) makes [`build()`](Self::build) fail
+    /// with [`Error::EnvInit`], as does an address that doesn't parse.
+    /// Can be called repeatedly.
+    pub fn pci_allowed<I, S>(mut self, addrs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.pci_allowed
+            .extend(addrs.into_iter().map(|addr| addr.as_ref().to_string()));
+        self
+    }
+
+    /// Never probe the PCI devices at these addresses.
+    ///
+    /// The counterpart of [`pci_allowed`](Self::pci_allowed), with the same
+    /// address format; the two are mutually exclusive.
+    pub fn pci_blocked<I, S>(mut self, addrs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.pci_blocked
+            .extend(addrs.into_iter().map(|addr| addr.as_ref().to_string()));
+        self
+    }
+
     /// Set the log level for SPDK messages printed to stderr.
     ///
     /// Use [`LogLevel::Debug`] for verbose output during development.
@@ -188,24 +892,129 @@ impl SpdkEnvBuilder {
         self
     }
 
+    /// Disable the DPDK telemetry socket.
+    ///
+    /// DPDK creates a telemetry socket under `/var/run/dpdk` by default,
+    /// which can fail or leave clutter in containers. Passed to DPDK as
+    /// `--no-telemetry` via `env_context`. Default is `false`.
+    pub fn no_telemetry(mut self, no_telemetry: bool) -> Self {
+        self.no_telemetry = no_telemetry;
+        self
+    }
+
+    /// Set the default DPDK EAL log level (1 = critical ... 8 = debug).
+    ///
+    /// DPDK's own logging (e.g. PCI probing) is separate from SPDK's and is
+    /// not affected by [`log_level`](Self::log_level). Passed to DPDK as
+    /// `--log-level=<level>` via `env_context`.
+    pub fn eal_log_level(mut self, level: i32) -> Self {
+        self.eal_log_level = Some(level);
+        self
+    }
+
+    /// Set the DPDK log level for components matching `component`.
+    ///
+    /// `component` is a DPDK log type glob such as `lib.eal` or `pmd.net.*`,
+    /// without whitespace. Passed as `--log-level=<component>:<level>` after
+    /// the default from [`eal_log_level`](Self::eal_log_level), so it takes
+    /// precedence. Can be called repeatedly.
+    pub fn eal_log_level_for(mut self, component: &str, level: i32) -> Self {
+        self.eal_log_levels.push((component.to_string(), level));
+        self
+    }
+
+    /// Pass raw DPDK EAL arguments, e.g. `["--file-prefix=spdk0",
+    /// "--vdev=net_null0"]`.
+    ///
+    /// SPDK hands `spdk_env_opts.env_context` to DPDK split on whitespace,
+    /// so each argument is one EAL word; one containing whitespace makes
+    /// [`build()`](Self::build) fail with [`Error::Config`]. They are
+    /// appended after the arguments generated by the other builder methods,
+    /// in order. Can be called repeatedly.
+    ///
+    /// Subsystem JSON configuration is not an EAL concern; pass it to
+    /// `SpdkAppBuilder::config_file` instead.
+    pub fn eal_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.eal_args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_string()));
+        self
+    }
+
+    /// Extra DPDK EAL arguments passed through `spdk_env_opts.env_context`.
+    fn env_context(&self) -> Option<String> {
+        let mut args = Vec::new();
+        if self.no_telemetry {
+            args.push("--no-telemetry".to_string());
+        }
+        if let Some(level) = self.eal_log_level {
+            args.push(format!("--log-level={}", level));
+        }
+        for (component, level) in &self.eal_log_levels {
+            args.push(format!("--log-level={}:{}", component, level));
+        }
+        args.extend(self.eal_args.iter().cloned());
+
+        if args.is_empty() {
+            None
+        } else {
+            Some(args.join(" "))
+        }
+    }
+
     /// Initialize the SPDK environment with the configured options.
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - SPDK is already initialized
+    /// - SPDK is already initialized, or another thread is initializing it
     /// - Hugepage allocation fails
     /// - PCI access fails
     /// - Other DPDK/SPDK initialization failures
+    ///
+    /// On failure [`SpdkEnv::is_initialized()`] stays `false`. If DPDK came
+    /// up and SPDK's own setup failed afterwards, `spdk_env_fini()` is called
+    /// to release what was set up. DPDK cannot be initialized twice, so a
+    /// retry in the same process may still fail.
     pub fn build(self) -> Result<SpdkEnv> {
-        // Check if already initialized
-        if ENV_INITIALIZED.swap(true, Ordering::SeqCst) {
-            return Err(Error::AlreadyInitialized);
-        }
-
         // Convert strings to CStrings
         let name_cstr = self.name.as_deref().map(CString::new).transpose()?;
         let core_mask_cstr = self.core_mask.as_deref().map(CString::new).transpose()?;
+        if let Some(arg) = self
+            .eal_args
+            .iter()
+            .find(|arg| arg.chars().any(char::is_whitespace))
+        {
+            return Err(Error::Config(format!(
+                "EAL argument {:?} contains whitespace",
+                arg
+            )));
+        }
+        // Must outlive spdk_env_init(), which parses it
+        let env_context_cstr = self.env_context().map(CString::new).transpose()?;
+        if let Some(mode) = self.iova_mode.as_deref()
+            && !matches!(mode, "va" | "pa")
+        {
+            return Err(Error::EnvInit(format!(
+                "invalid IOVA mode {:?}, expected \"va\" or \"pa\"",
+                mode
+            )));
+        }
+        let iova_mode_cstr = self.iova_mode.as_deref().map(CString::new).transpose()?;
+        // spdk_env_opts has one count shared by both lists
+        if !self.pci_allowed.is_empty() && !self.pci_blocked.is_empty() {
+            return Err(Error::EnvInit(
+                "pci_allowed and pci_blocked are mutually exclusive".to_string(),
+            ));
+        }
+        let mut pci_allowed = parse_pci_addrs(&self.pci_allowed)?;
+        let mut pci_blocked = parse_pci_addrs(&self.pci_blocked)?;
+
+        // Exactly one concurrent caller gets past this point
+        let claim = InitClaim::acquire()?;
 
         unsafe {
             // Initialize opts with defaults
@@ -231,19 +1040,36 @@ impl SpdkEnvBuilder {
             if let Some(main_core) = self.main_core {
                 opts.main_core = main_core;
             }
+            if let Some(ref mode) = iova_mode_cstr {
+                opts.iova_mode = mode.as_ptr();
+            }
+            if !pci_allowed.is_empty() {
+                opts.pci_allowed = pci_allowed.as_mut_ptr();
+                opts.num_pci_addr = pci_allowed.len();
+            }
+            if !pci_blocked.is_empty() {
+                opts.pci_blocked = pci_blocked.as_mut_ptr();
+                opts.num_pci_addr = pci_blocked.len();
+            }
             opts.no_pci = self.no_pci;
             opts.no_huge = self.no_huge;
             opts.hugepage_single_segments = self.hugepage_single_segments;
+            if let Some(ref env_context) = env_context_cstr {
+                opts.env_context = env_context.as_ptr() as *mut c_void;
+            }
 
             // Set log level before init if requested
             if let Some(level) = self.log_level {
                 spdk_log_set_print_level(level as i32);
             }
 
-            // Initialize SPDK environment
+            // Initialize SPDK environment, recording DPDK's diagnostics
+            let capture = InitLogCapture::start();
             let rc = spdk_env_init(&opts);
+            *INIT_WARNINGS.lock().unwrap_or_else(|e| e.into_inner()) =
+                capture.map(InitLogCapture::finish).unwrap_or_default();
             if rc != 0 {
-                ENV_INITIALIZED.store(false, Ordering::SeqCst);
+                unwind_failed_init();
                 return Err(Error::EnvInit(format!(
                     "spdk_env_init failed with error code {}",
                     rc
@@ -251,12 +1077,287 @@ impl SpdkEnvBuilder {
             }
         }
 
+        claim.commit();
         Ok(SpdkEnv { _private: () })
     }
 }
 
+/// Parse PCI addresses for `spdk_env_opts.pci_allowed`/`pci_blocked`.
+fn parse_pci_addrs(addrs: &[String]) -> Result<Vec<spdk_pci_addr>> {
+    addrs
+        .iter()
+        .map(|addr| {
+            let invalid = || Error::EnvInit(format!("invalid PCI address {:?}", addr));
+            let cstr = CString::new(addr.as_str()).map_err(|_| invalid())?;
+            let mut parsed: spdk_pci_addr = unsafe { std::mem::zeroed() };
+            let rc = unsafe { spdk_pci_addr_parse(&mut parsed, cstr.as_ptr()) };
+            if rc != 0 {
+                return Err(invalid());
+            }
+            Ok(parsed)
+        })
+        .collect()
+}
+
+/// Release what a failed `spdk_env_init()` left behind.
+///
+/// `spdk_env_init()` fails either inside `rte_eal_init()`, which unwinds
+/// itself, or afterwards in SPDK's post-init (PCI, memory maps), which
+/// leaves DPDK running. DPDK only reports memory once its memory subsystem
+/// is up (the size reads a static early config before that), so a non-zero
+/// size identifies the second case.
+fn unwind_failed_init() {
+    unsafe {
+        if rte_eal_get_physmem_size() > 0 {
+            spdk_env_fini();
+        }
+    }
+}
+
+/// Value of the short flag `flag` if `arg` is it, either attached
+/// (`-c0x3`) or taken from the next argument (`-c 0x3`).
+fn short_flag(
+    arg: &str,
+    flag: &str,
+    rest: &mut std::slice::Iter<'_, String>,
+) -> Result<Option<String>> {
+    let Some(attached) = arg.strip_prefix(flag) else {
+        return Ok(None);
+    };
+    if !attached.is_empty() {
+        return Ok(Some(attached.to_string()));
+    }
+    rest.next()
+        .cloned()
+        .map(Some)
+        .ok_or_else(|| Error::Config(format!("{} requires a value", flag)))
+}
+
+fn parse_number(value: &str, flag: &str) -> Result<i32> {
+    value
+        .parse()
+        .map_err(|_| Error::Config(format!("{} expects a number, got {:?}", flag, value)))
+}
+
 impl Default for SpdkEnvBuilder {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_level_ordering() {
+        assert!(LogLevel::Error < LogLevel::Debug);
+        assert!(LogLevel::Disabled < LogLevel::Error);
+        assert!(LogLevel::Notice > LogLevel::Warn);
+
+        // Ordering matches SPDK's numeric values
+        let levels = [
+            LogLevel::Disabled,
+            LogLevel::Error,
+            LogLevel::Warn,
+            LogLevel::Notice,
+            LogLevel::Info,
+            LogLevel::Debug,
+        ];
+        assert!(levels.windows(2).all(|w| w[0] < w[1]));
+        assert!(levels.windows(2).all(|w| (w[0] as i32) < (w[1] as i32)));
+
+        let max = LogLevel::Notice;
+        let passed: Vec<_> = levels.iter().filter(|&&level| level <= max).collect();
+        assert_eq!(
+            passed,
+            [
+                &LogLevel::Disabled,
+                &LogLevel::Error,
+                &LogLevel::Warn,
+                &LogLevel::Notice
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spdk_build_version() {
+        let version = spdk_build_version();
+        assert!(!version.is_empty());
+        assert!(version.chars().any(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_no_telemetry_env_context() {
+        assert_eq!(SpdkEnvBuilder::new().env_context(), None);
+
+        let builder = SpdkEnvBuilder::new().no_telemetry(true);
+        assert_eq!(builder.env_context().as_deref(), Some("--no-telemetry"));
+
+        let builder = builder.no_telemetry(false);
+        assert_eq!(builder.env_context(), None);
+    }
+
+    #[test]
+    fn test_eal_log_level_env_context() {
+        let builder = SpdkEnvBuilder::new().eal_log_level(4);
+        assert_eq!(builder.env_context().as_deref(), Some("--log-level=4"));
+
+        let builder = SpdkEnvBuilder::new()
+            .no_telemetry(true)
+            .eal_log_level_for("lib.eal", 8)
+            .eal_log_level(4)
+            .eal_log_level_for("pmd.net.*", 1);
+        assert_eq!(
+            builder.env_context().as_deref(),
+            Some("--no-telemetry --log-level=4 --log-level=lib.eal:8 --log-level=pmd.net.*:1")
+        );
+    }
+
+    fn args(argv: &[&str]) -> Vec<String> {
+        argv.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args() {
+        let builder = SpdkEnvBuilder::from_args(&args(&[
+            "-c",
+            "0x3",
+            "-m512",
+            "--no-pci",
+            "--file-prefix",
+            "spdk_tool",
+            "-i",
+            "7",
+            "--no-huge",
+            "--iova-mode=va",
+        ]))
+        .unwrap();
+        assert_eq!(builder.core_mask.as_deref(), Some("0x3"));
+        assert_eq!(builder.mem_size_mb, Some(512));
+        assert_eq!(builder.shm_id, Some(7));
+        assert!(builder.no_pci);
+        assert!(builder.no_huge);
+        assert_eq!(
+            builder.env_context().as_deref(),
+            Some("--file-prefix spdk_tool --iova-mode=va")
+        );
+
+        // Later builder calls win
+        let builder = builder.mem_size_mb(256).no_telemetry(true);
+        assert_eq!(builder.mem_size_mb, Some(256));
+        assert_eq!(
+            builder.env_context().as_deref(),
+            Some("--no-telemetry --file-prefix spdk_tool --iova-mode=va")
+        );
+
+        let builder = SpdkEnvBuilder::from_args(&[]).unwrap();
+        assert_eq!(builder.core_mask, None);
+        assert_eq!(builder.env_context(), None);
+    }
+
+    #[test]
+    fn test_eal_args_env_context() {
+        let builder = SpdkEnvBuilder::new()
+            .no_telemetry(true)
+            .eal_args(["--file-prefix=spdk0"])
+            .eal_args(vec![String::from("--vdev=net_null0")]);
+        assert_eq!(
+            builder.env_context().as_deref(),
+            Some("--no-telemetry --file-prefix=spdk0 --vdev=net_null0")
+        );
+
+        // Rejected before SPDK is touched
+        let err = SpdkEnvBuilder::new().eal_args(["--vdev=a b"]).build().err();
+        assert!(matches!(err, Some(Error::Config(_))));
+        assert!(!SpdkEnv::is_initialized());
+    }
+
+    #[test]
+    fn test_core_mask_set() {
+        let builder = SpdkEnvBuilder::new().core_mask_set(CpuSet::from_cores([0, 1]));
+        assert_eq!(builder.core_mask.as_deref(), Some("0x3"));
+
+        let builder = builder.core_mask("0x1");
+        assert_eq!(builder.core_mask.as_deref(), Some("0x1"));
+    }
+
+    #[test]
+    fn test_socket_id_requires_init() {
+        assert!(matches!(SpdkEnv::socket_id(0), Err(Error::NotInitialized)));
+        assert!(matches!(
+            SpdkEnv::current_socket(),
+            Err(Error::NotInitialized)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_iova_mode() {
+        // Rejected before SPDK is touched
+        let err = SpdkEnvBuilder::new().iova_mode("vaa").build().err();
+        assert!(matches!(err, Some(Error::EnvInit(_))));
+        assert!(!SpdkEnv::is_initialized());
+    }
+
+    #[test]
+    fn test_invalid_pci_lists() {
+        let err = SpdkEnvBuilder::new()
+            .pci_allowed(["0000:5e:00.0"])
+            .pci_blocked(["0000:5f:00.0"])
+            .build()
+            .err();
+        assert!(matches!(err, Some(Error::EnvInit(msg)) if msg.contains("mutually exclusive")));
+
+        let err = SpdkEnvBuilder::new()
+            .pci_allowed(["0000:5e:00.0", "not-an-address"])
+            .build()
+            .err();
+        assert!(matches!(err, Some(Error::EnvInit(msg)) if msg.contains("not-an-address")));
+        assert!(!SpdkEnv::is_initialized());
+    }
+
+    #[test]
+    fn test_from_args_errors() {
+        for argv in [&["-c"][..], &["-m", "lots"], &["-i0x1"], &["--vdev", "a b"]] {
+            assert!(
+                matches!(
+                    SpdkEnvBuilder::from_args(&args(argv)),
+                    Err(Error::Config(_))
+                ),
+                "{:?} was accepted",
+                argv
+            );
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn current_affinity() -> Vec<usize> {
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        let rc =
+            unsafe { libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) };
+        assert_eq!(rc, 0);
+        (0..libc::CPU_SETSIZE as usize)
+            .filter(|&core| unsafe { libc::CPU_ISSET(core, &set) })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_pin_current_thread() {
+        // Run on a fresh thread so the test harness thread keeps its affinity
+        std::thread::spawn(|| {
+            pin_current_thread(&[0]).expect("failed to pin to core 0");
+            assert_eq!(current_affinity(), [0]);
+
+            unpin_current_thread().expect("failed to unpin");
+            assert!(current_affinity().contains(&0));
+
+            assert!(matches!(
+                pin_current_thread(&[]),
+                Err(Error::InvalidArgument(_))
+            ));
+        })
+        .join()
+        .unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 //! Error types for spdk-io
 
-use std::ffi::NulError;
+use std::ffi::{CStr, NulError, c_char};
+use std::str::Utf8Error;
 
 /// Result type for spdk-io operations
 pub type Result<T> = std::result::Result<T, Error>;
@@ -24,10 +25,18 @@ pub enum Error {
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
+    /// Invalid configuration or request parameters
+    #[error("Configuration error: {0}")]
+    Config(String),
+
     /// String contains null byte
     #[error("String contains null byte")]
     NulError(#[from] NulError),
 
+    /// String returned by SPDK is not valid UTF-8
+    #[error("Invalid UTF-8 in string: {0}")]
+    Utf8(#[from] Utf8Error),
+
     /// I/O operation failed
     #[error("I/O operation failed")]
     IoError,
@@ -36,6 +45,10 @@ pub enum Error {
     #[error("Device not found: {0}")]
     DeviceNotFound(String),
 
+    /// Device is already claimed or in use
+    #[error("Device busy: {0}")]
+    Busy(String),
+
     /// Channel allocation failed
     #[error("I/O channel allocation failed")]
     ChannelAlloc,
@@ -56,10 +69,35 @@ pub enum Error {
     #[error("Thread panicked")]
     ThreadPanic,
 
+    /// An SPDK thread is already attached to this OS thread
+    #[error("An SPDK thread is already attached to this OS thread")]
+    AlreadyAttached,
+
+    /// `poll()` was called from inside another `poll()` on the same OS thread
+    #[error("SPDK thread polled reentrantly")]
+    ReentrantPoll,
+
+    /// The target SPDK thread has exited (or is not tracked by this crate)
+    #[error("SPDK thread has exited")]
+    ThreadExited,
+
     /// OS error with errno
     #[error("OS error: {0}")]
     Os(i32),
 
+    /// Socket or file I/O failed outside SPDK (e.g. the RPC client)
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON-RPC error response from an SPDK application
+    #[error("RPC error {code}: {message}")]
+    Rpc {
+        /// JSON-RPC error code
+        code: i64,
+        /// Error message from SPDK
+        message: String,
+    },
+
     /// NVMe command error
     #[error("NVMe error: SCT={sct}, SC={sc}")]
     NvmeError {
@@ -97,3 +135,36 @@ impl Error {
         }
     }
 }
+
+/// Convert a C string returned by SPDK to `&str`, failing on invalid UTF-8.
+///
+/// A null pointer yields an empty string.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a nul-terminated string valid for `'a`.
+pub(crate) unsafe fn c_str_checked<'a>(ptr: *const c_char) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Ok("");
+    }
+    Ok(unsafe { CStr::from_ptr(ptr) }.to_str()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c_str_checked() {
+        let valid = c"worker";
+        assert_eq!(unsafe { c_str_checked(valid.as_ptr()) }.unwrap(), "worker");
+
+        assert_eq!(unsafe { c_str_checked(std::ptr::null()) }.unwrap(), "");
+
+        let invalid = CStr::from_bytes_with_nul(b"bad\xff\xfename\0").unwrap();
+        assert!(matches!(
+            unsafe { c_str_checked(invalid.as_ptr()) },
+            Err(Error::Utf8(_))
+        ));
+    }
+}
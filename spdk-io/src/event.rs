@@ -37,6 +37,8 @@ use std::ffi::c_void;
 use spdk_io_sys::*;
 
 use crate::complete::{CompletionReceiver, completion};
+pub use crate::env::CoreIterator;
+use crate::env::SpdkEnv;
 use crate::error::{Error, Result};
 
 // =============================================================================
@@ -54,24 +56,24 @@ impl Cores {
     ///
     /// Returns the logical core ID of the calling thread.
     pub fn current() -> u32 {
-        unsafe { spdk_env_get_current_core() }
+        SpdkEnv::current_core()
     }
 
     /// Get the first reactor lcore.
     ///
     /// This is the main reactor core (typically the one running the main callback).
     pub fn first() -> u32 {
-        unsafe { spdk_env_get_first_core() }
+        SpdkEnv::first_core()
     }
 
     /// Get the last reactor lcore.
     pub fn last() -> u32 {
-        unsafe { spdk_env_get_last_core() }
+        SpdkEnv::last_core()
     }
 
     /// Get the total number of reactor cores.
     pub fn count() -> u32 {
-        unsafe { spdk_env_get_core_count() }
+        SpdkEnv::core_count()
     }
 
     /// Iterate over all reactor lcores.
@@ -84,36 +86,7 @@ impl Cores {
     /// }
     /// ```
     pub fn iter() -> CoreIterator {
-        CoreIterator {
-            current: u32::MAX, // Signal to start from first
-        }
-    }
-}
-
-/// Iterator over reactor lcores.
-///
-/// Created by [`Cores::iter()`].
-pub struct CoreIterator {
-    current: u32,
-}
-
-impl Iterator for CoreIterator {
-    type Item = u32;
-
-    fn next(&mut self) -> Option<u32> {
-        if self.current == u32::MAX {
-            // First iteration
-            self.current = Cores::first();
-            Some(self.current)
-        } else {
-            let next = unsafe { spdk_env_get_next_core(self.current) };
-            if next == u32::MAX {
-                None
-            } else {
-                self.current = next;
-                Some(self.current)
-            }
-        }
+        SpdkEnv::cores()
     }
 }
 
@@ -21,40 +21,75 @@
 //!
 //! # Modules
 //!
+//! - [`accel`] - Copy offload and encryption via the acceleration framework
 //! - [`app`] - SPDK Application Framework (recommended for most apps)
 //! - [`bdev`] - Block device API
 //! - [`complete`] - Callback-to-future utilities
+//! - [`cpuset`] - Typed CPU core sets
 //! - [`dma`] - DMA-capable buffer allocation
 //! - [`env`] - Low-level environment initialization  
 //! - [`event`] - Event dispatching to specific reactor lcores
-//! - [`poller`] - SPDK poller integration for async executors
+//! - [`mempool`] - Fixed-size object pools
+//! - [`poller`] - SPDK poller integration for async executors and registered pollers
+//! - [`reactor`] - Polling loop for daemon-style programs
+//! - [`rpc`] - JSON-RPC client for a running SPDK application
 //! - [`thread`] - SPDK thread management
 //! - [`channel`] - I/O channel management
 //! - [`error`] - Error types
 //! - [`nvme`] - Direct NVMe driver access
 //! - [`nvmf`] - NVMe-oF target for in-process testing
+//!
+//! # Features
+//!
+//! `accel`, `app`/`event`, `bdev`, `nvme`, `nvmf` and `rpc` are only built
+//! with the cargo feature of the same name (`event` for [`app`]), which also
+//! selects the SPDK libraries linked by `spdk-io-sys`. The default `full`
+//! feature enables everything.
 
+#[cfg(feature = "accel")]
+pub mod accel;
+#[cfg(feature = "event")]
 pub mod app;
+#[cfg(feature = "bdev")]
 pub mod bdev;
 pub mod channel;
 pub mod complete;
+pub mod cpuset;
 pub mod dma;
 pub mod env;
 pub mod error;
+#[cfg(feature = "event")]
 pub mod event;
+pub mod mempool;
+#[cfg(feature = "nvme")]
 pub mod nvme;
+#[cfg(feature = "nvmf")]
 pub mod nvmf;
 pub mod poller;
+pub mod reactor;
+#[cfg(feature = "rpc")]
+pub mod rpc;
 pub mod thread;
 
 // Re-exports
+#[cfg(feature = "event")]
 pub use app::{SpdkApp, SpdkAppBuilder};
-pub use bdev::{Bdev, BdevDesc};
+#[cfg(feature = "bdev")]
+pub use bdev::{
+    Bdev, BdevDesc, BdevModule, BdevUuid, ClaimGuard, NullBdev, ReadHint, SharedBdevDesc,
+    WriteFlags, ZcopyHandle,
+};
 pub use channel::IoChannel;
-pub use complete::{CompletionReceiver, CompletionSender, block_on, completion, io_completion};
-pub use dma::DmaBuf;
-pub use env::{LogLevel, SpdkEnv, SpdkEnvBuilder};
+pub use complete::{
+    Completion, CompletionReceiver, CompletionSender, block_on, completion, io_completion,
+};
+pub use cpuset::CpuSet;
+pub use dma::{BufferPool, DmaBuf};
+pub use env::{CoreIterator, LogLevel, SpdkEnv, SpdkEnvBuilder};
 pub use error::{Error, Result};
-pub use event::{CoreIterator, Cores, SpdkEvent};
-pub use poller::{spdk_poller, spdk_poller_limited};
-pub use thread::{CurrentThread, JoinHandle, SpdkThread, ThreadHandle};
+#[cfg(feature = "event")]
+pub use event::{Cores, SpdkEvent};
+pub use mempool::{Mempool, MempoolBox, TypedMempool};
+pub use poller::{PollerId, spdk_poller, spdk_poller_limited};
+pub use reactor::Reactor;
+pub use thread::{CurrentThread, JoinHandle, Messenger, SpdkThread, ThreadHandle};
@@ -0,0 +1,241 @@
+//! Fixed-size object pools backed by `spdk_mempool`.
+//!
+//! [`Mempool`] is a thin wrapper over the raw element pool. [`TypedMempool`]
+//! builds a safe object pool on top of it: elements are sized for `T`, and
+//! [`get()`](TypedMempool::get) returns a [`MempoolBox`] guard that drops the
+//! value and returns the slot to the pool when it goes out of scope.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use spdk_io::mempool::TypedMempool;
+//!
+//! #[derive(Default)]
+//! struct Request {
+//!     lba: u64,
+//!     blocks: u32,
+//! }
+//!
+//! let pool = TypedMempool::<Request>::new("requests", 1024)?;
+//! {
+//!     let mut req = pool.get().expect("pool exhausted");
+//!     req.lba = 42;
+//! } // returned to the pool here
+//! # Ok::<(), spdk_io::Error>(())
+//! ```
+
+use std::ffi::{CString, c_void};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+use spdk_io_sys::*;
+
+use crate::dma::NUMA_ID_ANY;
+use crate::error::{Error, Result};
+
+/// Alignment of mempool elements (DPDK aligns objects to a cache line).
+const ELEMENT_ALIGN: usize = 64;
+
+/// Pool of fixed-size, untyped elements (`spdk_mempool`).
+///
+/// # Thread Safety
+///
+/// `Send + Sync` - `spdk_mempool` get/put are multi-producer/multi-consumer
+/// safe.
+///
+/// # Drop
+///
+/// Frees the pool. All elements should have been returned by then.
+pub struct Mempool {
+    ptr: NonNull<spdk_mempool>,
+}
+
+// SAFETY: spdk_mempool operations are thread-safe
+unsafe impl Send for Mempool {}
+unsafe impl Sync for Mempool {}
+
+impl Mempool {
+    /// Create a pool of `count` elements of `elt_size` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Pool name, must be unique within the process
+    /// * `count` - Number of elements
+    /// * `elt_size` - Size of each element in bytes
+    /// * `cache_size` - Per-core cache size (0 to disable)
+    /// * `socket_id` - NUMA socket ([`NUMA_ID_ANY`] for any)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MemoryAlloc`] if the pool could not be created.
+    pub fn new(
+        name: &str,
+        count: usize,
+        elt_size: usize,
+        cache_size: usize,
+        socket_id: i32,
+    ) -> Result<Self> {
+        let name_cstr = CString::new(name)?;
+
+        let ptr = unsafe {
+            spdk_mempool_create(name_cstr.as_ptr(), count, elt_size, cache_size, socket_id)
+        };
+
+        NonNull::new(ptr)
+            .map(|ptr| Self { ptr })
+            .ok_or(Error::MemoryAlloc)
+    }
+
+    /// Take an element from the pool.
+    ///
+    /// Returns `None` if the pool is exhausted.
+    pub fn get(&self) -> Option<NonNull<c_void>> {
+        NonNull::new(unsafe { spdk_mempool_get(self.ptr.as_ptr()) })
+    }
+
+    /// Return an element to the pool.
+    ///
+    /// # Safety
+    ///
+    /// `elt` must have been obtained from [`get()`](Self::get) on this pool
+    /// and must not be used afterwards.
+    pub unsafe fn put(&self, elt: NonNull<c_void>) {
+        unsafe { spdk_mempool_put(self.ptr.as_ptr(), elt.as_ptr()) };
+    }
+
+    /// Number of elements currently available.
+    ///
+    /// Elements held in per-core caches are not counted.
+    pub fn count(&self) -> usize {
+        unsafe { spdk_mempool_count(self.ptr.as_ptr()) }
+    }
+
+    /// Get the raw pointer.
+    pub fn as_ptr(&self) -> *mut spdk_mempool {
+        self.ptr.as_ptr()
+    }
+}
+
+impl Drop for Mempool {
+    fn drop(&mut self) {
+        unsafe { spdk_mempool_free(self.ptr.as_ptr()) };
+    }
+}
+
+/// Object pool of `T` values backed by a [`Mempool`].
+///
+/// Each element is `size_of::<T>()` bytes rounded up to `align_of::<T>()`.
+/// Values are constructed when taken and dropped when returned, so no `T`
+/// outlives its [`MempoolBox`].
+pub struct TypedMempool<T> {
+    pool: Mempool,
+    _marker: PhantomData<T>,
+}
+
+impl<T> TypedMempool<T> {
+    /// Create a pool of `count` elements on any NUMA socket, without a
+    /// per-core cache.
+    pub fn new(name: &str, count: usize) -> Result<Self> {
+        Self::with_options(name, count, 0, NUMA_ID_ANY)
+    }
+
+    /// Create a pool with an explicit per-core cache size and NUMA socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `T` needs more than cache-line
+    /// alignment, or [`Error::MemoryAlloc`] if the pool could not be created.
+    pub fn with_options(
+        name: &str,
+        count: usize,
+        cache_size: usize,
+        socket_id: i32,
+    ) -> Result<Self> {
+        let align = std::mem::align_of::<T>();
+        if align > ELEMENT_ALIGN {
+            return Err(Error::InvalidArgument(format!(
+                "mempool elements are {}-byte aligned, but the type needs {}",
+                ELEMENT_ALIGN, align
+            )));
+        }
+        let elt_size = std::mem::size_of::<T>().max(1).next_multiple_of(align);
+
+        Ok(Self {
+            pool: Mempool::new(name, count, elt_size, cache_size, socket_id)?,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Take a slot and initialize it with `T::default()`.
+    ///
+    /// Returns `None` if the pool is exhausted.
+    pub fn get(&self) -> Option<MempoolBox<'_, T>>
+    where
+        T: Default,
+    {
+        self.get_init(T::default)
+    }
+
+    /// Take a slot and initialize it with the value returned by `f`.
+    ///
+    /// `f` is only called if a slot is available. Returns `None` if the pool
+    /// is exhausted.
+    pub fn get_init<F>(&self, f: F) -> Option<MempoolBox<'_, T>>
+    where
+        F: FnOnce() -> T,
+    {
+        let ptr = self.pool.get()?.cast::<T>();
+        unsafe { ptr.as_ptr().write(f()) };
+
+        Some(MempoolBox { ptr, pool: self })
+    }
+
+    /// Number of slots currently available.
+    pub fn available(&self) -> usize {
+        self.pool.count()
+    }
+
+    /// Get the underlying untyped pool.
+    pub fn as_mempool(&self) -> &Mempool {
+        &self.pool
+    }
+}
+
+/// A `T` borrowed from a [`TypedMempool`].
+///
+/// Dereferences to `T`. On drop the value is dropped in place and the slot
+/// is returned to the pool.
+pub struct MempoolBox<'a, T> {
+    ptr: NonNull<T>,
+    pool: &'a TypedMempool<T>,
+}
+
+impl<T> Deref for MempoolBox<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for MempoolBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T> Drop for MempoolBox<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            std::ptr::drop_in_place(self.ptr.as_ptr());
+            self.pool.pool.put(self.ptr.cast());
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for MempoolBox<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&**self, f)
+    }
+}
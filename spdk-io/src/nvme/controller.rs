@@ -2,6 +2,7 @@
 //!
 //! Controller management and connection.
 
+use std::cell::Cell;
 use std::ffi::c_void;
 use std::mem::MaybeUninit;
 use std::ptr::NonNull;
@@ -11,11 +12,55 @@ use spdk_io_sys::*;
 use crate::error::{Error, Result};
 use crate::thread::SpdkThread;
 
+use super::discovery::{self, DiscoveryLogEntry};
 use super::namespace::NvmeNamespace;
 use super::opts::{NvmeCtrlrOpts, NvmeQpairOpts};
 use super::qpair::NvmeQpair;
 use super::transport::TransportId;
 
+/// Controller event delivered to a handler registered with
+/// [`NvmeController::on_event()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NvmeEvent {
+    /// Asynchronous Event Request completion from the controller.
+    ///
+    /// `cdw0` holds the event type, info and associated log page.
+    Aer {
+        /// Completion dword 0
+        cdw0: u32,
+    },
+    /// The controller failed, e.g. because the keep-alive timed out or the
+    /// fabric connection dropped. Use [`NvmeController::reconnect()`] to
+    /// re-establish it.
+    ConnectionLost,
+}
+
+/// Secure Erase Setting of a [`NvmeController::format()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecureErase {
+    /// No secure erase; the data may remain readable after formatting.
+    #[default]
+    None,
+    /// Overwrite all user data.
+    UserData,
+    /// Erase the encryption key, making all user data unreadable.
+    Crypto,
+}
+
+impl SecureErase {
+    /// SES field value (bits 11:9 of Format NVM command dword 10).
+    fn ses(self) -> u32 {
+        match self {
+            SecureErase::None => 0,
+            SecureErase::UserData => 1,
+            SecureErase::Crypto => 2,
+        }
+    }
+}
+
+/// Boxed event handler, kept at a stable address for the AER callback.
+type EventHandler = Box<dyn FnMut(NvmeEvent) + Send>;
+
 /// NVMe controller handle.
 ///
 /// Represents a connected NVMe controller. Obtained via [`connect()`](Self::connect).
@@ -69,6 +114,10 @@ use super::transport::TransportId;
 /// ```
 pub struct NvmeController {
     ptr: NonNull<spdk_nvme_ctrlr>,
+    /// Event handler registered via [`on_event()`](Self::on_event)
+    event_handler: Option<Box<EventHandler>>,
+    /// Whether [`NvmeEvent::ConnectionLost`] was already delivered
+    connection_lost: bool,
 }
 
 // SAFETY: spdk_nvme_ctrlr has internal mutex (ctrlr_lock) protecting most operations.
@@ -139,7 +188,7 @@ impl NvmeController {
         let ctrlr = unsafe { spdk_nvme_connect(trid.as_ptr(), opts_ptr, 0) };
 
         NonNull::new(ctrlr)
-            .map(|ptr| Self { ptr })
+            .map(Self::from_non_null)
             .ok_or(Error::ControllerNotFound)
     }
 
@@ -231,10 +280,18 @@ impl NvmeController {
         ATTACHED_CTRLR
             .with(|cell| cell.borrow_mut().take())
             .and_then(NonNull::new)
-            .map(|ptr| Self { ptr })
+            .map(Self::from_non_null)
             .ok_or(Error::ControllerNotFound)
     }
 
+    fn from_non_null(ptr: NonNull<spdk_nvme_ctrlr>) -> Self {
+        Self {
+            ptr,
+            event_handler: None,
+            connection_lost: false,
+        }
+    }
+
     /// Create an NvmeController from a raw pointer.
     ///
     /// # Safety
@@ -243,9 +300,7 @@ impl NvmeController {
     /// obtained from `spdk_nvme_connect` or similar. The caller transfers
     /// ownership to this struct.
     pub unsafe fn from_raw(ptr: *mut spdk_nvme_ctrlr) -> Self {
-        Self {
-            ptr: unsafe { NonNull::new_unchecked(ptr) },
-        }
+        Self::from_non_null(unsafe { NonNull::new_unchecked(ptr) })
     }
 
     /// Get the number of namespaces.
@@ -330,13 +385,223 @@ impl NvmeController {
     /// Process admin command completions.
     ///
     /// Call periodically to process admin command responses and keep-alive.
+    /// Keep-alive commands are only sent from here, so a controller connected
+    /// with [`NvmeCtrlrOpts::keep_alive_timeout_ms`] must be polled more often
+    /// than the timeout.
+    ///
+    /// If the controller has failed, the registered event handler (if any)
+    /// receives [`NvmeEvent::ConnectionLost`] once.
     ///
     /// # Thread Safety
     ///
     /// Requires `&mut self` because this operation is NOT thread-safe.
     /// Only one thread may call this at a time.
     pub fn process_admin_completions(&mut self) -> i32 {
-        unsafe { spdk_nvme_ctrlr_process_admin_completions(self.ptr.as_ptr()) }
+        let rc = unsafe { spdk_nvme_ctrlr_process_admin_completions(self.ptr.as_ptr()) };
+
+        if !self.connection_lost && (rc < 0 || self.is_failed()) {
+            self.connection_lost = true;
+            if let Some(handler) = self.event_handler.as_mut() {
+                handler(NvmeEvent::ConnectionLost);
+            }
+        }
+
+        rc
+    }
+
+    /// Register a handler for controller events.
+    ///
+    /// The handler receives Asynchronous Event Request completions and
+    /// [`NvmeEvent::ConnectionLost`]. It runs inside
+    /// [`process_admin_completions()`](Self::process_admin_completions).
+    /// Registering a new handler replaces the previous one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::nvme::{NvmeController, NvmeEvent};
+    ///
+    /// # fn example(ctrlr: &mut NvmeController) {
+    /// ctrlr.on_event(|event| {
+    ///     if event == NvmeEvent::ConnectionLost {
+    ///         eprintln!("controller connection lost");
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn on_event<F>(&mut self, f: F)
+    where
+        F: FnMut(NvmeEvent) + Send + 'static,
+    {
+        let handler: &mut Box<EventHandler> = self.event_handler.insert(Box::new(Box::new(f)));
+        let ctx = handler.as_mut() as *mut EventHandler as *mut c_void;
+
+        unsafe {
+            spdk_nvme_ctrlr_register_aer_callback(self.ptr.as_ptr(), Some(aer_callback), ctx);
+        }
+    }
+
+    /// Check whether the controller is in the failed state.
+    pub fn is_failed(&self) -> bool {
+        unsafe { spdk_nvme_ctrlr_is_failed(self.ptr.as_ptr()) }
+    }
+
+    /// Re-establish a failed controller.
+    ///
+    /// Performs a controller reset, which for fabrics transports (TCP/RDMA)
+    /// disconnects and reconnects the admin queue. Existing I/O qpairs are
+    /// disconnected by the reset; reconnect them with
+    /// [`NvmeQpair::reconnect()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller could not be reconnected.
+    pub fn reconnect(&mut self) -> Result<()> {
+        let rc = unsafe { spdk_nvme_ctrlr_reset(self.ptr.as_ptr()) };
+        if rc != 0 {
+            return Err(Error::from_rc(rc));
+        }
+        self.connection_lost = false;
+        Ok(())
+    }
+
+    /// Format a namespace with Format NVM.
+    ///
+    /// `lba_format_index` selects one of the namespace's LBA formats (block
+    /// and metadata size), `secure_erase` whether existing data is erased.
+    /// Pass `0xffffffff` as `nsid` to format all namespaces.
+    ///
+    /// SPDK polls the admin queue until the command completes, which can take
+    /// minutes for a secure erase, and then resets the controller so the new
+    /// namespace geometry is picked up. The calling SPDK thread does not run
+    /// other work meanwhile, and existing I/O qpairs are disconnected by the
+    /// reset; reconnect them with [`NvmeQpair::reconnect()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `lba_format_index` is above 63,
+    /// or an error if the controller rejects the format or the reset fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::nvme::{NvmeController, SecureErase};
+    ///
+    /// # async fn example(ctrlr: &mut NvmeController) -> spdk_io::Result<()> {
+    /// // Switch namespace 1 to its first LBA format, erasing user data
+    /// ctrlr.format(1, 0, SecureErase::UserData).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn format(
+        &mut self,
+        nsid: u32,
+        lba_format_index: u8,
+        secure_erase: SecureErase,
+    ) -> Result<()> {
+        if lba_format_index > 63 {
+            return Err(Error::InvalidArgument(format!(
+                "LBA format index {} out of range (0-63)",
+                lba_format_index
+            )));
+        }
+
+        let mut format = spdk_nvme_format::default();
+        // The index is split into LBAF (bits 3:0) and LBAFU (bits 5:4)
+        format.set_lbaf((lba_format_index & 0xf) as u32);
+        format.set_lbafu((lba_format_index >> 4) as u32);
+        format.set_ses(secure_erase.ses());
+
+        let rc = unsafe { spdk_nvme_ctrlr_format(self.ptr.as_ptr(), nsid, &mut format) };
+        if rc != 0 {
+            return Err(Error::from_rc(rc));
+        }
+        Ok(())
+    }
+
+    /// Fetch the discovery log page.
+    ///
+    /// The controller must be connected to a discovery subsystem, i.e. with
+    /// a [`TransportId`] whose subnqn is [`DISCOVERY_NQN`](super::DISCOVERY_NQN).
+    /// Blocks while polling the admin queue, like [`connect()`](Self::connect).
+    /// The page is re-read if the target changes it during the transfer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::nvme::{DISCOVERY_NQN, NvmeController, TransportId};
+    ///
+    /// # fn example() -> spdk_io::Result<()> {
+    /// let trid = TransportId::tcp("127.0.0.1", "4420", DISCOVERY_NQN)?;
+    /// let mut ctrlr = NvmeController::connect(&trid, None)?;
+    ///
+    /// for entry in ctrlr.discovery_log()? {
+    ///     println!("{} at {}:{}", entry.subnqn, entry.traddr, entry.trsvcid);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn discovery_log(&mut self) -> Result<Vec<DiscoveryLogEntry>> {
+        /// Bytes fetched per Get Log Page command
+        const CHUNK_SIZE: usize = 4096;
+        /// Attempts before giving up on a log page that keeps changing
+        const MAX_ATTEMPTS: usize = 3;
+
+        let mut header = vec![0u8; discovery::LOG_HEADER_SIZE];
+        for _ in 0..MAX_ATTEMPTS {
+            self.get_log_page(spdk_nvme_log_page_SPDK_NVME_LOG_DISCOVERY, &mut header, 0)?;
+            let genctr = discovery::header_genctr(&header);
+            let numrec = discovery::header_numrec(&header) as usize;
+
+            let mut entries = vec![0u8; numrec * discovery::LOG_ENTRY_SIZE];
+            for (i, chunk) in entries.chunks_mut(CHUNK_SIZE).enumerate() {
+                let offset = (discovery::LOG_HEADER_SIZE + i * CHUNK_SIZE) as u64;
+                self.get_log_page(spdk_nvme_log_page_SPDK_NVME_LOG_DISCOVERY, chunk, offset)?;
+            }
+
+            // The page is only consistent if the generation did not change
+            self.get_log_page(spdk_nvme_log_page_SPDK_NVME_LOG_DISCOVERY, &mut header, 0)?;
+            if discovery::header_genctr(&header) == genctr {
+                return Ok(entries
+                    .chunks_exact(discovery::LOG_ENTRY_SIZE)
+                    .map(DiscoveryLogEntry::parse)
+                    .collect());
+            }
+        }
+
+        Err(Error::InvalidArgument(
+            "discovery log page kept changing while being read".into(),
+        ))
+    }
+
+    /// Issue a Get Log Page admin command and poll until it completes.
+    fn get_log_page(&mut self, page: u32, buf: &mut [u8], offset: u64) -> Result<()> {
+        let status: Cell<Option<Result<()>>> = Cell::new(None);
+
+        let rc = unsafe {
+            spdk_nvme_ctrlr_cmd_get_log_page(
+                self.ptr.as_ptr(),
+                page as u8,
+                0, // nsid
+                buf.as_mut_ptr() as *mut c_void,
+                buf.len() as u32,
+                offset,
+                Some(admin_sync_done),
+                &status as *const _ as *mut c_void,
+            )
+        };
+        if rc != 0 {
+            return Err(Error::from_rc(rc));
+        }
+
+        // SPDK completes (or aborts) every submitted admin command from
+        // process_admin_completions, so `status` is always filled eventually
+        loop {
+            if let Some(result) = status.take() {
+                return result;
+            }
+            self.process_admin_completions();
+        }
     }
 
     /// Get raw pointer (for internal use).
@@ -346,6 +611,34 @@ impl NvmeController {
     }
 }
 
+/// Callback for spdk_nvme_ctrlr_register_aer_callback
+unsafe extern "C" fn aer_callback(ctx: *mut c_void, cpl: *const spdk_nvme_cpl) {
+    let handler = unsafe { &mut *(ctx as *mut EventHandler) };
+    let cdw0 = unsafe { (*cpl).cdw0 };
+    handler(NvmeEvent::Aer { cdw0 });
+}
+
+/// Callback for admin commands polled synchronously.
+///
+/// `ctx` points to a `Cell<Option<Result<()>>>` on the submitter's stack.
+unsafe extern "C" fn admin_sync_done(ctx: *mut c_void, cpl: *const spdk_nvme_cpl) {
+    let status = unsafe { &*(ctx as *const Cell<Option<Result<()>>>) };
+
+    // SCT is in bits 9:11, SC in bits 1:8 of the status field
+    let status_raw = unsafe { (*cpl).__bindgen_anon_1.status_raw };
+    let sct = (status_raw >> 9) & 0x7;
+    let sc = (status_raw >> 1) & 0xff;
+
+    status.set(Some(if sct == 0 && sc == 0 {
+        Ok(())
+    } else {
+        Err(Error::NvmeError {
+            sct: sct as u8,
+            sc: sc as u8,
+        })
+    }));
+}
+
 impl Drop for NvmeController {
     fn drop(&mut self) {
         // Detach from controller
@@ -0,0 +1,125 @@
+//! NVMe-oF discovery log page.
+//!
+//! Entries returned by [`NvmeController::discovery_log()`](super::NvmeController::discovery_log)
+//! when connected to a discovery controller.
+
+/// Well-known NQN of the discovery subsystem.
+pub const DISCOVERY_NQN: &str = "nqn.2014-08.org.nvmexpress.discovery";
+
+/// Size of the discovery log page header; entries start at this offset.
+pub(crate) const LOG_HEADER_SIZE: usize = 1024;
+
+/// Size of one discovery log page entry.
+pub(crate) const LOG_ENTRY_SIZE: usize = 1024;
+
+/// One record of the discovery log page.
+///
+/// Describes a subsystem (or another discovery controller) and the
+/// transport address it can be reached on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveryLogEntry {
+    /// Transport type (`SPDK_NVMF_TRTYPE_*`, e.g. 3 for TCP)
+    pub trtype: u8,
+    /// Address family (`SPDK_NVMF_ADRFAM_*`, e.g. 1 for IPv4)
+    pub adrfam: u8,
+    /// Subsystem type (1 = discovery referral, 2 = NVM, 3 = current discovery)
+    pub subtype: u8,
+    /// Port ID
+    pub portid: u16,
+    /// Controller ID (0xFFFF for the dynamic controller model)
+    pub cntlid: u16,
+    /// Transport service ID (e.g. the TCP port)
+    pub trsvcid: String,
+    /// Transport address (e.g. the IP address)
+    pub traddr: String,
+    /// Subsystem NQN
+    pub subnqn: String,
+}
+
+impl DiscoveryLogEntry {
+    /// Whether the entry describes an NVM subsystem (as opposed to a
+    /// discovery controller).
+    pub fn is_nvm_subsystem(&self) -> bool {
+        self.subtype == 2
+    }
+
+    /// Parse a raw 1024-byte log page entry.
+    pub(crate) fn parse(raw: &[u8]) -> Self {
+        let u16_at = |offset: usize| u16::from_le_bytes([raw[offset], raw[offset + 1]]);
+
+        Self {
+            trtype: raw[0],
+            adrfam: raw[1],
+            subtype: raw[2],
+            portid: u16_at(4),
+            cntlid: u16_at(6),
+            trsvcid: padded_str(&raw[32..64]),
+            subnqn: padded_str(&raw[256..512]),
+            traddr: padded_str(&raw[512..768]),
+        }
+    }
+}
+
+/// Decode a fixed-size ASCII field padded with NULs or spaces.
+fn padded_str(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end])
+        .trim_end()
+        .to_string()
+}
+
+/// Read the record count from a discovery log page header.
+pub(crate) fn header_numrec(header: &[u8]) -> u64 {
+    u64::from_le_bytes(header[8..16].try_into().unwrap())
+}
+
+/// Read the generation counter from a discovery log page header.
+pub(crate) fn header_genctr(header: &[u8]) -> u64 {
+    u64::from_le_bytes(header[0..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entry() {
+        let mut raw = [0u8; LOG_ENTRY_SIZE];
+        raw[0] = 3; // TCP
+        raw[1] = 1; // IPv4
+        raw[2] = 2; // NVM subsystem
+        raw[4..6].copy_from_slice(&7u16.to_le_bytes());
+        raw[6..8].copy_from_slice(&0xffffu16.to_le_bytes());
+        raw[32..36].copy_from_slice(b"4420");
+        raw[36..64].fill(b' ');
+        let nqn = b"nqn.2024-01.io.spdk:test";
+        raw[256..256 + nqn.len()].copy_from_slice(nqn);
+        raw[512..521].copy_from_slice(b"127.0.0.1");
+
+        let entry = DiscoveryLogEntry::parse(&raw);
+        assert_eq!(
+            entry,
+            DiscoveryLogEntry {
+                trtype: 3,
+                adrfam: 1,
+                subtype: 2,
+                portid: 7,
+                cntlid: 0xffff,
+                trsvcid: "4420".to_string(),
+                traddr: "127.0.0.1".to_string(),
+                subnqn: "nqn.2024-01.io.spdk:test".to_string(),
+            }
+        );
+        assert!(entry.is_nvm_subsystem());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let mut header = [0u8; LOG_HEADER_SIZE];
+        header[0..8].copy_from_slice(&5u64.to_le_bytes());
+        header[8..16].copy_from_slice(&2u64.to_le_bytes());
+
+        assert_eq!(header_genctr(&header), 5);
+        assert_eq!(header_numrec(&header), 2);
+    }
+}
@@ -55,12 +55,14 @@
 //! ```
 
 mod controller;
+mod discovery;
 mod namespace;
 mod opts;
 mod qpair;
 mod transport;
 
-pub use controller::NvmeController;
+pub use controller::{NvmeController, NvmeEvent, SecureErase};
+pub use discovery::{DISCOVERY_NQN, DiscoveryLogEntry};
 pub use namespace::NvmeNamespace;
 pub use opts::{NvmeCtrlrOpts, NvmeQpairOpts};
 pub use qpair::NvmeQpair;
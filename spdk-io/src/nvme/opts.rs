@@ -12,6 +12,9 @@ pub struct NvmeCtrlrOpts {
     /// Admin queue depth
     pub admin_queue_size: Option<u16>,
     /// Keep-alive timeout in ms (0 = disabled)
+    ///
+    /// Keep-alives are sent from
+    /// [`NvmeController::process_admin_completions()`](super::NvmeController::process_admin_completions).
     pub keep_alive_timeout_ms: Option<u32>,
 }
 
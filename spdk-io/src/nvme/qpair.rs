@@ -7,6 +7,8 @@ use std::ptr::NonNull;
 
 use spdk_io_sys::*;
 
+use crate::error::{Error, Result};
+
 /// NVMe I/O queue pair.
 ///
 /// Used to submit I/O commands to a namespace. Each thread should
@@ -61,6 +63,17 @@ impl NvmeQpair {
         unsafe { spdk_nvme_qpair_process_completions(self.ptr.as_ptr(), max_completions) }
     }
 
+    /// Reconnect the qpair after a controller reset.
+    ///
+    /// See [`NvmeController::reconnect()`](super::NvmeController::reconnect).
+    pub fn reconnect(&self) -> Result<()> {
+        let rc = unsafe { spdk_nvme_ctrlr_reconnect_io_qpair(self.ptr.as_ptr()) };
+        if rc != 0 {
+            return Err(Error::from_rc(rc));
+        }
+        Ok(())
+    }
+
     /// Get raw pointer (for internal use).
     pub(crate) fn as_ptr(&self) -> *mut spdk_nvme_qpair {
         self.ptr.as_ptr()
@@ -44,11 +44,13 @@
 //! See [`NvmfTarget`] for creating in-process targets.
 
 mod opts;
+mod poll_group;
 mod subsystem;
 mod target;
 mod transport;
 
 pub use opts::{NvmfNsOpts, NvmfSubsystemOpts, NvmfTargetOpts, NvmfTransportOpts};
+pub use poll_group::NvmfPollGroup;
 pub use subsystem::NvmfSubsystem;
 pub use target::NvmfTarget;
 pub use transport::NvmfTransport;
@@ -0,0 +1,44 @@
+//! NVMf poll group management.
+
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use spdk_io_sys::*;
+
+/// Per-thread NVMf poll group.
+///
+/// The target hands newly accepted connections to its poll groups, so at
+/// least one must exist before initiators can connect. Create one per SPDK
+/// thread that should serve I/O via [`NvmfTarget::create_poll_group()`].
+///
+/// # Thread Safety
+///
+/// `!Send + !Sync` - the poll group belongs to the thread that created it.
+///
+/// # Drop
+///
+/// Destroys the poll group, disconnecting its qpairs.
+///
+/// [`NvmfTarget::create_poll_group()`]: super::NvmfTarget::create_poll_group
+pub struct NvmfPollGroup {
+    ptr: NonNull<spdk_nvmf_poll_group>,
+    _marker: PhantomData<*mut ()>, // !Send + !Sync
+}
+
+impl NvmfPollGroup {
+    /// Create from raw pointer (internal use).
+    pub(crate) fn from_ptr(ptr: NonNull<spdk_nvmf_poll_group>) -> Self {
+        Self {
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Drop for NvmfPollGroup {
+    fn drop(&mut self) {
+        unsafe {
+            spdk_nvmf_poll_group_destroy(self.ptr.as_ptr(), None, std::ptr::null_mut());
+        }
+    }
+}
@@ -10,7 +10,10 @@ use spdk_io_sys::*;
 use crate::complete::{CompletionSender, completion};
 use crate::error::{Error, Result};
 
+use crate::nvme::{DISCOVERY_NQN, TransportId};
+
 use super::opts::NvmfTargetOpts;
+use super::poll_group::NvmfPollGroup;
 use super::subsystem::NvmfSubsystem;
 use super::transport::NvmfTransport;
 
@@ -97,7 +100,7 @@ impl NvmfTarget {
     /// # Arguments
     ///
     /// * `trid` - Transport ID specifying the listen address
-    pub fn listen(&self, trid: &crate::nvme::TransportId) -> Result<()> {
+    pub fn listen(&self, trid: &TransportId) -> Result<()> {
         // Initialize listen options
         let mut opts: spdk_nvmf_listen_opts = unsafe { MaybeUninit::zeroed().assume_init() };
         unsafe {
@@ -157,6 +160,59 @@ impl NvmfTarget {
         Ok(subsys)
     }
 
+    /// Create a poll group on the current SPDK thread.
+    ///
+    /// Accepted connections are assigned to the target's poll groups, so
+    /// create at least one before initiators connect. Keep the returned
+    /// [`NvmfPollGroup`] alive for as long as the thread should serve I/O.
+    pub fn create_poll_group(&self) -> Result<NvmfPollGroup> {
+        let group = unsafe { spdk_nvmf_poll_group_create(self.ptr.as_ptr()) };
+
+        NonNull::new(group)
+            .map(NvmfPollGroup::from_ptr)
+            .ok_or_else(|| Error::InvalidArgument("Failed to create NVMf poll group".into()))
+    }
+
+    /// Expose the discovery subsystem on a transport address.
+    ///
+    /// Creates the discovery subsystem ([`DISCOVERY_NQN`]) if needed, starts
+    /// listening on `trid`, adds it as a listener and starts the subsystem,
+    /// so `nvme discover` (or [`NvmeController::discovery_log()`]) against
+    /// that address lists the target's subsystems. The address may be the
+    /// same as an I/O subsystem's listener. The transport must already have
+    /// been added with [`add_transport()`](Self::add_transport).
+    ///
+    /// [`NvmeController::discovery_log()`]: crate::nvme::NvmeController::discovery_log
+    pub async fn enable_discovery(&self, trid: &TransportId) -> Result<NvmfSubsystem> {
+        let subsys = match self.find_subsystem(DISCOVERY_NQN) {
+            Some(subsys) => subsys,
+            None => {
+                let nqn_cstr = CString::new(DISCOVERY_NQN)?;
+                let ptr = unsafe {
+                    spdk_nvmf_subsystem_create(
+                        self.ptr.as_ptr(),
+                        nqn_cstr.as_ptr(),
+                        spdk_nvmf_subtype_SPDK_NVMF_SUBTYPE_DISCOVERY_CURRENT,
+                        0,
+                    )
+                };
+                let subsys = NonNull::new(ptr)
+                    .map(NvmfSubsystem::from_ptr)
+                    .ok_or_else(|| {
+                        Error::InvalidArgument("Failed to create discovery subsystem".into())
+                    })?;
+                subsys.set_allow_any_host(true);
+                subsys
+            }
+        };
+
+        self.listen(trid)?;
+        subsys.add_listener(trid).await?;
+        subsys.start().await?;
+
+        Ok(subsys)
+    }
+
     /// Find a subsystem by NQN.
     pub fn find_subsystem(&self, nqn: &str) -> Option<NvmfSubsystem> {
         let nqn_cstr = CString::new(nqn).ok()?;
@@ -26,11 +26,27 @@
 //!
 //! Use [`spdk_poller()`] as a background task in your executor to drive
 //! SPDK I/O completions.
+//!
+//! # Registered Pollers
+//!
+//! Registered pollers go the other way: SPDK runs a Rust closure
+//! (`spdk_poller_register`) on every [`poll()`](SpdkThread::poll) or once
+//! per period. [`SpdkThread::register_poller()`] and
+//! [`SpdkThread::register_timed_poller()`] return a [`PollerId`]; the thread
+//! keeps the poller until
+//! [`unregister_poller()`](SpdkThread::unregister_poller) or until it is
+//! dropped.
 
+use std::cell::{Cell, RefCell};
+use std::ffi::c_void;
 use std::future::Future;
 use std::pin::Pin;
+use std::ptr::NonNull;
 use std::task::{Context, Poll};
 
+use spdk_io_sys::*;
+
+use crate::error::{Error, Result};
 use crate::thread::SpdkThread;
 
 /// A future that yields once, then completes.
@@ -107,6 +123,169 @@ pub async fn spdk_poller_limited(max_iters: u64) {
     }
 }
 
+/// Closure of a registered [`Poller`], owned by the poller.
+struct PollerState {
+    f: RefCell<Box<dyn FnMut() -> i32>>,
+    /// Set while the trampoline is running the closure
+    running: Cell<bool>,
+    /// Set if the [`Poller`] was dropped from inside its own closure; the
+    /// trampoline then frees the state once the closure returns
+    released: Cell<bool>,
+}
+
+/// Identifies a poller registered with
+/// [`SpdkThread::register_poller()`].
+///
+/// Ids are unique per [`SpdkThread`] value and never reused, so a stale id
+/// does not match a later poller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PollerId(u64);
+
+/// An SPDK poller running a Rust closure (`spdk_poller`).
+///
+/// Dropping it unregisters the poller and frees the closure. Must be
+/// dropped on the thread that registered it.
+pub(crate) struct Poller {
+    ptr: NonNull<spdk_poller>,
+    state: *mut PollerState,
+}
+
+impl Poller {
+    /// Register `f` on `thread`, which must be current.
+    fn register<F>(thread: *mut spdk_thread, f: F, period_us: u64) -> Result<Self>
+    where
+        F: FnMut() -> i32 + 'static,
+    {
+        // spdk_poller_register() always registers on the current thread
+        if unsafe { spdk_get_thread() } != thread {
+            return Err(Error::InvalidArgument(
+                "register_poller called for a thread not attached to this OS thread".to_string(),
+            ));
+        }
+
+        let state = Box::into_raw(Box::new(PollerState {
+            f: RefCell::new(Box::new(f)),
+            running: Cell::new(false),
+            released: Cell::new(false),
+        }));
+        let ptr = unsafe {
+            spdk_poller_register(Some(poller_trampoline), state as *mut c_void, period_us)
+        };
+        match NonNull::new(ptr) {
+            Some(ptr) => Ok(Self { ptr, state }),
+            None => {
+                drop(unsafe { Box::from_raw(state) });
+                Err(Error::MemoryAlloc)
+            }
+        }
+    }
+}
+
+impl Drop for Poller {
+    fn drop(&mut self) {
+        let mut ptr = self.ptr.as_ptr();
+        // The thread skips unregistered pollers, so the closure does not
+        // run again after this
+        unsafe { spdk_poller_unregister(&mut ptr) };
+
+        let state = unsafe { &*self.state };
+        if state.running.get() {
+            // Dropped from inside the closure: let the trampoline free it
+            state.released.set(true);
+        } else {
+            drop(unsafe { Box::from_raw(self.state) });
+        }
+    }
+}
+
+/// Pollers registered through an [`SpdkThread`], keyed by [`PollerId`].
+///
+/// Owned by the thread, which unregisters the remaining pollers in reverse
+/// registration order when it is dropped.
+#[derive(Default)]
+pub(crate) struct PollerRegistry {
+    pollers: RefCell<Vec<(PollerId, Poller)>>,
+    next_id: Cell<u64>,
+}
+
+impl PollerRegistry {
+    /// Register `f` on `thread` (which must be current) and keep it.
+    pub(crate) fn register<F>(
+        &self,
+        thread: *mut spdk_thread,
+        f: F,
+        period_us: u64,
+    ) -> Result<PollerId>
+    where
+        F: FnMut() -> i32 + 'static,
+    {
+        let poller = Poller::register(thread, f, period_us)?;
+        let id = PollerId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.pollers.borrow_mut().push((id, poller));
+        Ok(id)
+    }
+
+    /// Unregister the poller `id`. Returns `false` if it is not registered.
+    pub(crate) fn unregister(&self, id: PollerId) -> bool {
+        let poller = {
+            let mut pollers = self.pollers.borrow_mut();
+            match pollers.iter().position(|(poller_id, _)| *poller_id == id) {
+                Some(index) => pollers.remove(index),
+                None => return false,
+            }
+        };
+        // Dropped outside the borrow, so the registry stays usable if this
+        // runs inside a poller
+        drop(poller);
+        true
+    }
+
+    /// Get the raw poller `id`, if it is registered.
+    pub(crate) fn get(&self, id: PollerId) -> Option<*mut spdk_poller> {
+        self.pollers
+            .borrow()
+            .iter()
+            .find(|(poller_id, _)| *poller_id == id)
+            .map(|(_, poller)| poller.ptr.as_ptr())
+    }
+
+    /// Number of registered pollers.
+    pub(crate) fn len(&self) -> usize {
+        self.pollers.borrow().len()
+    }
+
+    /// Unregister every poller, newest first.
+    pub(crate) fn clear(&self) {
+        let pollers = std::mem::take(&mut *self.pollers.borrow_mut());
+        for (_, poller) in pollers.into_iter().rev() {
+            drop(poller);
+        }
+    }
+}
+
+/// Callback for spdk_poller_register
+unsafe extern "C" fn poller_trampoline(ctx: *mut c_void) -> i32 {
+    let state = ctx as *mut PollerState;
+    let work_done = {
+        let state = unsafe { &*state };
+        state.running.set(true);
+        let work_done = (state.f.borrow_mut())();
+        state.running.set(false);
+        work_done
+    };
+
+    if unsafe { (*state).released.get() } {
+        drop(unsafe { Box::from_raw(state) });
+    }
+
+    if work_done > 0 {
+        spdk_thread_poller_rc_SPDK_POLLER_BUSY as i32
+    } else {
+        spdk_thread_poller_rc_SPDK_POLLER_IDLE as i32
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,273 @@
+//! Reactor loop for daemon-style programs.
+//!
+//! A [`Reactor`] repeatedly polls an SPDK thread together with a set of
+//! local tasks until a shutdown condition is met. When a loop iteration
+//! does no work it backs off briefly so an idle reactor does not spin at
+//! 100% CPU.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//! use spdk_io::{Reactor, SpdkEnv, SpdkThread};
+//!
+//! static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+//!
+//! let _env = SpdkEnv::builder().name("daemon").build().unwrap();
+//! let thread = SpdkThread::new("main").unwrap();
+//!
+//! let reactor = Reactor::new(&thread);
+//! reactor.spawn_local(async {
+//!     // Application work...
+//!     SHUTDOWN.store(true, Ordering::SeqCst);
+//! });
+//! reactor.run(|| SHUTDOWN.load(Ordering::SeqCst));
+//! ```
+//!
+//! [`Reactor::block_on()`] drives a single future instead. Its waker posts an
+//! SPDK message and unparks the reactor, so futures woken from other OS
+//! threads (channels, timers of other runtimes) are resumed promptly while
+//! the reactor otherwise sleeps when idle.
+
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+use spdk_io_sys::*;
+
+use crate::thread::{CurrentThread, SpdkThread};
+
+/// Default sleep between idle loop iterations.
+pub const DEFAULT_IDLE_BACKOFF: Duration = Duration::from_micros(100);
+
+type LocalTask = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Polling loop driving an SPDK thread and local tasks.
+///
+/// # Thread Safety
+///
+/// `!Send + !Sync` - the reactor must run on the OS thread its SPDK thread
+/// is attached to.
+///
+/// A reactor borrows the [`SpdkThread`] or [`CurrentThread`] it was created
+/// from, so the thread cannot be dropped while the reactor still polls it.
+pub struct Reactor<'a> {
+    thread: CurrentThread,
+    idle_backoff: Duration,
+    tasks: RefCell<Vec<LocalTask>>,
+    _thread: PhantomData<&'a SpdkThread>,
+}
+
+impl<'a> Reactor<'a> {
+    /// Create a reactor driving `thread`.
+    pub fn new(thread: &'a SpdkThread) -> Self {
+        Self {
+            thread: CurrentThread::from_ptr(thread.as_ptr()),
+            idle_backoff: DEFAULT_IDLE_BACKOFF,
+            tasks: RefCell::new(Vec::new()),
+            _thread: PhantomData,
+        }
+    }
+
+    /// Create a reactor driving the SPDK thread attached to this OS thread.
+    ///
+    /// `thread` comes from [`SpdkThread::get_current()`] and stays borrowed
+    /// while the reactor exists. Returns `None` if it is no longer the thread
+    /// attached to this OS thread.
+    ///
+    /// ```no_run
+    /// use spdk_io::{Reactor, SpdkThread};
+    ///
+    /// let current = SpdkThread::get_current().expect("no SPDK thread attached");
+    /// let reactor = Reactor::current(&current).unwrap();
+    /// reactor.run(|| true);
+    /// ```
+    pub fn current(thread: &'a CurrentThread) -> Option<Self> {
+        if unsafe { spdk_get_thread() } != thread.as_ptr() {
+            return None;
+        }
+        Some(Self {
+            thread: CurrentThread::from_ptr(thread.as_ptr()),
+            idle_backoff: DEFAULT_IDLE_BACKOFF,
+            tasks: RefCell::new(Vec::new()),
+            _thread: PhantomData,
+        })
+    }
+
+    /// Set how long to sleep when a loop iteration does no work.
+    ///
+    /// The sleep is cut short when a timed poller is due earlier.
+    /// `Duration::ZERO` yields the OS thread instead of sleeping, trading
+    /// CPU for latency. Default is [`DEFAULT_IDLE_BACKOFF`].
+    pub fn idle_backoff(mut self, backoff: Duration) -> Self {
+        self.idle_backoff = backoff;
+        self
+    }
+
+    /// Spawn a task to run on this reactor.
+    ///
+    /// The task is polled on every loop iteration of [`run()`](Self::run)
+    /// until it completes. Tasks still pending when the reactor is dropped
+    /// are dropped with it.
+    pub fn spawn_local<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.tasks.borrow_mut().push(Box::pin(future));
+    }
+
+    /// Run the reactor until `shutdown()` returns `true`.
+    ///
+    /// Each iteration checks `shutdown`, polls the SPDK thread, then polls
+    /// every local task. If neither the thread nor any task made progress,
+    /// the reactor backs off for the configured
+    /// [`idle_backoff`](Self::idle_backoff).
+    pub fn run(&self, shutdown: impl Fn() -> bool) {
+        while !shutdown() {
+            let work = self.thread.poll();
+            let progressed = self.poll_tasks();
+
+            if work == 0 && !progressed {
+                let backoff = self.backoff();
+                if backoff.is_zero() {
+                    std::thread::yield_now();
+                } else {
+                    std::thread::sleep(backoff);
+                }
+            }
+        }
+    }
+
+    /// Run `future` to completion on this reactor.
+    ///
+    /// Each iteration polls the SPDK thread and the local tasks first, so
+    /// messages, pollers and I/O completions fire, then polls `future` if it
+    /// has been woken (a completion firing during the thread poll is picked
+    /// up in the same iteration). The first iteration always polls `future`.
+    ///
+    /// When neither made progress, the reactor sleeps like
+    /// [`run()`](Self::run): for the [`idle_backoff`](Self::idle_backoff),
+    /// cut short when a timed poller is due sooner, so waiting on a timer
+    /// does not peg the CPU. The waker may be used from any OS thread: it
+    /// posts a no-op message to the SPDK thread and unparks the reactor, so
+    /// a wake-up ends the sleep instead of waiting it out. Messages sent to
+    /// the SPDK thread by other means are processed once the sleep ends.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::{Reactor, SpdkThread};
+    ///
+    /// # fn example(thread: &SpdkThread) {
+    /// let (tx, rx) = futures::channel::oneshot::channel();
+    /// std::thread::spawn(move || tx.send(42).unwrap());
+    ///
+    /// let value = Reactor::new(thread).block_on(rx).unwrap();
+    /// assert_eq!(value, 42);
+    /// # }
+    /// ```
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let wake = Arc::new(ReactorWaker {
+            thread: Mutex::new(Some(ThreadPtr(self.thread.as_ptr()))),
+            os_thread: std::thread::current(),
+            woken: AtomicBool::new(true),
+        });
+        let waker = Waker::from(wake.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = std::pin::pin!(future);
+        let output = loop {
+            let work = self.thread.poll();
+            let progressed = self.poll_tasks();
+
+            if wake.woken.swap(false, Ordering::AcqRel)
+                && let Poll::Ready(output) = future.as_mut().poll(&mut cx)
+            {
+                break output;
+            }
+
+            if work == 0 && !progressed && !wake.woken.load(Ordering::Acquire) {
+                let backoff = self.backoff();
+                if backoff.is_zero() {
+                    std::thread::yield_now();
+                } else {
+                    std::thread::park_timeout(backoff);
+                }
+            }
+        };
+
+        // Clones of the waker may outlive this call (and the SPDK thread)
+        *wake.thread.lock().unwrap() = None;
+        output
+    }
+
+    /// Idle sleep: the configured backoff, cut short when a timed poller
+    /// is due sooner.
+    fn backoff(&self) -> Duration {
+        match self.thread.next_poller_expiration_us() {
+            Some(us) => self.idle_backoff.min(Duration::from_micros(us)),
+            None => self.idle_backoff,
+        }
+    }
+
+    /// Poll every local task once. Returns `true` if any task completed.
+    fn poll_tasks(&self) -> bool {
+        // We poll every iteration, so a no-op waker is sufficient.
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // Take the tasks out so they can spawn new ones while being polled
+        let mut tasks = std::mem::take(&mut *self.tasks.borrow_mut());
+        let before = tasks.len();
+        tasks.retain_mut(|task| task.as_mut().poll(&mut cx) == Poll::Pending);
+        let progressed = tasks.len() < before;
+
+        let mut current = self.tasks.borrow_mut();
+        tasks.append(&mut current);
+        *current = tasks;
+
+        progressed
+    }
+}
+
+/// `spdk_thread` pointer that may be shared with waking threads.
+struct ThreadPtr(*mut spdk_thread);
+
+// SAFETY: only used with spdk_thread_send_msg(), which is thread-safe
+unsafe impl Send for ThreadPtr {}
+
+/// Waker of [`Reactor::block_on()`].
+struct ReactorWaker {
+    /// Target of the wake-up message, cleared when `block_on` returns.
+    thread: Mutex<Option<ThreadPtr>>,
+    /// OS thread running the reactor, unparked on wake.
+    os_thread: std::thread::Thread,
+    /// Whether the future should be polled again.
+    woken: AtomicBool,
+}
+
+impl Wake for ReactorWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+
+        // The lock keeps the thread alive until the message is queued
+        if let Some(thread) = self.thread.lock().unwrap().as_ref() {
+            // Failure (message pool exhausted) is harmless: the reactor is
+            // unparked below and sees `woken` either way
+            unsafe { spdk_thread_send_msg(thread.0, Some(wake_msg), std::ptr::null_mut()) };
+            self.os_thread.unpark();
+        }
+    }
+}
+
+/// Message posted by [`ReactorWaker`]; processing it is the whole point.
+unsafe extern "C" fn wake_msg(_ctx: *mut c_void) {}
@@ -0,0 +1,280 @@
+//! JSON-RPC client for a running SPDK application
+//!
+//! SPDK applications serve JSON-RPC 2.0 on a unix socket (`/var/tmp/spdk.sock`
+//! unless started with another [`rpc_addr`](crate::SpdkAppBuilder::rpc_addr)).
+//! [`Client`] talks to that socket with plain std I/O, so tools can drive an
+//! SPDK process without initializing SPDK themselves.
+//!
+//! # Framing
+//!
+//! SPDK does not delimit messages: requests and responses are JSON objects
+//! written back to back on the stream, the same as SPDK's `rpc.py` sends
+//! them. A response is complete once the bytes read so far parse as one
+//! JSON value; anything after it is kept for the next call.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use spdk_io::rpc::{Client, DEFAULT_SOCKET};
+//! use serde_json::json;
+//!
+//! # fn main() -> spdk_io::Result<()> {
+//! let mut client = Client::connect(DEFAULT_SOCKET)?;
+//! let version = client.call("spdk_get_version", serde_json::Value::Null)?;
+//! println!("{}", version["version"]);
+//!
+//! client.call(
+//!     "bdev_null_create",
+//!     json!({ "name": "Null0", "num_blocks": 1024, "block_size": 512 }),
+//! )?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use crate::error::{Error, Result};
+
+/// Socket SPDK applications listen on by default.
+pub const DEFAULT_SOCKET: &str = "/var/tmp/spdk.sock";
+
+/// Bytes requested from the socket per read.
+const READ_CHUNK: usize = 4096;
+
+/// JSON-RPC client connected to an SPDK application's socket.
+///
+/// Calls are synchronous and run one at a time; each waits for the response
+/// with the matching `id`.
+pub struct Client {
+    stream: UnixStream,
+    /// Bytes received but not yet consumed as a response
+    buf: Vec<u8>,
+    next_id: u64,
+}
+
+impl Client {
+    /// Connect to the SPDK RPC socket at `socket_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the socket cannot be connected (e.g. the
+    /// application is not running or listens elsewhere).
+    pub fn connect(socket_path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            stream: UnixStream::connect(socket_path)?,
+            buf: Vec::new(),
+            next_id: 1,
+        })
+    }
+
+    /// Limit how long a call waits for its response. `None` (the default)
+    /// waits forever.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        self.stream.set_read_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// Call `method` and return its `result`.
+    ///
+    /// `params` is sent as the request's `params` member; pass
+    /// [`Value::Null`] for methods without parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - SPDK answers with a JSON-RPC error ([`Error::Rpc`], e.g. `-32601`
+    ///   for an unknown method)
+    /// - The socket fails, times out, or closes before the response
+    ///   ([`Error::Io`])
+    /// - The response is not valid JSON-RPC ([`Error::Io`] with
+    ///   `InvalidData`)
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.stream.write_all(&encode_request(id, method, params))?;
+        read_response(&mut self.stream, &mut self.buf, id)
+    }
+}
+
+/// Serialize a JSON-RPC 2.0 request.
+fn encode_request(id: u64, method: &str, params: Value) -> Vec<u8> {
+    let mut request = json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "id": id,
+    });
+    if !params.is_null() {
+        request["params"] = params;
+    }
+    request.to_string().into_bytes()
+}
+
+/// Read messages from `reader` until the response to `id` arrives.
+///
+/// `buf` carries bytes across calls. Messages for other ids (e.g. late
+/// responses to a call that timed out) are skipped.
+fn read_response(reader: &mut impl Read, buf: &mut Vec<u8>, id: u64) -> Result<Value> {
+    loop {
+        let Some(message) = next_message(reader, buf)? else {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "SPDK closed the RPC socket before responding",
+            )));
+        };
+        if message.get("id").and_then(Value::as_u64) != Some(id) {
+            continue;
+        }
+        return into_result(message);
+    }
+}
+
+/// Parse the next complete JSON value, reading more bytes as needed.
+///
+/// Returns `None` if the stream ends between messages.
+fn next_message(reader: &mut impl Read, buf: &mut Vec<u8>) -> Result<Option<Value>> {
+    loop {
+        let mut values = serde_json::Deserializer::from_slice(buf).into_iter::<Value>();
+        match values.next() {
+            Some(Ok(value)) => {
+                let consumed = values.byte_offset();
+                buf.drain(..consumed);
+                return Ok(Some(value));
+            }
+            Some(Err(err)) if !err.is_eof() => return Err(Error::Io(err.into())),
+            // Only whitespace or a partial value so far
+            _ => {}
+        }
+
+        let mut chunk = [0u8; READ_CHUNK];
+        let read = match reader.read(&mut chunk) {
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        if read == 0 {
+            if buf.iter().all(u8::is_ascii_whitespace) {
+                return Ok(None);
+            }
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "SPDK closed the RPC socket mid-response",
+            )));
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    }
+}
+
+/// Turn a JSON-RPC response into its `result` or [`Error::Rpc`].
+fn into_result(mut message: Value) -> Result<Value> {
+    if let Some(error) = message.get("error") {
+        return Err(Error::Rpc {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or(0),
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        });
+    }
+    match message.get_mut("result") {
+        Some(result) => Ok(result.take()),
+        None => Err(Error::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "JSON-RPC response has neither result nor error",
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_encode_request() {
+        let request: Value =
+            serde_json::from_slice(&encode_request(7, "bdev_get_bdevs", Value::Null)).unwrap();
+        assert_eq!(
+            request,
+            json!({ "jsonrpc": "2.0", "method": "bdev_get_bdevs", "id": 7 })
+        );
+
+        let request: Value =
+            serde_json::from_slice(&encode_request(8, "bdev_get_bdevs", json!({ "name": "a" })))
+                .unwrap();
+        assert_eq!(request["params"], json!({ "name": "a" }));
+    }
+
+    #[test]
+    fn test_call_over_mock_socket() {
+        let (client_end, mut server) = UnixStream::pair().unwrap();
+        let mut client = Client {
+            stream: client_end,
+            buf: Vec::new(),
+            next_id: 1,
+        };
+
+        // Answers each request like SPDK: split across writes, and the
+        // second answer preceded by a stale response for another id
+        let mock = thread::spawn(move || {
+            let mut buf = Vec::new();
+            for _ in 0..2 {
+                let request = next_message(&mut server, &mut buf).unwrap().unwrap();
+                let id = request["id"].as_u64().unwrap();
+                let response = match request["method"].as_str().unwrap() {
+                    "spdk_get_version" => {
+                        json!({ "jsonrpc": "2.0", "id": id, "result": { "version": "SPDK v26.01" } })
+                    }
+                    _ => {
+                        let stale = json!({ "jsonrpc": "2.0", "id": 99, "result": true });
+                        server.write_all(stale.to_string().as_bytes()).unwrap();
+                        json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": { "code": -32601, "message": "Method not found" }
+                        })
+                    }
+                };
+                let bytes = response.to_string().into_bytes();
+                let (head, tail) = bytes.split_at(bytes.len() / 2);
+                server.write_all(head).unwrap();
+                server.flush().unwrap();
+                server.write_all(tail).unwrap();
+            }
+        });
+
+        let version = client.call("spdk_get_version", Value::Null).unwrap();
+        assert_eq!(version["version"], "SPDK v26.01");
+
+        let err = client.call("no_such_method", Value::Null).unwrap_err();
+        assert!(
+            matches!(err, Error::Rpc { code: -32601, ref message } if message == "Method not found")
+        );
+
+        mock.join().unwrap();
+
+        // The mock closed its end, so another call sees the closed socket
+        assert!(matches!(
+            client.call("spdk_get_version", Value::Null),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_next_message_rejects_garbage() {
+        let mut buf = Vec::new();
+        let mut input: &[u8] = b"{\"id\": 1} not json";
+        let first = next_message(&mut input, &mut buf).unwrap().unwrap();
+        assert_eq!(first["id"], 1);
+        assert!(matches!(
+            next_message(&mut input, &mut buf),
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+}
@@ -37,26 +37,128 @@
 //! }
 //! ```
 
-use std::ffi::{CString, c_void};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString, c_void};
+use std::future::Future;
 use std::marker::PhantomData;
 use std::ptr::NonNull;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{PoisonError, RwLock};
+use std::task::{Context, Poll, Waker};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use spdk_io_sys::*;
 
 use crate::complete::{CompletionReceiver, completion};
-use crate::error::{Error, Result};
+use crate::cpuset::CpuSet;
+use crate::error::{Error, Result, c_str_checked};
+use crate::poller::{PollerId, PollerRegistry};
 
 /// Global flag to track if thread library is initialized
 static THREAD_LIB_INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+/// Whether the thread library was initialized by a framework that can
+/// reschedule threads (the event framework). Our own inits pass no
+/// `thread_op_supported_fn`, and `spdk_thread_set_cpumask()` asserts on that.
+static RESCHEDULE_SUPPORTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Whether this OS thread is inside a [`SpdkThread::poll()`] call
+    static IN_POLL: Cell<bool> = const { Cell::new(false) };
+
+    /// Set by [`SpdkThread::set_poll_observer()`]
+    static POLL_OBSERVER: RefCell<Option<Box<dyn FnMut(i32, Duration)>>> =
+        const { RefCell::new(None) };
+}
+
+/// Clears [`IN_POLL`] when the outermost poll returns (or unwinds).
+struct PollGuard(());
+
+impl PollGuard {
+    fn enter() -> Result<Self> {
+        if IN_POLL.replace(true) {
+            return Err(Error::ReentrantPoll);
+        }
+        Ok(PollGuard(()))
+    }
+}
+
+impl Drop for PollGuard {
+    fn drop(&mut self) {
+        IN_POLL.set(false);
+    }
+}
+
+/// Run `spdk_thread_poll()`, rejecting reentrant calls.
+fn poll_checked(thread: *mut spdk_thread, max_msgs: u32) -> Result<i32> {
+    let _guard = PollGuard::enter()?;
+
+    // Only read the clock when someone is listening
+    let start = POLL_OBSERVER
+        .with_borrow(Option::is_some)
+        .then(Instant::now);
+    let work = unsafe { spdk_thread_poll(thread, max_msgs, 0) };
+    if let Some(start) = start {
+        notify_poll_observer(work, start.elapsed());
+    }
+    Ok(work)
+}
+
+/// Call the poll observer without holding the borrow, so it may install a
+/// replacement.
+fn notify_poll_observer(work: i32, elapsed: Duration) {
+    let Some(mut observer) = POLL_OBSERVER.take() else {
+        return;
+    };
+    observer(work, elapsed);
+    POLL_OBSERVER.with_borrow_mut(|slot| {
+        if slot.is_none() {
+            *slot = Some(observer);
+        }
+    });
+}
+
+/// Like [`poll_checked`], but panics on reentry in debug builds. Release
+/// builds skip the nested poll and report no work.
+fn poll_unchecked(thread: *mut spdk_thread, max_msgs: u32) -> i32 {
+    match poll_checked(thread, max_msgs) {
+        Ok(work) => work,
+        Err(_) => {
+            debug_assert!(
+                false,
+                "SpdkThread::poll() called reentrantly (e.g. from a poller or message)"
+            );
+            0
+        }
+    }
+}
+
 /// Default message mempool size (same as SPDK's SPDK_DEFAULT_MSG_MEMPOOL_SIZE)
 pub const DEFAULT_MSG_MEMPOOL_SIZE: usize = 262144 - 1;
 
 /// Smaller mempool size for testing (1023 entries)
 pub const SMALL_MSG_MEMPOOL_SIZE: usize = 1023;
 
+/// Longest SPDK thread name in bytes (`SPDK_MAX_THREAD_NAME_LEN`).
+///
+/// SPDK silently truncates longer names, so [`SpdkThread`] constructors
+/// reject them instead.
+pub const MAX_THREAD_NAME_LEN: usize = SPDK_MAX_THREAD_NAME_LEN as usize;
+
+/// Convert a thread name for SPDK, rejecting names it would truncate.
+fn thread_name(name: &str) -> Result<CString> {
+    if name.len() > MAX_THREAD_NAME_LEN {
+        return Err(Error::Config(format!(
+            "thread name is {} bytes, SPDK allows at most {}",
+            name.len(),
+            MAX_THREAD_NAME_LEN
+        )));
+    }
+    Ok(CString::new(name)?)
+}
+
 /// Initialize the SPDK thread library with custom mempool size.
 ///
 /// This is called automatically when creating the first [`SpdkThread`].
@@ -101,11 +203,13 @@ pub(crate) fn thread_lib_init() -> Result<()> {
 /// Used when the thread library was initialized externally (e.g., by `spdk_app_start()`).
 pub(crate) fn assume_thread_lib_initialized() {
     THREAD_LIB_INITIALIZED.store(true, Ordering::SeqCst);
+    RESCHEDULE_SUPPORTED.store(true, Ordering::SeqCst);
 }
 
 /// Finalize the SPDK thread library.
 pub(crate) fn thread_lib_fini() {
     if THREAD_LIB_INITIALIZED.swap(false, Ordering::SeqCst) {
+        RESCHEDULE_SUPPORTED.store(false, Ordering::SeqCst);
         unsafe {
             spdk_thread_lib_fini();
         }
@@ -123,6 +227,12 @@ pub(crate) fn thread_lib_fini() {
 /// that created it. This is enforced at compile time.
 pub struct SpdkThread {
     ptr: NonNull<spdk_thread>,
+    /// Whether dropping this value exits and destroys the thread; `false`
+    /// for an existing thread returned by
+    /// [`attach_or_current()`](Self::attach_or_current)
+    owned: bool,
+    /// Pollers registered through this value, unregistered when it drops
+    pollers: PollerRegistry,
     /// Prevent Send/Sync - thread must stay on creating OS thread
     _marker: PhantomData<*mut ()>,
 }
@@ -145,8 +255,19 @@ impl SpdkThread {
     ///
     /// # Errors
     ///
+    /// Returns [`Error::NulError`] if the name contains a NUL byte, and
+    /// [`Error::Config`] if it is longer than [`MAX_THREAD_NAME_LEN`].
+    ///
+    /// Returns [`Error::AlreadyAttached`] if an SPDK thread is already
+    /// attached to this OS thread, rather than replacing it (which would
+    /// leak it). Use [`attach_or_current()`](Self::attach_or_current) to
+    /// reuse it instead.
+    ///
     /// Returns an error if thread creation fails.
     ///
+    /// Nothing is left behind on error. [`count()`](Self::count) is
+    /// unchanged, and a library initialized by this call is finalized again.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -163,9 +284,7 @@ impl SpdkThread {
     /// }
     /// ```
     pub fn current(name: &str) -> Result<Self> {
-        // Initialize thread library if not already done
-        thread_lib_init()?;
-        Self::attach(name)
+        Self::create_with_init(name, thread_lib_init)
     }
 
     /// Attach an SPDK thread context to the current OS thread.
@@ -176,24 +295,112 @@ impl SpdkThread {
     ///
     /// # Errors
     ///
-    /// Returns an error if thread creation fails.
+    /// Returns [`Error::NulError`] or [`Error::Config`] for an invalid name,
+    /// like [`current()`](Self::current). Returns [`Error::AlreadyAttached`]
+    /// if an SPDK thread is already attached to this OS thread, or an error
+    /// if thread creation fails.
     pub fn attach(name: &str) -> Result<Self> {
-        let name_cstr = CString::new(name)?;
+        Self::create(&thread_name(name)?)
+    }
 
-        let ptr = unsafe { spdk_thread_create(name_cstr.as_ptr(), std::ptr::null()) };
+    /// Reuse the SPDK thread attached to this OS thread, or attach a new one
+    /// like [`current()`](Self::current) if there is none.
+    ///
+    /// Returned for an existing thread (e.g. inside an
+    /// [`SpdkApp`](crate::SpdkApp) callback), the value does not own it:
+    /// dropping it leaves the thread attached and running, and it must not
+    /// be used after the thread's owner has destroyed it.
+    ///
+    /// # Errors
+    ///
+    /// As for [`current()`](Self::current) when a new thread is attached.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::SpdkThread;
+    ///
+    /// let thread = SpdkThread::current("main").unwrap();
+    ///
+    /// // Library code that needs a thread, whether or not one exists
+    /// let same = SpdkThread::attach_or_current("helper").unwrap();
+    /// assert_eq!(same.id(), thread.id());
+    /// ```
+    pub fn attach_or_current(name: &str) -> Result<Self> {
+        match NonNull::new(unsafe { spdk_get_thread() }) {
+            Some(ptr) => Ok(Self {
+                ptr,
+                owned: false,
+                pollers: PollerRegistry::default(),
+                _marker: PhantomData,
+            }),
+            None => Self::current(name),
+        }
+    }
+
+    /// Create a thread, initializing the thread library with `init` first.
+    ///
+    /// The name is validated before `init` runs. If this call initialized
+    /// the library and the thread cannot be created, the library is
+    /// finalized again, so a failed first call leaves nothing behind.
+    fn create_with_init(name: &str, init: impl FnOnce() -> Result<()>) -> Result<Self> {
+        Self::create_with_init_then(name, init, |_| Ok(()))
+    }
+
+    /// [`create_with_init()`](Self::create_with_init) followed by the
+    /// fallible `setup` step of [`create_then()`](Self::create_then).
+    fn create_with_init_then(
+        name: &str,
+        init: impl FnOnce() -> Result<()>,
+        setup: impl FnOnce(&Self) -> Result<()>,
+    ) -> Result<Self> {
+        let name = thread_name(name)?;
+        let initialized_here = !THREAD_LIB_INITIALIZED.load(Ordering::SeqCst);
+        init()?;
+
+        Self::create_then(&name, setup).inspect_err(|_| {
+            if initialized_here && Self::count() == 0 {
+                thread_lib_fini();
+            }
+        })
+    }
+
+    /// Create an SPDK thread and make it current on this OS thread.
+    fn create(name: &CStr) -> Result<Self> {
+        Self::create_then(name, |_| Ok(()))
+    }
+
+    /// Create an SPDK thread, make it current, then run `setup` on it.
+    ///
+    /// The thread is owned by the returned value as soon as
+    /// `spdk_thread_create` succeeds, so if `setup` (or any later step)
+    /// fails, the thread is dropped and [`Drop`] exits and destroys it
+    /// before the error is returned.
+    fn create_then(name: &CStr, setup: impl FnOnce(&Self) -> Result<()>) -> Result<Self> {
+        // spdk_set_thread() below would replace (and leak) an attached thread
+        if !unsafe { spdk_get_thread() }.is_null() {
+            return Err(Error::AlreadyAttached);
+        }
+
+        let ptr = unsafe { spdk_thread_create(name.as_ptr(), std::ptr::null()) };
 
         let ptr = NonNull::new(ptr)
             .ok_or_else(|| Error::EnvInit("spdk_thread_create returned NULL".to_string()))?;
+        let thread = Self {
+            ptr,
+            owned: true,
+            pollers: PollerRegistry::default(),
+            _marker: PhantomData,
+        };
+        track_thread(ptr);
 
         // Set as current thread for this OS thread
         unsafe {
             spdk_set_thread(ptr.as_ptr());
         }
 
-        Ok(Self {
-            ptr,
-            _marker: PhantomData,
-        })
+        setup(&thread)?;
+        Ok(thread)
     }
 
     /// Alias for [`current`](Self::current) - creates an SPDK thread on current OS thread.
@@ -214,27 +421,10 @@ impl SpdkThread {
     ///
     /// # Errors
     ///
-    /// Returns an error if thread creation fails.
+    /// Returns an error if thread creation fails, cleaning up like
+    /// [`current()`](Self::current).
     pub fn current_with_mempool_size(name: &str, msg_mempool_size: usize) -> Result<Self> {
-        // Initialize thread library if needed (with custom mempool size)
-        thread_lib_init_ext(msg_mempool_size)?;
-
-        let name_cstr = CString::new(name)?;
-
-        let ptr = unsafe { spdk_thread_create(name_cstr.as_ptr(), std::ptr::null()) };
-
-        let ptr = NonNull::new(ptr)
-            .ok_or_else(|| Error::EnvInit("spdk_thread_create returned NULL".to_string()))?;
-
-        // Set as current thread for this OS thread
-        unsafe {
-            spdk_set_thread(ptr.as_ptr());
-        }
-
-        Ok(Self {
-            ptr,
-            _marker: PhantomData,
-        })
+        Self::create_with_init(name, || thread_lib_init_ext(msg_mempool_size))
     }
 
     /// Alias for [`current_with_mempool_size`](Self::current_with_mempool_size).
@@ -254,15 +444,29 @@ impl SpdkThread {
         })
     }
 
-    /// Get the app thread (first thread created).
+    /// Get a handle to the app thread (first thread created).
+    ///
+    /// The app thread lives until the thread library is finalized, so the
+    /// returned [`ThreadHandle`] can be sent to worker OS threads to "run on
+    /// the main reactor" via [`ThreadHandle::send()`]. Messages only run when
+    /// the app thread is polled by its own OS thread (the main reactor under
+    /// [`SpdkApp`](crate::SpdkApp), or whoever owns the first [`SpdkThread`]).
     ///
     /// Returns `None` if no threads have been created.
-    pub fn app_thread() -> Option<CurrentThread> {
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use spdk_io::SpdkThread;
+    ///
+    /// let app = SpdkThread::app_thread().expect("thread library not initialized");
+    /// std::thread::spawn(move || {
+    ///     app.send(|| println!("running on the app thread"));
+    /// });
+    /// ```
+    pub fn app_thread() -> Option<ThreadHandle> {
         let ptr = unsafe { spdk_thread_get_app_thread() };
-        NonNull::new(ptr).map(|ptr| CurrentThread {
-            ptr,
-            _marker: PhantomData,
-        })
+        (!ptr.is_null()).then_some(ThreadHandle { ptr })
     }
 
     /// Poll the thread to process messages and run pollers.
@@ -284,17 +488,115 @@ impl SpdkThread {
     /// }
     /// # }
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Polling from inside a poll (e.g. from a poller or message callback)
+    /// corrupts SPDK's state. Debug builds panic; release builds return 0
+    /// without polling. Use [`poll_checked()`](Self::poll_checked) to get an
+    /// error instead.
     pub fn poll(&self) -> i32 {
-        unsafe { spdk_thread_poll(self.ptr.as_ptr(), 0, 0) }
+        poll_unchecked(self.ptr.as_ptr(), 0)
+    }
+
+    /// Poll the thread, failing if called from inside another poll.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ReentrantPoll`] if this OS thread is already inside
+    /// [`poll()`](Self::poll), [`poll_max()`](Self::poll_max) or
+    /// `poll_checked()`.
+    pub fn poll_checked(&self) -> Result<i32> {
+        poll_checked(self.ptr.as_ptr(), 0)
     }
 
     /// Poll with a maximum number of messages to process.
     ///
+    /// Reentrant calls are handled like [`poll()`](Self::poll).
+    ///
     /// # Arguments
     ///
     /// * `max_msgs` - Maximum messages to process (0 = unlimited)
     pub fn poll_max(&self, max_msgs: u32) -> i32 {
-        unsafe { spdk_thread_poll(self.ptr.as_ptr(), max_msgs, 0) }
+        poll_unchecked(self.ptr.as_ptr(), max_msgs)
+    }
+
+    /// Run `future` to completion, polling this thread while it is pending.
+    ///
+    /// The smallest way to await an SPDK operation from `main` without an
+    /// executor: each iteration polls `future` with a no-op waker, then
+    /// [`poll()`](Self::poll)s the thread so messages, pollers and I/O
+    /// completions fire, yielding the OS thread when there was no work.
+    /// Use [`Reactor::block_on()`](crate::Reactor::block_on) to sleep while
+    /// idle or to run other tasks alongside.
+    ///
+    /// Since the waker does nothing, `future` is only re-polled by this
+    /// busy loop: it should wait on SPDK objects owned by this thread
+    /// (completions of I/O submitted here, messages sent to this thread),
+    /// not on work that needs another thread to make progress.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use spdk_io::{BdevDesc, DmaBuf, IoChannel, SpdkThread};
+    /// # fn example(thread: &SpdkThread, desc: &BdevDesc, channel: &IoChannel) -> spdk_io::Result<()> {
+    /// let mut buf = DmaBuf::alloc(512, 512)?;
+    /// thread.block_on(desc.read(channel, &mut buf, 0))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Must not be called from inside a poll (e.g. from a poller or message
+    /// callback); see [`poll()`](Self::poll).
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut future = std::pin::pin!(future);
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            if self.poll() == 0 {
+                thread::yield_now();
+            }
+        }
+    }
+
+    /// Install a callback that runs after every poll on this OS thread.
+    ///
+    /// The callback receives the work count returned by the poll and how
+    /// long it took. It is stored in a thread-local, so it sees polls of any
+    /// SPDK thread (including [`CurrentThread`]) on this OS thread, and
+    /// replaces any previous observer. Reentrant polls that are rejected are
+    /// not reported.
+    ///
+    /// Without an observer, polling does not read the clock.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use spdk_io::SpdkThread;
+    /// # fn example(thread: &SpdkThread) {
+    /// thread.set_poll_observer(|work, elapsed| {
+    ///     if elapsed.as_micros() > 100 {
+    ///         eprintln!("slow poll: {} events in {:?}", work, elapsed);
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn set_poll_observer<F>(&self, f: F)
+    where
+        F: FnMut(i32, Duration) + 'static,
+    {
+        POLL_OBSERVER.set(Some(Box::new(f)));
+    }
+
+    /// Remove the observer installed by
+    /// [`set_poll_observer()`](Self::set_poll_observer).
+    pub fn clear_poll_observer(&self) {
+        POLL_OBSERVER.set(None);
     }
 
     /// Check if the thread has active pollers.
@@ -307,17 +609,51 @@ impl SpdkThread {
         unsafe { spdk_thread_has_pollers(self.ptr.as_ptr()) }
     }
 
+    /// Microseconds until the next timed poller on this thread is due.
+    ///
+    /// An idle loop can sleep this long without delaying a timed poller.
+    /// Returns `Some(0)` if one is already overdue and `None` if the thread
+    /// has no timed pollers (active pollers run on every poll and are not
+    /// counted).
+    pub fn next_poller_expiration_us(&self) -> Option<u64> {
+        next_poller_expiration_us(self.ptr.as_ptr())
+    }
+
     /// Check if the thread is idle (no work pending).
     pub fn is_idle(&self) -> bool {
         unsafe { spdk_thread_is_idle(self.ptr.as_ptr()) }
     }
 
+    /// Get the cores this thread may be scheduled on.
+    pub fn cpumask(&self) -> CpuSet {
+        cpumask(self.ptr.as_ptr())
+    }
+
+    /// Restrict the cores this thread may be scheduled on.
+    ///
+    /// Rescheduling is up to the framework that created the thread: the
+    /// event framework ([`SpdkApp`](crate::SpdkApp)) moves the thread to a
+    /// reactor in `cpus`. Threads created without it cannot be rescheduled,
+    /// since nothing but their owner polls them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if this thread is not the one
+    /// attached to the calling OS thread, or [`Error::Os`] with `ENOTSUP`
+    /// if the thread library cannot reschedule threads.
+    pub fn set_cpumask(&self, cpus: &CpuSet) -> Result<()> {
+        set_cpumask(self.ptr.as_ptr(), cpus)
+    }
+
     /// Check if the thread is running (not exited).
     pub fn is_running(&self) -> bool {
         unsafe { spdk_thread_is_running(self.ptr.as_ptr()) }
     }
 
     /// Get the thread name.
+    ///
+    /// Returns an empty string if the name is not valid UTF-8; use
+    /// [`name_checked()`](Self::name_checked) to detect that.
     pub fn name(&self) -> &str {
         unsafe {
             let ptr = spdk_thread_get_name(self.ptr.as_ptr());
@@ -329,6 +665,11 @@ impl SpdkThread {
         }
     }
 
+    /// Get the thread name, failing if it is not valid UTF-8.
+    pub fn name_checked(&self) -> Result<&str> {
+        unsafe { c_str_checked(spdk_thread_get_name(self.ptr.as_ptr())) }
+    }
+
     /// Get the thread ID.
     pub fn id(&self) -> u64 {
         unsafe { spdk_thread_get_id(self.ptr.as_ptr()) }
@@ -415,10 +756,163 @@ impl SpdkThread {
             ptr: self.ptr.as_ptr(),
         }
     }
+
+    /// Get a [`Messenger`] that can only send messages to this thread.
+    ///
+    /// A thread this value does not own (see
+    /// [`attach_or_current()`](Self::attach_or_current)) is watched from here
+    /// on, so the messenger notices when its owner exits it.
+    pub fn messenger(&self) -> Messenger {
+        if !self.owned {
+            watch_current_thread();
+        }
+        Messenger {
+            id: unsafe { spdk_thread_get_id(self.ptr.as_ptr()) },
+        }
+    }
+
+    /// Send a closure to run on this thread the next time it is polled.
+    ///
+    /// Useful for deferring work out of a callback; use [`handle()`](Self::handle)
+    /// or [`messenger()`](Self::messenger) to message this thread from other
+    /// OS threads.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be queued (message pool
+    /// exhausted, or the thread is exiting), see [`Messenger::send_msg()`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use spdk_io::SpdkThread;
+    /// # fn example(thread: &SpdkThread) -> spdk_io::Result<()> {
+    /// thread.send_msg(|| println!("runs on the next poll"))?;
+    /// thread.poll();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn send_msg<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.messenger().send_msg(f)
+    }
+
+    /// Execute a closure on this thread and block until it has run.
+    ///
+    /// See [`ThreadHandle::exec_msg()`] for details, errors and deadlock
+    /// caveats.
+    pub fn exec_msg<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce() + Send,
+    {
+        self.handle().exec_msg(f)
+    }
+
+    /// Register `f` as a poller that runs on every [`poll()`](Self::poll).
+    ///
+    /// `f` returns the amount of work it did, like [`poll()`](Self::poll):
+    /// a positive value reports the poller busy, zero or less idle.
+    ///
+    /// The thread keeps the poller, so there is no handle to hold: it runs
+    /// until [`unregister_poller()`](Self::unregister_poller) or until this
+    /// `SpdkThread` is dropped, which unregisters the remaining pollers in
+    /// reverse registration order and drops their closures.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if this thread is not the one
+    /// attached to the calling OS thread, or [`Error::MemoryAlloc`] if SPDK
+    /// could not register the poller.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use spdk_io::SpdkThread;
+    /// # fn example(thread: &SpdkThread) -> spdk_io::Result<()> {
+    /// let mut ticks = 0;
+    /// let id = thread.register_timed_poller(
+    ///     move || {
+    ///         ticks += 1;
+    ///         1
+    ///     },
+    ///     1000,
+    /// )?;
+    ///
+    /// thread.poll();
+    /// thread.unregister_poller(id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_poller<F>(&self, f: F) -> Result<PollerId>
+    where
+        F: FnMut() -> i32 + 'static,
+    {
+        self.pollers.register(self.ptr.as_ptr(), f, 0)
+    }
+
+    /// Register `f` as a poller that runs at most once every `period_us`
+    /// microseconds.
+    ///
+    /// See [`register_poller()`](Self::register_poller); a `period_us` of 0
+    /// is the same as an untimed poller.
+    pub fn register_timed_poller<F>(&self, f: F, period_us: u64) -> Result<PollerId>
+    where
+        F: FnMut() -> i32 + 'static,
+    {
+        self.pollers.register(self.ptr.as_ptr(), f, period_us)
+    }
+
+    /// Unregister the poller `id` and drop its closure.
+    ///
+    /// May be called from inside a poller, including the one being
+    /// unregistered. Returns `false` if `id` is not registered with this
+    /// `SpdkThread`.
+    pub fn unregister_poller(&self, id: PollerId) -> bool {
+        self.pollers.unregister(id)
+    }
+
+    /// Stop running the poller `id` until
+    /// [`resume_poller()`](Self::resume_poller).
+    ///
+    /// Takes effect on the next poll; pausing an already paused poller does
+    /// nothing. Returns `false` if `id` is not registered.
+    pub fn pause_poller(&self, id: PollerId) -> bool {
+        self.pollers
+            .get(id)
+            .map(|poller| unsafe { spdk_poller_pause(poller) })
+            .is_some()
+    }
+
+    /// Run the poller `id` again after [`pause_poller()`](Self::pause_poller).
+    ///
+    /// Returns `false` if `id` is not registered.
+    pub fn resume_poller(&self, id: PollerId) -> bool {
+        self.pollers
+            .get(id)
+            .map(|poller| unsafe { spdk_poller_resume(poller) })
+            .is_some()
+    }
+
+    /// Number of pollers registered through this `SpdkThread`.
+    pub fn poller_count(&self) -> usize {
+        self.pollers.len()
+    }
 }
 
 impl Drop for SpdkThread {
     fn drop(&mut self) {
+        // Pollers must be unregistered on their thread, before it exits
+        self.pollers.clear();
+
+        if !self.owned {
+            return;
+        }
+
+        // Before exiting, so no messenger sends to the thread once it is gone
+        untrack_thread(self.id());
+
         unsafe {
             // Request thread exit
             spdk_thread_exit(self.ptr.as_ptr());
@@ -467,11 +961,33 @@ impl CurrentThread {
     }
 
     /// Poll the thread.
+    ///
+    /// Reentrant calls are handled like [`SpdkThread::poll()`].
     pub fn poll(&self) -> i32 {
-        unsafe { spdk_thread_poll(self.ptr.as_ptr(), 0, 0) }
+        poll_unchecked(self.ptr.as_ptr(), 0)
+    }
+
+    /// Microseconds until the next timed poller is due, see
+    /// [`SpdkThread::next_poller_expiration_us()`].
+    pub fn next_poller_expiration_us(&self) -> Option<u64> {
+        next_poller_expiration_us(self.ptr.as_ptr())
+    }
+
+    /// Get the cores this thread may be scheduled on.
+    pub fn cpumask(&self) -> CpuSet {
+        cpumask(self.ptr.as_ptr())
+    }
+
+    /// Restrict the cores this thread may be scheduled on, see
+    /// [`SpdkThread::set_cpumask()`].
+    pub fn set_cpumask(&self, cpus: &CpuSet) -> Result<()> {
+        set_cpumask(self.ptr.as_ptr(), cpus)
     }
 
     /// Get the thread name.
+    ///
+    /// Returns an empty string if the name is not valid UTF-8; use
+    /// [`name_checked()`](Self::name_checked) to detect that.
     pub fn name(&self) -> &str {
         unsafe {
             let ptr = spdk_thread_get_name(self.ptr.as_ptr());
@@ -483,6 +999,11 @@ impl CurrentThread {
         }
     }
 
+    /// Get the thread name, failing if it is not valid UTF-8.
+    pub fn name_checked(&self) -> Result<&str> {
+        unsafe { c_str_checked(spdk_thread_get_name(self.ptr.as_ptr())) }
+    }
+
     /// Get the thread ID.
     pub fn id(&self) -> u64 {
         unsafe { spdk_thread_get_id(self.ptr.as_ptr()) }
@@ -596,6 +1117,75 @@ impl ThreadHandle {
         rx
     }
 
+    /// Execute a closure on the target thread and block until it has run.
+    ///
+    /// If the calling OS thread is currently running the target SPDK thread,
+    /// the closure runs inline. Otherwise it is sent with
+    /// `spdk_thread_send_msg()` and the caller spins, polling its own SPDK
+    /// thread (if any) so that messages addressed to it keep flowing, until
+    /// the closure has completed.
+    ///
+    /// Because the caller blocks, the closure may borrow from the caller's
+    /// stack.
+    ///
+    /// # Deadlock
+    ///
+    /// The target thread must be polled by some other OS thread while the
+    /// caller waits. If two threads `exec_msg()` each other at the same time
+    /// they both keep polling, so that case makes progress, but if the target
+    /// is itself blocked (e.g. in its own `exec_msg()` to a third thread that
+    /// is waiting on the caller, or in a plain `join()`), this never returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ReentrantPoll`] if called from a poller or message on
+    /// another SPDK thread, since waiting would poll that thread reentrantly.
+    /// Returns an error if the message could not be queued. The closure is
+    /// dropped without running in both cases.
+    pub fn exec_msg<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce() + Send,
+    {
+        let current = unsafe { spdk_get_thread() };
+        if current == self.ptr {
+            f();
+            return Ok(());
+        }
+        // Checked before sending: once the message is queued we cannot
+        // return until it has run
+        if !current.is_null() && IN_POLL.get() {
+            return Err(Error::ReentrantPoll);
+        }
+
+        let done = AtomicBool::new(false);
+        let done_ref = &done;
+        let task: Box<dyn FnOnce() + Send + '_> = Box::new(move || {
+            f();
+            done_ref.store(true, Ordering::Release);
+        });
+        // SAFETY: we do not return until the closure has run and set `done`,
+        // so everything it borrows outlives its execution.
+        let task: Box<dyn FnOnce() + Send + 'static> = unsafe { std::mem::transmute(task) };
+        let ctx = Box::into_raw(Box::new(task)) as *mut c_void;
+
+        let rc = unsafe { spdk_thread_send_msg(self.ptr, Some(msg_callback), ctx) };
+        if rc != 0 {
+            // SAFETY: the message was not queued, so the callback won't run
+            drop(unsafe { Box::from_raw(ctx as *mut Box<dyn FnOnce() + Send>) });
+            return Err(Error::from_rc(rc));
+        }
+
+        while !done.load(Ordering::Acquire) {
+            if current.is_null() {
+                std::hint::spin_loop();
+            } else {
+                // Not inside a poll (checked above), so this cannot reenter
+                poll_unchecked(current, 0);
+            }
+        }
+        Ok(())
+    }
+
     /// Get the target thread's ID.
     pub fn id(&self) -> u64 {
         unsafe { spdk_thread_get_id(self.ptr) }
@@ -607,6 +1197,234 @@ impl ThreadHandle {
     }
 }
 
+/// SPDK threads a [`Messenger`] may send to, by id.
+///
+/// Senders look their thread up and send while holding the read lock, and
+/// an entry is removed under the write lock before its thread is destroyed,
+/// so a messenger never touches a freed `spdk_thread`.
+static LIVE_THREADS: RwLock<BTreeMap<u64, LiveThread>> = RwLock::new(BTreeMap::new());
+
+/// How often a watched thread checks whether it is exiting.
+const EXIT_WATCH_PERIOD_US: u64 = 100_000;
+
+struct LiveThread(NonNull<spdk_thread>);
+
+// SAFETY: only passed to spdk_thread_send_msg(), which is thread-safe, while
+// the LIVE_THREADS lock keeps the thread from being destroyed
+unsafe impl Send for LiveThread {}
+unsafe impl Sync for LiveThread {}
+
+/// Track a thread created by [`SpdkThread`]; its `Drop` untracks it.
+fn track_thread(ptr: NonNull<spdk_thread>) {
+    let id = unsafe { spdk_thread_get_id(ptr.as_ptr()) };
+    LIVE_THREADS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(id, LiveThread(ptr));
+}
+
+fn untrack_thread(id: u64) {
+    LIVE_THREADS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&id);
+}
+
+/// Track the current thread, which some other code owns and destroys (e.g.
+/// an event framework reactor thread).
+///
+/// A timed poller untracks it once the thread is exiting. SPDK does not
+/// finish the exit while the poller is registered, so the entry is gone
+/// before the thread is destroyed. Does nothing for a tracked thread.
+fn watch_current_thread() {
+    let Some(ptr) = NonNull::new(unsafe { spdk_get_thread() }) else {
+        return;
+    };
+    let id = unsafe { spdk_thread_get_id(ptr.as_ptr()) };
+    let mut threads = LIVE_THREADS.write().unwrap_or_else(PoisonError::into_inner);
+    if threads.contains_key(&id) {
+        return;
+    }
+
+    // The poller unregisters itself through this slot
+    let slot = Box::into_raw(Box::new(std::ptr::null_mut::<spdk_poller>()));
+    let poller = unsafe {
+        spdk_poller_register(
+            Some(exit_watch_poller),
+            slot as *mut c_void,
+            EXIT_WATCH_PERIOD_US,
+        )
+    };
+    if poller.is_null() {
+        // Untracked, so messages to the thread fail instead of racing its exit
+        drop(unsafe { Box::from_raw(slot) });
+        return;
+    }
+    unsafe { *slot = poller };
+    threads.insert(id, LiveThread(ptr));
+}
+
+/// Id of the current SPDK thread, tracked so that [`send_to_thread()`] can
+/// reach it until it exits. `None` off an SPDK thread.
+#[cfg_attr(not(feature = "bdev"), allow(dead_code))]
+pub(crate) fn current_thread_id() -> Option<u64> {
+    let thread = NonNull::new(unsafe { spdk_get_thread() })?;
+    watch_current_thread();
+    Some(unsafe { spdk_thread_get_id(thread.as_ptr()) })
+}
+
+/// Poller of [`watch_current_thread()`].
+unsafe extern "C" fn exit_watch_poller(ctx: *mut c_void) -> i32 {
+    let thread = unsafe { spdk_get_thread() };
+    if unsafe { spdk_thread_is_exiting(thread) } {
+        untrack_thread(unsafe { spdk_thread_get_id(thread) });
+        let slot = ctx as *mut *mut spdk_poller;
+        unsafe {
+            spdk_poller_unregister(slot);
+            drop(Box::from_raw(slot));
+        }
+    }
+    spdk_thread_poller_rc_SPDK_POLLER_IDLE as i32
+}
+
+/// Queue `func(ctx)` on the thread `id` if it is still tracked.
+///
+/// On error the message was not queued and `ctx` still belongs to the caller.
+pub(crate) fn send_to_thread(id: u64, func: spdk_msg_fn, ctx: *mut c_void) -> Result<()> {
+    let threads = LIVE_THREADS.read().unwrap_or_else(PoisonError::into_inner);
+    let thread = threads.get(&id).ok_or(Error::ThreadExited)?;
+    let rc = unsafe { spdk_thread_send_msg(thread.0.as_ptr(), func, ctx) };
+    if rc != 0 {
+        return Err(Error::from_rc(rc));
+    }
+    Ok(())
+}
+
+/// Send-only handle to an SPDK thread.
+///
+/// A narrower [`ThreadHandle`]: it can send messages and report the thread
+/// ID, nothing else, so it is safe to hand to any component that only needs
+/// to post work back to its owner (e.g. many producers fanning in to one
+/// SPDK thread). Created with [`SpdkThread::messenger()`].
+///
+/// The messenger refers to its thread by id and may outlive it: once the
+/// thread is exiting or destroyed, sends return [`Error::ThreadExited`].
+/// Threads created with [`SpdkThread`] are reachable until the `SpdkThread`
+/// is dropped; other threads (e.g. those of [`SpdkApp`](crate::SpdkApp))
+/// from the first [`messenger()`](SpdkThread::messenger) call on them until
+/// they start exiting.
+///
+/// # Example
+///
+/// ```no_run
+/// use spdk_io::SpdkThread;
+///
+/// let thread = SpdkThread::new("owner").unwrap();
+/// let messenger = thread.messenger();
+///
+/// std::thread::spawn(move || {
+///     messenger.send_msg(|| println!("back on the owner")).unwrap();
+/// });
+/// ```
+#[derive(Clone, Copy)]
+pub struct Messenger {
+    id: u64,
+}
+
+impl Messenger {
+    /// Send a closure to run on the target thread the next time it is polled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ThreadExited`] if the target thread is exiting or
+    /// gone, or an error if the message could not be queued (message pool
+    /// exhausted). The closure is dropped without running.
+    pub fn send_msg<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let boxed: Box<Box<dyn FnOnce() + Send>> = Box::new(Box::new(f));
+        let ctx = Box::into_raw(boxed) as *mut c_void;
+
+        send_to_thread(self.id, Some(msg_callback), ctx).inspect_err(|_| {
+            // SAFETY: the message was not queued, so the callback won't run
+            drop(unsafe { Box::from_raw(ctx as *mut Box<dyn FnOnce() + Send>) });
+        })
+    }
+
+    /// Get the target thread's ID.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// Look up an SPDK thread by its [`id()`](SpdkThread::id).
+///
+/// Returns a [`Messenger`] for the thread, so an id that crossed a process
+/// or RPC boundary can be turned back into something to send work to.
+/// Returns `None` if no thread reachable by messengers (see [`Messenger`])
+/// has the id, e.g. because it has exited. The thread may still exit after
+/// the lookup, in which case sends return [`Error::ThreadExited`].
+///
+/// # Example
+///
+/// ```no_run
+/// use spdk_io::SpdkThread;
+/// use spdk_io::thread::get_by_id;
+///
+/// let thread = SpdkThread::new("owner").unwrap();
+/// let id = thread.id();
+///
+/// std::thread::spawn(move || {
+///     let messenger = get_by_id(id).expect("thread gone");
+///     messenger.send_msg(|| println!("routed by id")).unwrap();
+/// });
+/// ```
+pub fn get_by_id(id: u64) -> Option<Messenger> {
+    LIVE_THREADS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains_key(&id)
+        .then_some(Messenger { id })
+}
+
+/// Convert `spdk_thread_next_poller_expiration()` (absolute ticks, 0 for
+/// none) to microseconds from now.
+fn next_poller_expiration_us(thread: *mut spdk_thread) -> Option<u64> {
+    let expiration = unsafe { spdk_thread_next_poller_expiration(thread) };
+    if expiration == 0 {
+        return None;
+    }
+    let now = unsafe { spdk_get_ticks() };
+    let hz = unsafe { spdk_get_ticks_hz() };
+    let ticks = expiration.saturating_sub(now) as u128;
+    Some((ticks * 1_000_000 / hz as u128) as u64)
+}
+
+/// Copy of `thread`'s cpumask.
+fn cpumask(thread: *mut spdk_thread) -> CpuSet {
+    unsafe { CpuSet::from_raw(spdk_thread_get_cpumask(thread)) }
+}
+
+/// `spdk_thread_set_cpumask()` acts on the calling OS thread's SPDK thread,
+/// so `thread` must be that one.
+fn set_cpumask(thread: *mut spdk_thread, cpus: &CpuSet) -> Result<()> {
+    if unsafe { spdk_get_thread() } != thread {
+        return Err(Error::InvalidArgument(
+            "set_cpumask called for a thread not attached to this OS thread".to_string(),
+        ));
+    }
+    if !RESCHEDULE_SUPPORTED.load(Ordering::SeqCst) {
+        return Err(Error::Os(libc::ENOTSUP));
+    }
+    // SPDK copies the mask; the pointer is only non-const in its signature
+    let rc = unsafe { spdk_thread_set_cpumask(cpus.as_ptr() as *mut spdk_cpuset) };
+    if rc != 0 {
+        return Err(Error::from_rc(rc));
+    }
+    Ok(())
+}
+
 /// Callback for spdk_thread_send_msg
 unsafe extern "C" fn msg_callback(ctx: *mut c_void) {
     // Reconstruct the boxed closure
@@ -614,3 +1432,58 @@ unsafe extern "C" fn msg_callback(ctx: *mut c_void) {
     // Call the closure
     boxed();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_name_length() {
+        assert!(thread_name("worker").is_ok());
+        assert!(thread_name(&"w".repeat(MAX_THREAD_NAME_LEN)).is_ok());
+
+        let long = "w".repeat(MAX_THREAD_NAME_LEN + 1);
+        assert!(matches!(thread_name(&long), Err(Error::Config(_))));
+
+        assert!(matches!(thread_name("bad\0name"), Err(Error::NulError(_))));
+    }
+
+    #[test]
+    #[ignore] // Requires hugepages
+    fn test_failed_setup_after_thread_create_cleans_up() -> Result<()> {
+        let _env = crate::SpdkEnv::builder()
+            .name("test_thread_create_fail")
+            .no_pci(true)
+            .no_huge(true)
+            .mem_size_mb(256)
+            .build()?;
+
+        // First thread: the library initialized by the call is finalized again
+        let before = SpdkThread::count();
+        let mut setup_ran = false;
+        let err = SpdkThread::create_with_init_then(
+            "worker",
+            || thread_lib_init_ext(SMALL_MSG_MEMPOOL_SIZE),
+            |thread| {
+                // The thread exists and is current when setup runs
+                setup_ran = true;
+                assert_eq!(SpdkThread::count(), before + 1);
+                assert_eq!(unsafe { spdk_get_thread() }, thread.ptr.as_ptr());
+                Err(Error::EnvInit("setup failed".to_string()))
+            },
+        );
+        assert!(setup_ran);
+        assert!(matches!(err, Err(Error::EnvInit(_))));
+        assert_eq!(SpdkThread::count(), before);
+        assert!(SpdkThread::get_current().is_none());
+        assert!(!THREAD_LIB_INITIALIZED.load(Ordering::SeqCst));
+
+        // The half-created thread is gone, so a retry attaches normally
+        let thread = SpdkThread::current_with_mempool_size("worker", SMALL_MSG_MEMPOOL_SIZE)?;
+        assert_eq!(SpdkThread::count(), before + 1);
+        drop(thread);
+        assert_eq!(SpdkThread::count(), before);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+//! Integration test for accel copy offload
+
+use spdk_io::{DmaBuf, Error, Result, SpdkApp, accel, block_on};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_accel_copy_offloaded() -> Result<()> {
+    SpdkApp::builder().name("test_accel").no_pci(true).run(|| {
+        const LEN: usize = 1 << 20;
+
+        let mut src = DmaBuf::alloc(LEN, 4096).expect("Failed to allocate DmaBuf");
+        for (i, byte) in src.as_mut_slice().iter_mut().enumerate() {
+            *byte = (i % 251) as u8;
+        }
+        let dst = DmaBuf::alloc_zeroed(LEN, 4096).expect("Failed to allocate DmaBuf");
+
+        let (dst, src) = block_on(accel::copy_offloaded(dst, src)).expect("Copy failed");
+        assert_eq!(dst.as_slice(), src.as_slice());
+
+        // Length mismatch is rejected before submission
+        let short = DmaBuf::alloc(4096, 4096).expect("Failed to allocate DmaBuf");
+        let err = block_on(accel::copy_offloaded(short, src)).unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidArgument(_)),
+            "unexpected error: {err}"
+        );
+
+        SpdkApp::stop();
+    })
+}
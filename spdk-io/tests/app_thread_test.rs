@@ -0,0 +1,36 @@
+//! Integration test for messaging the app thread from worker OS threads
+
+use spdk_io::{Result, SpdkEnv, SpdkThread};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_send_to_app_thread_from_worker() -> Result<()> {
+    let _env = SpdkEnv::builder().name("test_app_thread").build()?;
+
+    // The first SPDK thread created becomes the app thread
+    let thread = SpdkThread::new("app")?;
+    let app = SpdkThread::app_thread().expect("App thread should exist");
+    assert_eq!(app.id(), thread.id());
+
+    let ran_on = Arc::new(AtomicU64::new(0));
+    let ran_on_clone = ran_on.clone();
+    std::thread::spawn(move || {
+        app.send(move || {
+            let current = SpdkThread::get_current().expect("Not on an SPDK thread");
+            ran_on_clone.store(current.id(), Ordering::SeqCst);
+        });
+    })
+    .join()
+    .unwrap();
+
+    // The message only runs once the app thread polls
+    assert_eq!(ran_on.load(Ordering::SeqCst), 0);
+    while ran_on.load(Ordering::SeqCst) == 0 {
+        thread.poll();
+    }
+    assert_eq!(ran_on.load(Ordering::SeqCst), thread.id());
+
+    Ok(())
+}
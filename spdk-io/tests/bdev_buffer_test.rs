@@ -0,0 +1,50 @@
+//! Integration test for bdev I/O buffer size validation
+
+use spdk_io::{Bdev, DmaBuf, Error, Result, SpdkApp, block_on};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_bdev_io_rejects_undersized_buffer() -> Result<()> {
+    let config = r#"{
+        "subsystems": [{
+            "subsystem": "bdev",
+            "config": [{
+                "method": "bdev_null_create",
+                "params": {
+                    "name": "Null0",
+                    "num_blocks": 1024,
+                    "block_size": 4096
+                }
+            }]
+        }]
+    }"#;
+
+    SpdkApp::builder()
+        .name("test_bdev_buffer")
+        .json_data(config)
+        .no_pci(true)
+        .run(|| {
+            let bdev = Bdev::get_by_name("Null0").expect("Bdev 'Null0' not found");
+            let desc = bdev.open(true).expect("Failed to open bdev");
+            let channel = desc.get_io_channel().expect("Failed to get I/O channel");
+
+            // Half a block: must be rejected before reaching SPDK
+            let mut buf = DmaBuf::alloc_zeroed(2048, 4096).expect("Failed to allocate DmaBuf");
+            let err = block_on(desc.read(&channel, &mut buf, 0)).unwrap_err();
+            assert!(matches!(err, Error::Config(_)), "unexpected error: {err}");
+            let err = block_on(desc.write(&channel, &buf, 0)).unwrap_err();
+            assert!(matches!(err, Error::Config(_)), "unexpected error: {err}");
+
+            // Whole block at a misaligned offset
+            let mut buf = DmaBuf::alloc_zeroed(4096, 4096).expect("Failed to allocate DmaBuf");
+            let err = block_on(desc.read(&channel, &mut buf, 512)).unwrap_err();
+            assert!(matches!(err, Error::Config(_)), "unexpected error: {err}");
+
+            // Correctly sized I/O still succeeds
+            block_on(desc.read(&channel, &mut buf, 4096)).expect("Read failed");
+
+            drop(channel);
+            drop(desc);
+            SpdkApp::stop();
+        })
+}
@@ -0,0 +1,52 @@
+//! Integration test for claiming a bdev through `BdevDesc::claim()`
+
+use spdk_io::{Bdev, Error, Result, SpdkApp};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_claim_bdev() -> Result<()> {
+    let config = r#"{
+        "subsystems": [{
+            "subsystem": "bdev",
+            "config": [{
+                "method": "bdev_malloc_create",
+                "params": {
+                    "name": "Malloc0",
+                    "num_blocks": 256,
+                    "block_size": 512
+                }
+            }]
+        }]
+    }"#;
+
+    SpdkApp::builder()
+        .name("test_claim")
+        .json_data(config)
+        .no_pci(true)
+        .run(|| {
+            let bdev = Bdev::get_by_name("Malloc0").expect("Bdev 'Malloc0' not found");
+            let writer = bdev.open(false).expect("Failed to open bdev");
+            let other = bdev.open(false).expect("Failed to open bdev");
+
+            let claim = writer.claim(true).expect("First claim failed");
+            assert_eq!(claim.bdev().name(), "Malloc0");
+
+            // A second claim is rejected while the first is held
+            match other.claim(false) {
+                Err(Error::Busy(name)) => assert_eq!(name, "Malloc0"),
+                Err(e) => panic!("Expected Error::Busy, got {}", e),
+                Ok(_) => panic!("Second claim unexpectedly succeeded"),
+            }
+
+            // The claim is exclusive-write: new write opens are rejected
+            assert!(bdev.open(true).is_err());
+
+            // Releasing the claim allows re-claiming and write opens
+            drop(claim);
+            drop(bdev.open(true).expect("Write open after release failed"));
+            let reclaim = other.claim(false).expect("Re-claim after release failed");
+            drop(reclaim);
+
+            SpdkApp::stop();
+        })
+}
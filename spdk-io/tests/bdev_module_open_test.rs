@@ -0,0 +1,90 @@
+//! Integration test for `BdevDesc::open_for_module()`
+//!
+//! A minimal passthrough opens its malloc base bdev for its own module,
+//! claims it and forwards a read to it.
+
+use spdk_io::{Bdev, BdevDesc, BdevModule, DmaBuf, Error, IoChannel, Result, SpdkApp, block_on};
+
+/// Passthrough that owns a claimed base bdev and forwards I/O to it
+struct Passthru {
+    // Dropped before the descriptor it was taken from
+    channel: IoChannel,
+    base: BdevDesc,
+}
+
+impl Passthru {
+    fn new(base_name: &str, module: &BdevModule) -> Result<Self> {
+        let base = BdevDesc::open_for_module(base_name, true, module)?;
+        let channel = base.get_io_channel()?;
+        Ok(Self { channel, base })
+    }
+
+    async fn read(&self, buf: &mut DmaBuf, offset: u64) -> Result<()> {
+        self.base.read(&self.channel, buf, offset).await
+    }
+}
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_open_for_module() -> Result<()> {
+    let config = r#"{
+        "subsystems": [{
+            "subsystem": "bdev",
+            "config": [{
+                "method": "bdev_malloc_create",
+                "params": {
+                    "name": "Malloc0",
+                    "num_blocks": 256,
+                    "block_size": 512
+                }
+            }]
+        }]
+    }"#;
+
+    SpdkApp::builder()
+        .name("test_bdev_module_open")
+        .json_data(config)
+        .no_pci(true)
+        .run(|| {
+            let module = BdevModule::new("passthru_test").expect("Failed to create module");
+            assert_eq!(module.name(), "passthru_test");
+            assert_eq!(BdevModule::new("passthru_test").unwrap(), module);
+
+            // Write a pattern through a plain descriptor before the claim
+            let bdev = Bdev::get_by_name("Malloc0").expect("Bdev 'Malloc0' not found");
+            let writer = bdev.open(true).expect("Failed to open bdev");
+            let channel = writer.get_io_channel().expect("Failed to get channel");
+            let mut buf = DmaBuf::alloc(512, 512).expect("Failed to allocate buffer");
+            buf.as_mut_slice().fill(0x5a);
+            block_on(writer.write(&channel, &buf, 8 * 512)).expect("Write failed");
+            drop(channel);
+            drop(writer);
+
+            let passthru = Passthru::new("Malloc0", &module).expect("Failed to open base");
+            assert_eq!(passthru.base.module(), Some(module));
+            let claim = passthru.base.claim(true).expect("Claim failed");
+
+            // The base is now owned by the passthrough module
+            let other = bdev.open(false).expect("Failed to open bdev");
+            assert!(other.module().is_none());
+            match other.claim(false) {
+                Err(Error::Busy(name)) => assert_eq!(name, "Malloc0"),
+                Err(e) => panic!("Expected Error::Busy, got {}", e),
+                Ok(_) => panic!("Second claim unexpectedly succeeded"),
+            }
+
+            let mut read = DmaBuf::alloc(512, 512).expect("Failed to allocate buffer");
+            block_on(passthru.read(&mut read, 8 * 512)).expect("Forwarded read failed");
+            assert!(read.as_slice().iter().all(|&b| b == 0x5a));
+
+            assert!(matches!(
+                BdevDesc::open_for_module("NoSuchBdev", false, &module),
+                Err(Error::DeviceNotFound(_))
+            ));
+
+            drop(claim);
+            drop(other);
+            drop(passthru);
+            SpdkApp::stop();
+        })
+}
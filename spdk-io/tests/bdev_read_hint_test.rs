@@ -0,0 +1,58 @@
+//! Integration test for bdev reads with access hints
+//!
+//! Issues sequential-hinted reads to a malloc bdev (which ignores the hint,
+//! but must still accept the ext opts) and checks the data.
+
+use spdk_io::{Bdev, DmaBuf, Error, ReadHint, Result, SpdkApp, block_on};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_sequential_hint_read() -> Result<()> {
+    let config = r#"{
+        "subsystems": [{
+            "subsystem": "bdev",
+            "config": [{
+                "method": "bdev_malloc_create",
+                "params": {
+                    "name": "Malloc0",
+                    "num_blocks": 256,
+                    "block_size": 512
+                }
+            }]
+        }]
+    }"#;
+
+    SpdkApp::builder()
+        .name("test_read_hint")
+        .json_data(config)
+        .no_pci(true)
+        .run(|| {
+            let bdev = Bdev::get_by_name("Malloc0").expect("Bdev 'Malloc0' not found");
+            let desc = bdev.open(true).expect("Failed to open bdev");
+            let channel = desc.get_io_channel().expect("Failed to get I/O channel");
+
+            let mut buf = DmaBuf::alloc_zeroed(2048, 512).expect("Failed to allocate DmaBuf");
+            buf.as_mut_slice().fill(0x5a);
+            block_on(desc.write(&channel, &buf, 0)).expect("Write failed");
+
+            let mut read = DmaBuf::alloc_zeroed(1024, 512).expect("Failed to allocate DmaBuf");
+            for offset_blocks in [0, 2] {
+                read.as_mut_slice().fill(0);
+                block_on(desc.read_with_opts(
+                    &channel,
+                    &mut read,
+                    offset_blocks,
+                    2,
+                    ReadHint::Sequential,
+                ))
+                .expect("Sequential-hinted read failed");
+                assert!(read.as_slice().iter().all(|&b| b == 0x5a));
+            }
+
+            // More blocks than the buffer holds is rejected before submission
+            let err = block_on(desc.read_with_opts(&channel, &mut read, 0, 3, ReadHint::Random));
+            assert!(matches!(err, Err(Error::Config(_))));
+
+            SpdkApp::stop();
+        })
+}
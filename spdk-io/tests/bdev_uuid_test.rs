@@ -0,0 +1,63 @@
+//! Integration test for `Bdev::uuid()` and `Bdev::aliases()`
+//!
+//! Creates a malloc bdev with a fixed UUID, adds an alias through SPDK and
+//! reads both back.
+
+use std::ffi::CString;
+
+use spdk_io::{Bdev, Result, SpdkApp};
+use spdk_io_sys::spdk_bdev_alias_add;
+
+const UUID: &str = "6f0b2f5c-1d4e-4b6a-9c3d-2e8f7a1b0c9d";
+const UUID_BYTES: [u8; 16] = [
+    0x6f, 0x0b, 0x2f, 0x5c, 0x1d, 0x4e, 0x4b, 0x6a, 0x9c, 0x3d, 0x2e, 0x8f, 0x7a, 0x1b, 0x0c, 0x9d,
+];
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_bdev_uuid_and_aliases() -> Result<()> {
+    let config = format!(
+        r#"{{
+        "subsystems": [{{
+            "subsystem": "bdev",
+            "config": [{{
+                "method": "bdev_malloc_create",
+                "params": {{
+                    "name": "Malloc0",
+                    "num_blocks": 256,
+                    "block_size": 512,
+                    "uuid": "{}"
+                }}
+            }}]
+        }}]
+    }}"#,
+        UUID
+    );
+
+    SpdkApp::builder()
+        .name("test_bdev_uuid")
+        .json_data(&config)
+        .no_pci(true)
+        .run(|| {
+            let bdev = Bdev::get_by_name("Malloc0").expect("Bdev 'Malloc0' not found");
+
+            let uuid = bdev.uuid().expect("malloc bdev has no UUID");
+            #[cfg(feature = "uuid")]
+            assert_eq!(*uuid.as_bytes(), UUID_BYTES);
+            #[cfg(not(feature = "uuid"))]
+            assert_eq!(uuid, UUID_BYTES);
+            assert!(bdev.aliases().is_empty());
+
+            let alias = CString::new("journal").unwrap();
+            let rc = unsafe { spdk_bdev_alias_add(bdev.as_ptr(), alias.as_ptr()) };
+            assert_eq!(rc, 0);
+            assert_eq!(bdev.aliases(), ["journal"]);
+
+            // The descriptor reports the same identity
+            let desc = bdev.open(false).expect("Failed to open bdev");
+            assert_eq!(desc.uuid(), Some(uuid));
+            assert_eq!(desc.aliases(), ["journal"]);
+
+            SpdkApp::stop();
+        })
+}
@@ -0,0 +1,49 @@
+//! Integration test for bdev writes with per-I/O flags
+//!
+//! Issues an FUA write to a malloc bdev (which ignores the flag, but must
+//! still accept the ext opts) and reads the data back.
+
+use spdk_io::{Bdev, DmaBuf, Error, Result, SpdkApp, WriteFlags, block_on};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_fua_write() -> Result<()> {
+    let config = r#"{
+        "subsystems": [{
+            "subsystem": "bdev",
+            "config": [{
+                "method": "bdev_malloc_create",
+                "params": {
+                    "name": "Malloc0",
+                    "num_blocks": 256,
+                    "block_size": 512
+                }
+            }]
+        }]
+    }"#;
+
+    SpdkApp::builder()
+        .name("test_write_flags")
+        .json_data(config)
+        .no_pci(true)
+        .run(|| {
+            let bdev = Bdev::get_by_name("Malloc0").expect("Bdev 'Malloc0' not found");
+            let desc = bdev.open(true).expect("Failed to open bdev");
+            let channel = desc.get_io_channel().expect("Failed to get I/O channel");
+
+            let mut buf = DmaBuf::alloc_zeroed(1024, 512).expect("Failed to allocate DmaBuf");
+            buf.as_mut_slice().fill(0xc3);
+            block_on(desc.write_with_flags(&channel, &buf, 4, 2, WriteFlags::FUA))
+                .expect("FUA write failed");
+
+            let mut read = DmaBuf::alloc_zeroed(1024, 512).expect("Failed to allocate DmaBuf");
+            block_on(desc.read(&channel, &mut read, 4 * 512)).expect("Read failed");
+            assert!(read.as_slice().iter().all(|&b| b == 0xc3));
+
+            // More blocks than the buffer holds is rejected before submission
+            let err = block_on(desc.write_with_flags(&channel, &buf, 0, 3, WriteFlags::FUA));
+            assert!(matches!(err, Err(Error::Config(_))));
+
+            SpdkApp::stop();
+        })
+}
@@ -0,0 +1,34 @@
+//! Integration test for NUMA-aware DMA buffer pools
+
+use spdk_io::{BufferPool, Result, SpdkEnv};
+
+#[test]
+#[ignore] // Requires hugepages (physical addresses are not available under no_huge)
+fn test_buffer_pool_on_socket() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_buffer_pool")
+        .no_pci(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let mut pool = BufferPool::new_on_socket(4096, 8, 4096, 0)?;
+    assert_eq!(pool.available(), 8);
+    assert_eq!(pool.socket_id(), 0);
+
+    let bufs: Vec<_> = (0..8)
+        .map(|_| pool.get().expect("pool exhausted"))
+        .collect();
+    assert!(pool.get().is_none());
+
+    for buf in &bufs {
+        assert_eq!(buf.len(), 4096);
+        assert!(buf.phys_addr().is_some(), "buffer has no valid phys addr");
+    }
+
+    for buf in bufs {
+        pool.put(buf);
+    }
+    assert_eq!(pool.available(), 8);
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! Integration test for the block-sized `DmaBuf` constructors
+
+use spdk_io::dma::PAGE_SIZE;
+use spdk_io::{DmaBuf, Error, Result, SpdkEnv};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_for_blocks_alignment() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_dma_blocks")
+        .no_pci(true)
+        .mem_size_mb(128)
+        .build()?;
+
+    let buf = DmaBuf::for_blocks(4096, 2)?;
+    assert_eq!(buf.len(), 8192);
+    assert_eq!(buf.as_ptr() as usize % 4096, 0);
+    assert!(buf.phys_addr().is_some());
+
+    let buf = DmaBuf::page_aligned(100)?;
+    assert_eq!(buf.len(), 100);
+    assert_eq!(buf.as_ptr() as usize % PAGE_SIZE, 0);
+
+    assert!(matches!(
+        DmaBuf::for_blocks(520, 1),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        DmaBuf::for_blocks(512, 0),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        DmaBuf::for_blocks(4096, usize::MAX),
+        Err(Error::InvalidArgument(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Integration test for the core iteration helpers on `SpdkEnv`
+
+use spdk_io::{Result, SpdkEnv};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_env_cores() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_env_cores")
+        .core_mask("0x1")
+        .no_pci(true)
+        .build()?;
+
+    let cores: Vec<u32> = SpdkEnv::cores().collect();
+    assert_eq!(cores.len(), SpdkEnv::core_count() as usize);
+    assert_eq!(cores, [0]);
+    assert_eq!(SpdkEnv::first_core(), 0);
+    assert_eq!(SpdkEnv::last_core(), 0);
+
+    // The initializing thread is DPDK's main lcore
+    assert_eq!(SpdkEnv::current_core(), SpdkEnv::first_core());
+
+    // A fresh iterator starts over; an exhausted one stays exhausted
+    let mut iter = SpdkEnv::cores();
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! Integration test for a failed SPDK environment initialization
+
+use spdk_io::{Error, SpdkEnv};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_failed_init_leaves_consistent_state() {
+    // Core 63 does not exist on the test hosts, so EAL rejects the mask
+    let build = || {
+        SpdkEnv::builder()
+            .name("test_init_failure")
+            .core_mask("0x8000000000000000")
+            .no_pci(true)
+            .no_huge(true)
+            .mem_size_mb(256)
+            .build()
+    };
+
+    assert!(matches!(build(), Err(Error::EnvInit(_))));
+    assert!(!SpdkEnv::is_initialized());
+    assert_eq!(SpdkEnv::reserved_mem_mb(), None);
+
+    // The failed attempt released its claim: a retry reaches SPDK again
+    // instead of reporting another initialization in progress
+    let retry = build();
+    assert!(!matches!(retry, Err(Error::AlreadyInitialized)));
+    assert!(retry.is_err());
+    assert!(!SpdkEnv::is_initialized());
+}
@@ -0,0 +1,31 @@
+//! Integration test for `SpdkEnvBuilder::iova_mode()`
+
+use std::ffi::c_int;
+
+use spdk_io::{Result, SpdkEnv};
+
+// rte_eal.h is not part of the generated bindings
+unsafe extern "C" {
+    fn rte_eal_iova_mode() -> c_int;
+}
+
+/// DPDK's `RTE_IOVA_VA`.
+const RTE_IOVA_VA: c_int = 2;
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_iova_mode_va() -> Result<()> {
+    // Physical addresses are unavailable without hugepages, so "pa" needs
+    // a hugepage-backed, privileged run; "va" works everywhere
+    let _env = SpdkEnv::builder()
+        .name("test_iova_mode")
+        .iova_mode("va")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    assert_eq!(unsafe { rte_eal_iova_mode() }, RTE_IOVA_VA);
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! Integration test for DPDK memory event callbacks
+//!
+//! Starts DPDK in dynamic memory mode (no `mem_size_mb`) and allocates a
+//! DMA buffer larger than the initial heap, which maps more hugepages.
+
+use std::sync::{Arc, Mutex};
+
+use spdk_io::env::{MemEvent, register_mem_event_cb};
+use spdk_io::{DmaBuf, Result, SpdkEnv};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_mem_event_on_growth() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_mem_event")
+        .no_pci(true)
+        .build()?;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let cb = register_mem_event_cb(move |event| recorded.lock().unwrap().push(event))?;
+
+    let buf = DmaBuf::alloc(256 * 1024 * 1024, 4096)?;
+    let start = buf.as_ptr() as usize;
+
+    let added: Vec<_> = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match *event {
+            MemEvent::Added { addr, len } => Some((addr, len)),
+            MemEvent::Removed { .. } => None,
+        })
+        .collect();
+    assert!(!added.is_empty(), "no memory was added");
+    assert!(added.iter().all(|&(_, len)| len > 0));
+    assert!(
+        added
+            .iter()
+            .any(|&(addr, len)| addr <= start && start < addr + len),
+        "buffer at {:#x} is outside the added memory {:x?}",
+        start,
+        added
+    );
+
+    // No events reach the closure after unregistering
+    drop(cb);
+    let seen = events.lock().unwrap().len();
+    drop(buf);
+    drop(DmaBuf::alloc(256 * 1024 * 1024, 4096)?);
+    assert_eq!(events.lock().unwrap().len(), seen);
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+//! Integration test for `SpdkEnv::reserved_mem_mb()`
+
+use spdk_io::{Result, SpdkEnv};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_reserved_mem_mb() -> Result<()> {
+    assert_eq!(SpdkEnv::reserved_mem_mb(), None);
+
+    let _env = SpdkEnv::builder()
+        .name("test_reserved_mem")
+        .no_pci(true)
+        .mem_size_mb(128)
+        .build()?;
+
+    let reserved = SpdkEnv::reserved_mem_mb().expect("DPDK reported no reserved memory");
+    eprintln!("reserved {} MiB", reserved);
+    assert!(reserved >= 128, "reserved only {} MiB", reserved);
+
+    Ok(())
+}
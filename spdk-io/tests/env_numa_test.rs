@@ -0,0 +1,35 @@
+//! Integration test for the NUMA topology queries in `spdk_io::env` and
+//! `SpdkEnv`
+
+use spdk_io::env::{core_to_socket, numa_node_count};
+use spdk_io::{Result, SpdkEnv};
+use spdk_io_sys::spdk_env_get_first_core;
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_numa_topology() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_env_numa")
+        .core_mask("0x1")
+        .no_pci(true)
+        .build()?;
+
+    let nodes = numa_node_count();
+    assert!(nodes >= 1);
+
+    let main_core = unsafe { spdk_env_get_first_core() };
+    let socket = core_to_socket(main_core);
+    // Socket ids may be sparse on multi-socket hosts, but are never "any"
+    // for a configured core
+    assert!(socket >= 0, "core {} maps to socket {}", main_core, socket);
+    if nodes == 1 {
+        assert_eq!(socket, 0);
+    }
+
+    // The checked wrappers agree once the environment is up; this thread
+    // is the main lcore
+    assert_eq!(SpdkEnv::socket_id(main_core)?, socket);
+    assert_eq!(SpdkEnv::current_socket()?, socket);
+
+    Ok(())
+}
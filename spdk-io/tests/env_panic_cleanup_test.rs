@@ -0,0 +1,31 @@
+//! Integration test for `env::install_cleanup_on_panic()`
+
+use spdk_io::{Result, SpdkEnv, env};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_cleanup_on_panic() -> Result<()> {
+    let env = SpdkEnv::builder()
+        .name("test_panic_cleanup")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    env::install_cleanup_on_panic();
+    // Installing twice does not chain the hook onto itself
+    env::install_cleanup_on_panic();
+    assert!(SpdkEnv::is_initialized());
+
+    let worker = std::thread::spawn(|| panic!("worker failed"));
+    assert!(worker.join().is_err());
+
+    // The hook finalized the environment on the worker's panic
+    assert!(!SpdkEnv::is_initialized());
+
+    // Dropping the guard afterwards must not finalize it a second time
+    drop(env);
+    assert!(!SpdkEnv::is_initialized());
+
+    Ok(())
+}
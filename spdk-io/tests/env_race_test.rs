@@ -0,0 +1,53 @@
+//! Concurrent SPDK environment initialization
+//!
+//! Several threads race to build the environment; exactly one may win.
+
+use std::sync::{Arc, Barrier};
+
+use spdk_io::{Error, SpdkEnv};
+
+const RACERS: usize = 8;
+
+#[test]
+fn test_env_build_race() {
+    let barrier = Arc::new(Barrier::new(RACERS));
+
+    let handles: Vec<_> = (0..RACERS)
+        .map(|_| {
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                SpdkEnv::builder()
+                    .name("test_env_race")
+                    .no_pci(true)
+                    .no_huge(true)
+                    .mem_size_mb(64)
+                    .build()
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    let winners = results.iter().filter(|r| r.is_ok()).count();
+    assert_eq!(winners, 1, "exactly one build() must succeed");
+    for result in &results {
+        if let Err(err) = result {
+            assert!(
+                matches!(err, Error::AlreadyInitialized),
+                "loser got {:?}",
+                err
+            );
+        }
+    }
+    assert!(SpdkEnv::is_initialized());
+
+    // A late caller is rejected too
+    assert!(matches!(
+        SpdkEnv::builder().no_huge(true).mem_size_mb(64).build(),
+        Err(Error::AlreadyInitialized)
+    ));
+
+    drop(results);
+    assert!(!SpdkEnv::is_initialized());
+}
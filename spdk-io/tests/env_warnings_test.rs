@@ -0,0 +1,29 @@
+//! Integration test for capturing DPDK init warnings
+
+use spdk_io::{Result, SpdkEnv};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_init_warnings_captured() -> Result<()> {
+    assert!(SpdkEnv::init_warnings().is_empty());
+
+    let _env = SpdkEnv::builder()
+        .name("test_init_warnings")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    // Whether DPDK warns depends on the host, so only the shape is checked
+    let warnings = SpdkEnv::init_warnings();
+    for warning in &warnings {
+        eprintln!("init warning: {}", warning);
+        assert!(!warning.is_empty());
+        assert!(!warning.contains('\n'));
+    }
+
+    // Repeated calls return the same snapshot
+    assert_eq!(SpdkEnv::init_warnings(), warnings);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+//! Integration test for creating and deleting a null bdev at runtime
+
+use spdk_io::{Bdev, DmaBuf, Result, SpdkApp, bdev, block_on};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_create_null_bdev() -> Result<()> {
+    SpdkApp::builder()
+        .name("test_null_bdev")
+        .no_pci(true)
+        .run(|| {
+            let null = bdev::create_null("NullRt0", 1024, 512).expect("Failed to create bdev");
+            assert_eq!(null.bdev().name(), "NullRt0");
+            assert_eq!(null.bdev().num_blocks(), 1024);
+            assert_eq!(null.bdev().block_size(), 512);
+            assert!(Bdev::get_by_name("NullRt0").is_some());
+
+            // Duplicate names are rejected
+            assert!(bdev::create_null("NullRt0", 1024, 512).is_err());
+
+            {
+                let desc = null.bdev().open(false).expect("Failed to open bdev");
+                let channel = desc.get_io_channel().expect("Failed to get I/O channel");
+
+                // Contents are unspecified, but the read completes
+                let mut buf = DmaBuf::alloc(4096, 512).expect("Failed to allocate DmaBuf");
+                block_on(desc.read(&channel, &mut buf, 0)).expect("Read failed");
+            }
+
+            block_on(null.delete()).expect("Failed to delete bdev");
+            assert!(Bdev::get_by_name("NullRt0").is_none());
+
+            // Dropping also deletes
+            let null = bdev::create_null("NullRt1", 64, 4096).expect("Failed to create bdev");
+            drop(null);
+
+            SpdkApp::stop();
+        })
+}
@@ -0,0 +1,52 @@
+//! Integration test for `NvmeController::format()`.
+//!
+//! Formatting destroys all data on the namespace, so the test only runs
+//! against a controller named explicitly with `SPDK_IO_TEST_NVME_PCIE`
+//! (e.g. `0000:00:04.0`, bound to vfio-pci or uio). An emulated controller
+//! such as QEMU's `-device nvme` supports Format NVM.
+
+use spdk_io::SpdkApp;
+use spdk_io::nvme::{NvmeController, SecureErase, TransportId};
+
+/// Environment variable with the PCIe address of a scratch controller.
+const PCIE_ADDR_VAR: &str = "SPDK_IO_TEST_NVME_PCIE";
+
+#[test]
+#[ignore] // Requires hugepages and a scratch NVMe device
+fn test_nvme_format_namespace() {
+    let Ok(addr) = std::env::var(PCIE_ADDR_VAR) else {
+        eprintln!("{} not set, skipping", PCIE_ADDR_VAR);
+        return;
+    };
+
+    SpdkApp::builder()
+        .name("nvme_format_test")
+        .run_async(move || async move {
+            let trid = TransportId::pcie(&addr).expect("Invalid PCIe address");
+            let mut ctrlr = NvmeController::connect(&trid, None).expect("Failed to connect");
+
+            let sector_size = ctrlr.namespace(1).expect("No namespace 1").sector_size();
+
+            // Format with the first LBA format, user data erased
+            ctrlr
+                .format(1, 0, SecureErase::UserData)
+                .await
+                .expect("Format failed");
+
+            // The namespace is still there after the controller reset
+            let ns = ctrlr.namespace(1).expect("No namespace 1 after format");
+            eprintln!(
+                "Formatted namespace 1: {} -> {} bytes/sector, {} sectors",
+                sector_size,
+                ns.sector_size(),
+                ns.num_sectors()
+            );
+            assert!(ns.num_sectors() > 0);
+
+            // Out-of-range LBA format indexes are rejected before submission
+            assert!(ctrlr.format(1, 64, SecureErase::None).await.is_err());
+
+            SpdkApp::stop();
+        })
+        .expect("SPDK app failed");
+}
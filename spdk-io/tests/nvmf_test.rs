@@ -534,3 +534,163 @@ fn test_nvmf_subprocess() -> Result<()> {
 
     Ok(())
 }
+
+/// Test that a TCP controller with a short keep-alive stays connected
+/// while its admin queue is polled.
+#[test]
+#[ignore] // Requires nvmf_tgt and hugepages
+fn test_nvmf_keep_alive() -> Result<()> {
+    use spdk_io::SpdkApp;
+    use spdk_io::nvme::{NvmeController, NvmeCtrlrOpts, NvmeEvent};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
+
+    const TEST_PORT: u16 = 4422;
+
+    nvmf_subprocess::NvmfTarget::cleanup_stale(TEST_PORT);
+    let (target, nqn) =
+        nvmf_subprocess::NvmfTarget::start(TEST_PORT).map_err(spdk_io::Error::InvalidArgument)?;
+
+    SpdkApp::builder()
+        .name("nvmf_keep_alive_test")
+        .run(move || {
+            let trid = TransportId::tcp("127.0.0.1", &TEST_PORT.to_string(), &nqn)
+                .expect("Failed to create TransportId");
+            let opts = NvmeCtrlrOpts {
+                keep_alive_timeout_ms: Some(1000),
+                ..Default::default()
+            };
+            let mut ctrlr = NvmeController::connect(&trid, Some(&opts)).expect("Failed to connect");
+
+            let lost = Arc::new(AtomicBool::new(false));
+            let lost_clone = lost.clone();
+            ctrlr.on_event(move |event| {
+                if event == NvmeEvent::ConnectionLost {
+                    lost_clone.store(true, Ordering::SeqCst);
+                }
+            });
+
+            // Poll for several keep-alive periods
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_secs(5) {
+                ctrlr.process_admin_completions();
+                std::thread::sleep(Duration::from_millis(50));
+            }
+
+            assert!(!ctrlr.is_failed(), "Controller failed despite keep-alive");
+            assert!(!lost.load(Ordering::SeqCst), "Unexpected connection loss");
+
+            drop(ctrlr);
+            SpdkApp::stop();
+        })?;
+
+    drop(target);
+    Ok(())
+}
+
+/// Test that an in-process target with discovery enabled lists its
+/// subsystem in the discovery log page.
+///
+/// The target is served from the main core while the initiator connects
+/// from the second core, as recommended in the `nvmf` module docs.
+#[test]
+#[ignore] // Requires hugepages and 2 cores
+fn test_nvmf_discovery() -> Result<()> {
+    use spdk_io::nvme::{DISCOVERY_NQN, NvmeController};
+    use spdk_io::nvmf::{NvmfSubsystemOpts, NvmfTarget, NvmfTransport};
+    use spdk_io::{Cores, SpdkApp, SpdkEvent, block_on};
+
+    const TEST_PORT: &str = "4423";
+    const NQN: &str = "nqn.2024-01.io.spdk:discovery-test";
+
+    let config = r#"{
+        "subsystems": [{
+            "subsystem": "bdev",
+            "config": [{
+                "method": "bdev_null_create",
+                "params": {
+                    "name": "Null0",
+                    "num_blocks": 1024,
+                    "block_size": 512
+                }
+            }]
+        }]
+    }"#;
+
+    SpdkApp::builder()
+        .name("nvmf_discovery_test")
+        .json_data(config)
+        .no_pci(true)
+        .reactor_mask("0x3")
+        .run(|| {
+            block_on(async {
+                let target = NvmfTarget::create("discovery_test").expect("Failed to create target");
+                let transport = NvmfTransport::tcp(None).expect("Failed to create transport");
+                target
+                    .add_transport(transport)
+                    .await
+                    .expect("Failed to add transport");
+                let poll_group = target
+                    .create_poll_group()
+                    .expect("Failed to create poll group");
+
+                // I/O subsystem backed by the null bdev
+                let trid = TransportId::tcp("127.0.0.1", TEST_PORT, NQN).unwrap();
+                target.listen(&trid).expect("Failed to listen");
+                let subsys = target
+                    .create_subsystem(
+                        NQN,
+                        NvmfSubsystemOpts {
+                            allow_any_host: true,
+                            ..Default::default()
+                        },
+                    )
+                    .expect("Failed to create subsystem");
+                subsys
+                    .add_namespace("Null0")
+                    .expect("Failed to add namespace");
+                subsys
+                    .add_listener(&trid)
+                    .await
+                    .expect("Failed to add listener");
+                subsys.start().await.expect("Failed to start subsystem");
+
+                // Discovery on the same port
+                let discovery_trid =
+                    TransportId::tcp("127.0.0.1", TEST_PORT, DISCOVERY_NQN).unwrap();
+                target
+                    .enable_discovery(&discovery_trid)
+                    .await
+                    .expect("Failed to enable discovery");
+
+                // Initiator on the other core; block_on keeps polling the
+                // target on this one
+                let other_core = Cores::iter()
+                    .find(|&c| c != Cores::current())
+                    .expect("Expected 2 cores available");
+                let entries = SpdkEvent::call_on_async(other_core, || {
+                    let trid = TransportId::tcp("127.0.0.1", TEST_PORT, DISCOVERY_NQN).unwrap();
+                    let mut ctrlr =
+                        NvmeController::connect(&trid, None).expect("Failed to connect");
+                    ctrlr.discovery_log().expect("Failed to read discovery log")
+                })
+                .expect("Failed to dispatch to other core")
+                .await
+                .expect("Initiator task failed");
+
+                eprintln!("Discovery log: {:#?}", entries);
+                let nvm: Vec<_> = entries.iter().filter(|e| e.is_nvm_subsystem()).collect();
+                assert_eq!(nvm.len(), 1, "Expected exactly one NVM subsystem");
+                assert_eq!(nvm[0].subnqn, NQN);
+                assert_eq!(nvm[0].traddr, "127.0.0.1");
+                assert_eq!(nvm[0].trsvcid, TEST_PORT);
+
+                subsys.stop().await.expect("Failed to stop subsystem");
+                drop(poll_group);
+                drop(target);
+            });
+
+            SpdkApp::stop();
+        })
+}
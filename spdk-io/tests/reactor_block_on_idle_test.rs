@@ -0,0 +1,75 @@
+//! Integration test for `Reactor::block_on()` waiting on a timer
+
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::time::{Duration, Instant};
+
+use spdk_io::{CompletionSender, Reactor, Result, SpdkEnv, SpdkThread, completion};
+use spdk_io_sys::*;
+
+const TIMER_US: u64 = 200_000;
+
+/// Timed poller resolving the completion on its first run.
+unsafe extern "C" fn timer_poller(arg: *mut c_void) -> i32 {
+    let sender = unsafe { &*(arg as *const RefCell<Option<CompletionSender<()>>>) };
+    if let Some(tx) = sender.borrow_mut().take() {
+        tx.success(());
+    }
+    spdk_thread_poller_rc_SPDK_POLLER_BUSY as i32
+}
+
+/// CPU time consumed by the calling OS thread.
+fn thread_cpu_time() -> Duration {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    let rc = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) };
+    assert_eq!(rc, 0);
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_reactor_block_on_timer_does_not_spin() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_reactor_idle")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+    let thread = SpdkThread::new("reactor_idle")?;
+    let reactor = Reactor::new(&thread);
+
+    let (tx, rx) = completion::<()>();
+    let sender = RefCell::new(Some(tx));
+    let mut poller = unsafe {
+        spdk_poller_register(
+            Some(timer_poller),
+            &sender as *const _ as *mut c_void,
+            TIMER_US,
+        )
+    };
+    assert!(!poller.is_null());
+
+    let start = Instant::now();
+    let cpu_start = thread_cpu_time();
+    reactor.block_on(rx)?;
+    let busy = thread_cpu_time() - cpu_start;
+    let elapsed = start.elapsed();
+
+    unsafe { spdk_poller_unregister(&mut poller) };
+    thread.poll();
+
+    eprintln!("block_on waited {:?}, busy {:?}", elapsed, busy);
+    assert!(elapsed >= Duration::from_micros(TIMER_US / 2));
+    // A spinning loop would be busy for (nearly) the whole wait
+    assert!(
+        busy < elapsed / 2,
+        "busy {:?} of {:?} waiting on a timer",
+        busy,
+        elapsed
+    );
+
+    Ok(())
+}
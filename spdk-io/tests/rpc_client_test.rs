@@ -0,0 +1,48 @@
+//! Integration test for the JSON-RPC client
+//!
+//! Needs an SPDK application already running and serving RPC, e.g.
+//! `build/bin/spdk_tgt`. The socket defaults to `/var/tmp/spdk.sock` and can
+//! be overridden with `SPDK_IO_TEST_RPC_SOCKET`.
+
+use serde_json::{Value, json};
+use spdk_io::Error;
+use spdk_io::rpc::{Client, DEFAULT_SOCKET};
+
+/// Environment variable with the socket of the running application.
+const SOCKET_VAR: &str = "SPDK_IO_TEST_RPC_SOCKET";
+
+#[test]
+#[ignore] // Requires a running SPDK application
+fn test_rpc_client() {
+    let socket = std::env::var(SOCKET_VAR).unwrap_or_else(|_| DEFAULT_SOCKET.to_string());
+    let mut client = Client::connect(&socket).expect("Failed to connect to SPDK RPC socket");
+
+    let version = client
+        .call("spdk_get_version", Value::Null)
+        .expect("spdk_get_version failed");
+    assert!(version["version"].as_str().unwrap().starts_with("SPDK v"));
+
+    // Create, list and delete a null bdev
+    let name = client
+        .call(
+            "bdev_null_create",
+            json!({ "name": "RpcNull0", "num_blocks": 1024, "block_size": 512 }),
+        )
+        .expect("bdev_null_create failed");
+    assert_eq!(name, "RpcNull0");
+
+    let bdevs = client
+        .call("bdev_get_bdevs", json!({ "name": "RpcNull0" }))
+        .expect("bdev_get_bdevs failed");
+    assert_eq!(bdevs[0]["num_blocks"], 1024);
+
+    client
+        .call("bdev_null_delete", json!({ "name": "RpcNull0" }))
+        .expect("bdev_null_delete failed");
+
+    // Unknown methods come back as JSON-RPC errors
+    assert!(matches!(
+        client.call("spdk_io_no_such_method", Value::Null),
+        Err(Error::Rpc { code: -32601, .. })
+    ));
+}
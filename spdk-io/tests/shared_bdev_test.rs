@@ -0,0 +1,58 @@
+//! Integration test for sharing a bdev descriptor across reactor threads
+
+use spdk_io::{Bdev, Cores, DmaBuf, Result, SpdkApp, SpdkEvent, block_on};
+
+#[test]
+#[ignore] // Requires hugepages and 2 cores
+fn test_shared_bdev_desc_across_reactors() -> Result<()> {
+    let config = r#"{
+        "subsystems": [{
+            "subsystem": "bdev",
+            "config": [{
+                "method": "bdev_null_create",
+                "params": {
+                    "name": "Null0",
+                    "num_blocks": 1024,
+                    "block_size": 512
+                }
+            }]
+        }]
+    }"#;
+
+    SpdkApp::builder()
+        .name("test_shared_bdev")
+        .json_data(config)
+        .no_pci(true)
+        .reactor_mask("0x3")
+        .run(|| {
+            let bdev = Bdev::get_by_name("Null0").expect("Bdev 'Null0' not found");
+            let block_size = bdev.block_size() as usize;
+            let desc = bdev.open_shared(false).expect("Failed to open bdev");
+
+            let main_core = Cores::current();
+            let other_core = Cores::iter()
+                .find(|&c| c != main_core)
+                .expect("Expected 2 cores available");
+
+            // Other reactor: read through its own channel
+            let desc_clone = desc.clone();
+            let other = SpdkEvent::call_on_async(other_core, move || {
+                let channel = desc_clone.get_io_channel().expect("Failed to get channel");
+                let mut buf = DmaBuf::alloc(block_size, block_size).expect("DmaBuf alloc");
+                block_on(desc_clone.read(&channel, &mut buf, 0)).is_ok()
+            })
+            .expect("Failed to dispatch to other core");
+
+            // This reactor: read through its own channel
+            let channel = desc.get_io_channel().expect("Failed to get channel");
+            let mut buf = DmaBuf::alloc(block_size, block_size).expect("DmaBuf alloc");
+            block_on(desc.read(&channel, &mut buf, 0)).expect("Read failed");
+
+            let other_ok = block_on(other).expect("Other core did not complete");
+            assert!(other_ok, "Read on other core failed");
+
+            drop(channel);
+            drop(desc);
+            SpdkApp::stop();
+        })
+}
@@ -0,0 +1,50 @@
+//! Integration test for attaching a second SPDK thread to one OS thread
+
+use spdk_io::{Error, Result, SpdkEnv, SpdkThread};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_attach_twice() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_attach_twice")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::current("first")?;
+    let count = SpdkThread::count();
+
+    // The attached thread is neither replaced nor leaked
+    assert!(matches!(
+        SpdkThread::current("second"),
+        Err(Error::AlreadyAttached)
+    ));
+    assert!(matches!(
+        SpdkThread::attach("second"),
+        Err(Error::AlreadyAttached)
+    ));
+    assert_eq!(SpdkThread::count(), count);
+    assert_eq!(
+        SpdkThread::get_current().map(|t| t.as_ptr()),
+        Some(thread.as_ptr())
+    );
+
+    // Reusing it on purpose hands back the same thread, and dropping that
+    // leaves it attached
+    let reused = SpdkThread::attach_or_current("second")?;
+    assert_eq!(reused.id(), thread.id());
+    drop(reused);
+    assert_eq!(SpdkThread::count(), count);
+    assert_eq!(
+        SpdkThread::get_current().map(|t| t.as_ptr()),
+        Some(thread.as_ptr())
+    );
+
+    // Once it is gone, a new thread can be attached
+    drop(thread);
+    let again = SpdkThread::attach_or_current("again")?;
+    assert_eq!(again.name(), "again");
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+//! Integration test for `SpdkThread::block_on()`
+
+use spdk_io::{Result, SpdkEnv, SpdkThread, completion};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_block_on_self_sent_message() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_thread_block_on")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("block_on")?;
+
+    // Only completed once block_on polls the thread and the message runs
+    let (tx, rx) = completion::<u32>();
+    thread.send_msg(move || tx.success(42))?;
+
+    assert_eq!(thread.block_on(rx)?, 42);
+
+    // Ready futures return without polling the thread
+    assert_eq!(thread.block_on(async { 7 }), 7);
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+//! Integration test for `SpdkThread::cpumask()` / `set_cpumask()`
+
+use spdk_io::{CpuSet, Error, Result, SpdkEnv, SpdkThread};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_thread_cpumask() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_thread_cpumask")
+        .core_mask_set(CpuSet::from_cores([0]))
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("cpumask")?;
+
+    // Created without a mask, the thread may run anywhere
+    let mask = thread.cpumask();
+    eprintln!("thread cpumask: {}", mask);
+    assert!(mask.contains(0));
+    assert_eq!(
+        SpdkThread::get_current().unwrap().cpumask(),
+        mask,
+        "current thread view disagrees"
+    );
+
+    // Only the event framework can move threads between cores
+    let err = thread.set_cpumask(&CpuSet::from_cores([0])).unwrap_err();
+    assert!(matches!(err, Error::Os(libc::ENOTSUP)), "{:?}", err);
+    assert_eq!(thread.cpumask(), mask);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! Integration test for cleanup after a failed `SpdkThread` creation
+//!
+//! SPDK has no deterministic way to make `spdk_thread_create` itself fail,
+//! so this drives the error paths around it (a name that cannot become a C
+//! string) and checks that no thread or library state is left behind.
+//! Failing after the thread exists needs a test-only hook, so that case is a
+//! unit test in `src/thread.rs`.
+
+use spdk_io::thread::SMALL_MSG_MEMPOOL_SIZE;
+use spdk_io::{Error, Result, SpdkEnv, SpdkThread};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_failed_create_leaves_no_thread() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_thread_cleanup")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    // Before any thread exists, a failed call must not leave the thread
+    // library initialized with no threads
+    let before = SpdkThread::count();
+    let err = SpdkThread::current_with_mempool_size("bad\0name", SMALL_MSG_MEMPOOL_SIZE);
+    assert!(matches!(err, Err(Error::NulError(_))));
+    assert_eq!(SpdkThread::count(), before);
+
+    // The library still accepts a fresh init (with the small mempool)
+    let thread = SpdkThread::current_with_mempool_size("worker", SMALL_MSG_MEMPOOL_SIZE)?;
+    let with_thread = SpdkThread::count();
+    assert_eq!(with_thread, before + 1);
+
+    // With a thread alive, failures leave the count and that thread alone
+    assert!(SpdkThread::current("bad\0name").is_err());
+    assert!(SpdkThread::attach("bad\0name").is_err());
+    assert_eq!(SpdkThread::count(), with_thread);
+    assert_eq!(
+        SpdkThread::get_current().map(|t| t.as_ptr()),
+        Some(thread.as_ptr())
+    );
+
+    drop(thread);
+    assert_eq!(SpdkThread::count(), before);
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! Integration test for synchronous execution on SPDK threads
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
+
+use spdk_io::{Error, Result, SpdkEnv, SpdkThread};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_exec_msg_inline() -> Result<()> {
+    let _env = SpdkEnv::builder().name("test_exec_msg").build()?;
+
+    let thread = SpdkThread::new("exec-main")?;
+
+    // Targeting the current thread runs inline, so the result is visible
+    // immediately without polling
+    let mut value = 0;
+    thread.exec_msg(|| value = 42)?;
+    assert_eq!(value, 42);
+
+    // Same through a handle
+    let handle = thread.handle();
+    handle.exec_msg(|| value += 1)?;
+    assert_eq!(value, 43);
+
+    Ok(())
+}
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_exec_msg_cross_thread() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_exec_msg_cross")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("exec-caller")?;
+
+    // Target thread polled by its own OS thread until told to stop
+    let stop = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    let target = {
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            let target = SpdkThread::new("exec-target").unwrap();
+            tx.send(target.handle()).unwrap();
+            while !stop.load(Ordering::Acquire) {
+                target.poll();
+            }
+        })
+    };
+    let handle = rx.recv().unwrap();
+
+    // Outside a poll the caller blocks until the closure has run
+    let mut value = 0;
+    handle.exec_msg(|| value = 7)?;
+    assert_eq!(value, 7);
+
+    // From inside a message, waiting would poll the caller reentrantly
+    let rejected = Arc::new(AtomicBool::new(false));
+    {
+        let rejected = rejected.clone();
+        thread.send_msg(move || {
+            let result = handle.exec_msg(|| unreachable!("must not be sent"));
+            rejected.store(
+                matches!(result, Err(Error::ReentrantPoll)),
+                Ordering::Release,
+            );
+        })?;
+    }
+    thread.poll();
+    assert!(rejected.load(Ordering::Acquire));
+
+    stop.store(true, Ordering::Release);
+    target.join().unwrap();
+
+    Ok(())
+}
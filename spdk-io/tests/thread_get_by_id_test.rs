@@ -0,0 +1,66 @@
+//! Integration test for `thread::get_by_id()`
+//!
+//! A worker OS thread resolves the main SPDK thread from its id alone and
+//! messages it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use spdk_io::thread::get_by_id;
+use spdk_io::{Error, Result, SpdkEnv, SpdkThread};
+
+static RECEIVED: AtomicBool = AtomicBool::new(false);
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_get_by_id() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_get_by_id")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("by_id")?;
+    let id = thread.id();
+
+    std::thread::spawn(move || {
+        let messenger = get_by_id(id).expect("thread not found by id");
+        assert_eq!(messenger.id(), id);
+        messenger
+            .send_msg(|| RECEIVED.store(true, Ordering::SeqCst))
+            .expect("send_msg failed");
+    })
+    .join()
+    .expect("worker panicked");
+
+    while !RECEIVED.load(Ordering::SeqCst) {
+        thread.poll();
+    }
+
+    // Ids that were never assigned resolve to nothing
+    assert!(get_by_id(0).is_none());
+    assert!(get_by_id(u64::MAX - 1).is_none());
+
+    // Neither does the id once the thread is gone (a thread on another OS
+    // thread keeps the thread library up)
+    let (attached_tx, attached_rx) = std::sync::mpsc::channel();
+    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+    let other = std::thread::spawn(move || {
+        let _other = SpdkThread::attach("other").expect("attach failed");
+        attached_tx.send(()).unwrap();
+        let _ = done_rx.recv();
+    });
+    attached_rx.recv().expect("other thread not attached");
+    let messenger = get_by_id(id).expect("thread not found by id");
+    drop(thread);
+    assert!(get_by_id(id).is_none());
+    // A messenger that outlived its thread fails instead of touching it
+    assert!(matches!(
+        messenger.send_msg(|| unreachable!()),
+        Err(Error::ThreadExited)
+    ));
+    drop(done_tx);
+    other.join().expect("other thread panicked");
+
+    Ok(())
+}
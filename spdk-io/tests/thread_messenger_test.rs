@@ -0,0 +1,63 @@
+//! Integration test for sending messages through a `Messenger`
+//!
+//! A worker OS thread receives the messenger of the main SPDK thread and
+//! messages back to it.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use spdk_io::{Messenger, Result, SpdkEnv, SpdkThread};
+
+static RECEIVED: AtomicU32 = AtomicU32::new(0);
+static RECEIVED_ON: Mutex<Vec<std::thread::ThreadId>> = Mutex::new(Vec::new());
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_messenger_fan_in() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_messenger")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("origin")?;
+    let messenger: Messenger = thread.messenger();
+    assert_eq!(messenger.id(), thread.id());
+
+    const WORKERS: u32 = 4;
+    const MSGS: u32 = 16;
+
+    let workers: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            std::thread::spawn(move || {
+                for _ in 0..MSGS {
+                    messenger
+                        .send_msg(|| {
+                            RECEIVED_ON
+                                .lock()
+                                .unwrap()
+                                .push(std::thread::current().id());
+                            RECEIVED.fetch_add(1, Ordering::SeqCst);
+                        })
+                        .expect("send_msg failed");
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().expect("worker panicked");
+    }
+
+    // Nothing runs until the origin thread is polled
+    while RECEIVED.load(Ordering::SeqCst) < WORKERS * MSGS {
+        thread.poll();
+    }
+
+    let origin = std::thread::current().id();
+    let received_on = RECEIVED_ON.lock().unwrap();
+    assert_eq!(received_on.len(), (WORKERS * MSGS) as usize);
+    assert!(received_on.iter().all(|&id| id == origin));
+
+    Ok(())
+}
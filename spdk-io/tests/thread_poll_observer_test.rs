@@ -0,0 +1,39 @@
+//! Integration test for `SpdkThread::set_poll_observer()`
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use spdk_io::{Result, SpdkEnv, SpdkThread};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_poll_observer() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_poll_observer")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("observed")?;
+
+    let calls: Rc<RefCell<Vec<(i32, Duration)>>> = Rc::default();
+    let recorded = calls.clone();
+    thread.set_poll_observer(move |work, elapsed| recorded.borrow_mut().push((work, elapsed)));
+
+    const POLLS: usize = 5;
+    for _ in 0..POLLS {
+        thread.poll();
+    }
+
+    assert_eq!(calls.borrow().len(), POLLS);
+    assert!(calls.borrow().iter().all(|&(work, _)| work >= 0));
+
+    // Cleared observers are not called
+    thread.clear_poll_observer();
+    thread.poll();
+    assert_eq!(calls.borrow().len(), POLLS);
+
+    Ok(())
+}
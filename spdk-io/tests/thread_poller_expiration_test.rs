@@ -0,0 +1,52 @@
+//! Integration test for `SpdkThread::next_poller_expiration_us()`
+
+use std::ffi::c_void;
+
+use spdk_io::{Result, SpdkEnv, SpdkThread};
+use spdk_io_sys::*;
+
+const PERIOD_US: u64 = 5000;
+
+unsafe extern "C" fn idle_poller(_arg: *mut c_void) -> i32 {
+    spdk_thread_poller_rc_SPDK_POLLER_IDLE as i32
+}
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_next_poller_expiration() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_poller_expiration")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("expiration")?;
+    assert_eq!(thread.next_poller_expiration_us(), None);
+
+    let mut poller =
+        unsafe { spdk_poller_register(Some(idle_poller), std::ptr::null_mut(), PERIOD_US) };
+    assert!(!poller.is_null());
+    thread.poll();
+
+    let hint = thread
+        .next_poller_expiration_us()
+        .expect("timed poller not reported");
+    eprintln!("next poller due in {} us", hint);
+    assert!(hint <= PERIOD_US, "hint {} exceeds the period", hint);
+    assert!(
+        hint >= PERIOD_US / 2,
+        "hint {} is far below the period",
+        hint
+    );
+
+    // The current thread view agrees
+    let current = SpdkThread::get_current().expect("no current thread");
+    assert!(current.next_poller_expiration_us().unwrap() <= hint);
+
+    unsafe { spdk_poller_unregister(&mut poller) };
+    thread.poll();
+    assert_eq!(thread.next_poller_expiration_us(), None);
+
+    Ok(())
+}
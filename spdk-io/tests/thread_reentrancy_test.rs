@@ -0,0 +1,67 @@
+//! Integration test for the reentrant poll guard
+//!
+//! Registers a raw SPDK poller that polls its own thread from inside the
+//! poller callback, which the guard must reject.
+
+use std::ffi::c_void;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use spdk_io::{Error, Result, SpdkEnv, SpdkThread};
+use spdk_io_sys::*;
+
+static CHECKED_REJECTED: AtomicU32 = AtomicU32::new(0);
+static POLL_PANICKED: AtomicU32 = AtomicU32::new(0);
+
+unsafe extern "C" fn reentrant_poller(arg: *mut c_void) -> i32 {
+    let thread = unsafe { &*(arg as *const SpdkThread) };
+
+    if matches!(thread.poll_checked(), Err(Error::ReentrantPoll)) {
+        CHECKED_REJECTED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    // Unwinding must not cross the C frame, so catch it here
+    if catch_unwind(AssertUnwindSafe(|| thread.poll())).is_err() {
+        POLL_PANICKED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    spdk_thread_poller_rc_SPDK_POLLER_BUSY as i32
+}
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_reentrant_poll_guard() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_reentrant_poll")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("reentrant")?;
+
+    let mut poller = unsafe {
+        spdk_poller_register(
+            Some(reentrant_poller),
+            &thread as *const SpdkThread as *mut c_void,
+            0,
+        )
+    };
+    assert!(!poller.is_null());
+
+    thread.poll();
+
+    assert_eq!(CHECKED_REJECTED.load(Ordering::SeqCst), 1);
+    if cfg!(debug_assertions) {
+        assert_eq!(POLL_PANICKED.load(Ordering::SeqCst), 1);
+    } else {
+        assert_eq!(POLL_PANICKED.load(Ordering::SeqCst), 0);
+    }
+
+    unsafe { spdk_poller_unregister(&mut poller) };
+
+    // The guard is released after the outer poll returns
+    assert!(thread.poll_checked().is_ok());
+
+    Ok(())
+}
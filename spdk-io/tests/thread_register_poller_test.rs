@@ -0,0 +1,109 @@
+//! Integration tests for `SpdkThread::register_poller()`
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use spdk_io::{Result, SpdkEnv, SpdkThread};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_register_timed_poller() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_register_poller")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("register_poller")?;
+    let runs = Rc::new(Cell::new(0u32));
+
+    let counter = runs.clone();
+    let id = thread.register_timed_poller(
+        move || {
+            counter.set(counter.get() + 1);
+            1
+        },
+        1000,
+    )?;
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while runs.get() < 3 {
+        assert!(Instant::now() < deadline, "timed poller never ran");
+        thread.poll();
+    }
+
+    // A paused poller is skipped
+    assert!(thread.pause_poller(id));
+    thread.poll();
+    let paused_at = runs.get();
+    let until = Instant::now() + Duration::from_millis(10);
+    while Instant::now() < until {
+        thread.poll();
+    }
+    assert_eq!(runs.get(), paused_at);
+
+    assert!(thread.resume_poller(id));
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while runs.get() == paused_at {
+        assert!(Instant::now() < deadline, "resumed poller never ran");
+        thread.poll();
+    }
+
+    // Unregistering early frees the closure; the id is then stale
+    assert!(thread.unregister_poller(id));
+    thread.poll();
+    assert_eq!(Rc::strong_count(&runs), 1);
+    assert!(!thread.unregister_poller(id));
+    assert!(!thread.pause_poller(id));
+
+    Ok(())
+}
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_pollers_unregistered_on_thread_drop() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_poller_registry")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let runs = Rc::new(Cell::new(0u32));
+    let dropped = Rc::new(Cell::new(0u32));
+
+    /// Counts its drop, so the test sees when SPDK no longer holds the closure
+    struct DropCounter(Rc<Cell<u32>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    {
+        let thread = SpdkThread::new("poller_registry")?;
+        for _ in 0..3 {
+            let counter = runs.clone();
+            let guard = DropCounter(dropped.clone());
+            thread.register_poller(move || {
+                let _ = &guard;
+                counter.set(counter.get() + 1);
+                0
+            })?;
+        }
+        assert_eq!(thread.poller_count(), 3);
+
+        thread.poll();
+        assert_eq!(runs.get(), 3);
+        assert_eq!(dropped.get(), 0);
+    }
+
+    // Closing the thread unregistered all three and dropped their closures
+    assert_eq!(dropped.get(), 3);
+    assert_eq!(Rc::strong_count(&runs), 1);
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+//! Integration test for `SpdkThread::send_msg()`
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use spdk_io::{Result, SpdkEnv, SpdkThread};
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_send_msg_to_current_thread() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_send_msg")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let thread = SpdkThread::new("send_msg")?;
+    let ran = Arc::new(AtomicU32::new(0));
+
+    let counter = ran.clone();
+    thread.send_msg(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+    })?;
+
+    // Queued, not run inline
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+
+    assert!(thread.poll() > 0);
+    assert_eq!(ran.load(Ordering::SeqCst), 1);
+
+    // The closure (and its clone of the counter) was dropped after running
+    assert_eq!(Arc::strong_count(&ran), 1);
+
+    Ok(())
+}
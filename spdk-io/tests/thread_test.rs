@@ -5,7 +5,7 @@
 //!
 //! Uses the simple spdk_thread_lib_init which should work with default SPDK setup.
 
-use spdk_io::{LogLevel, Result, SpdkEnv, SpdkThread, block_on};
+use spdk_io::{Error, LogLevel, Result, SpdkEnv, SpdkThread, block_on};
 use std::sync::atomic::{AtomicU32, Ordering};
 
 // Test thread with hugepages (standard setup)
@@ -140,9 +140,11 @@ fn test_thread() -> Result<()> {
     // === Test ThreadHandle::call() ===
     eprintln!("Testing ThreadHandle::call()...");
 
-    // Create a worker thread
-    let worker_thread = SpdkThread::new("call-worker")?;
-    let _worker_handle = worker_thread.handle();
+    // A second SPDK thread can't be attached to this OS thread
+    assert!(matches!(
+        SpdkThread::new("call-worker"),
+        Err(Error::AlreadyAttached)
+    ));
 
     // Get main thread handle
     let main_handle = main_thread.handle();
@@ -174,7 +176,6 @@ fn test_thread() -> Result<()> {
     assert_eq!(result, 123);
     eprintln!("ThreadHandle::call() test passed with result: {}", result);
 
-    drop(worker_thread);
     drop(main_thread);
 
     Ok(())
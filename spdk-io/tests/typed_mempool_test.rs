@@ -0,0 +1,60 @@
+//! TypedMempool test
+//!
+//! Takes typed elements from a pool, mutates them and checks that dropping
+//! the guards returns the slots.
+
+use spdk_io::{Result, SpdkEnv, TypedMempool};
+
+#[derive(Debug, Default)]
+struct Request {
+    lba: u64,
+    blocks: u32,
+    done: bool,
+}
+
+#[test]
+#[ignore] // Requires hugepages
+fn test_typed_mempool() -> Result<()> {
+    let _env = SpdkEnv::builder()
+        .name("test_typed_mempool")
+        .no_pci(true)
+        .no_huge(true)
+        .mem_size_mb(256)
+        .build()?;
+
+    let pool = TypedMempool::<Request>::new("typed_pool", 8)?;
+    assert_eq!(pool.available(), 8);
+
+    {
+        let mut req = pool.get().expect("pool exhausted");
+        assert_eq!(req.lba, 0);
+        assert!(!req.done);
+        req.lba = 42;
+        req.blocks = 8;
+        req.done = true;
+        assert_eq!(req.lba, 42);
+        assert_eq!(pool.available(), 7);
+
+        let other = pool
+            .get_init(|| Request {
+                lba: 7,
+                blocks: 1,
+                done: false,
+            })
+            .expect("pool exhausted");
+        assert_eq!(other.lba, 7);
+        assert_eq!(pool.available(), 6);
+    }
+    assert_eq!(pool.available(), 8);
+
+    // Exhaust the pool
+    let held: Vec<_> = (0..8)
+        .map(|_| pool.get().expect("pool exhausted"))
+        .collect();
+    assert_eq!(pool.available(), 0);
+    assert!(pool.get().is_none());
+    drop(held);
+    assert_eq!(pool.available(), 8);
+
+    Ok(())
+}